@@ -71,12 +71,24 @@ pub enum ttfp_glyph_class {
 #[repr(C)]
 pub enum ttfp_raster_image_format {
     PNG = 0,
+    JPEG,
+    TIFF,
+    BITMAP_MONO,
+    BITMAP_MONO_PACKED,
+    BITMAP_GRAY_2,
+    BITMAP_GRAY_2_PACKED,
+    BITMAP_GRAY_4,
+    BITMAP_GRAY_4_PACKED,
+    BITMAP_GRAY_8,
+    /// `data` contains 4-byte big-endian component records:
+    /// a `u16` glyph ID followed by `i8` x and y offsets in pixels.
+    BITMAP_COMPOSITE,
 }
 
 /// @brief A glyph image.
 ///
-/// An image offset and size isn't defined in all tables, so `x`, `y`, `width` and `height`
-/// can be set to 0.
+/// An image offset, size and advances aren't defined in all tables,
+/// so `x`, `y`, `width`, `height` and advances can be set to 0.
 #[repr(C)]
 pub struct ttfp_glyph_raster_image {
     /// Horizontal offset.
@@ -101,6 +113,21 @@ pub struct ttfp_glyph_raster_image {
     /// An image format.
     pub format: ttfp_raster_image_format,
 
+    /// Indicates that glyph's outline should be drawn under the image.
+    pub draw_outlines: bool,
+
+    /// Horizontal advance.
+    pub advance: u16,
+
+    /// A horizontal distance from the vertical center line to the left edge of the image.
+    pub ver_bearing_x: i16,
+
+    /// A vertical distance from the vertical origin to the top edge of the image.
+    pub ver_bearing_y: i16,
+
+    /// Vertical advance.
+    pub ver_advance: u16,
+
     /// A raw image data as is. It's up to the caller to decode PNG, JPEG, etc.
     pub data: *const c_char,
 
@@ -517,7 +544,11 @@ pub extern "C" fn ttfp_get_glyph_var_index(
         let get = || {
             let c = char::try_from(codepoint).ok()?;
             let v = char::try_from(variation).ok()?;
-            font_from_ptr(font).glyph_variation_index(c, v).map(|gid| gid.0)
+            let font = font_from_ptr(font);
+            match font.glyph_variation_index(c, v)? {
+                ttf_parser::GlyphVariationResult::Found(gid) => Some(gid.0),
+                ttf_parser::GlyphVariationResult::UseDefault => font.glyph_index(c).map(|gid| gid.0),
+            }
         };
 
         get().unwrap_or(0)
@@ -722,7 +753,22 @@ pub extern "C" fn ttfp_get_glyph_raster_image(
                     pixels_per_em: image.pixels_per_em,
                     format: match image.format {
                         ttf_parser::RasterImageFormat::PNG => ttfp_raster_image_format::PNG,
+                        ttf_parser::RasterImageFormat::JPEG => ttfp_raster_image_format::JPEG,
+                        ttf_parser::RasterImageFormat::TIFF => ttfp_raster_image_format::TIFF,
+                        ttf_parser::RasterImageFormat::BitmapMono => ttfp_raster_image_format::BITMAP_MONO,
+                        ttf_parser::RasterImageFormat::BitmapMonoPacked => ttfp_raster_image_format::BITMAP_MONO_PACKED,
+                        ttf_parser::RasterImageFormat::BitmapGray2 => ttfp_raster_image_format::BITMAP_GRAY_2,
+                        ttf_parser::RasterImageFormat::BitmapGray2Packed => ttfp_raster_image_format::BITMAP_GRAY_2_PACKED,
+                        ttf_parser::RasterImageFormat::BitmapGray4 => ttfp_raster_image_format::BITMAP_GRAY_4,
+                        ttf_parser::RasterImageFormat::BitmapGray4Packed => ttfp_raster_image_format::BITMAP_GRAY_4_PACKED,
+                        ttf_parser::RasterImageFormat::BitmapGray8 => ttfp_raster_image_format::BITMAP_GRAY_8,
+                        ttf_parser::RasterImageFormat::BitmapComposite => ttfp_raster_image_format::BITMAP_COMPOSITE,
                     },
+                    draw_outlines: image.draw_outlines,
+                    advance: image.advance.unwrap_or(0),
+                    ver_bearing_x: image.vertical_metrics.map(|m| m.bearing_x).unwrap_or(0),
+                    ver_bearing_y: image.vertical_metrics.map(|m| m.bearing_y).unwrap_or(0),
+                    ver_advance: image.vertical_metrics.map(|m| m.advance).unwrap_or(0),
                     data: image.data.as_ptr() as _,
                     len: image.data.len() as u32,
                 };
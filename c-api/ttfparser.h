@@ -25,6 +25,11 @@ typedef enum {
     TTFP_TABLE_NAME_COLOR_BITMAP_LOCATION,
     TTFP_TABLE_NAME_COMPACT_FONT_FORMAT,
     TTFP_TABLE_NAME_COMPACT_FONT_FORMAT2,
+    TTFP_TABLE_NAME_CONTROL_VALUE,
+    TTFP_TABLE_NAME_CONTROL_VALUE_PROGRAM,
+    TTFP_TABLE_NAME_EMBEDDED_BITMAP_DATA,
+    TTFP_TABLE_NAME_EMBEDDED_BITMAP_LOCATION,
+    TTFP_TABLE_NAME_FONT_PROGRAM,
     TTFP_TABLE_NAME_FONT_VARIATIONS,
     TTFP_TABLE_NAME_GLYPH_DATA,
     TTFP_TABLE_NAME_GLYPH_DEFINITION,
@@ -64,6 +69,20 @@ typedef enum {
  */
 typedef enum {
     TTFP_RASTER_IMAGE_FORMAT_PNG = 0,
+    TTFP_RASTER_IMAGE_FORMAT_JPEG,
+    TTFP_RASTER_IMAGE_FORMAT_TIFF,
+    TTFP_RASTER_IMAGE_FORMAT_BITMAP_MONO,
+    TTFP_RASTER_IMAGE_FORMAT_BITMAP_MONO_PACKED,
+    TTFP_RASTER_IMAGE_FORMAT_BITMAP_GRAY_2,
+    TTFP_RASTER_IMAGE_FORMAT_BITMAP_GRAY_2_PACKED,
+    TTFP_RASTER_IMAGE_FORMAT_BITMAP_GRAY_4,
+    TTFP_RASTER_IMAGE_FORMAT_BITMAP_GRAY_4_PACKED,
+    TTFP_RASTER_IMAGE_FORMAT_BITMAP_GRAY_8,
+    /**
+     * `data` contains 4-byte big-endian component records:
+     * a `uint16_t` glyph ID followed by `int8_t` x and y offsets in pixels.
+     */
+    TTFP_RASTER_IMAGE_FORMAT_BITMAP_COMPOSITE,
 } ttfp_raster_image_format;
 
 /**
@@ -128,8 +147,8 @@ typedef struct {
 /**
  * @brief A glyph image.
  *
- * An image offset and size isn't defined in all tables, so `x`, `y`, `width` and `height`
- * can be set to 0.
+ * An image offset, size and advances aren't defined in all tables,
+ * so `x`, `y`, `width`, `height` and advances can be set to 0.
  */
 typedef struct {
     /**
@@ -160,6 +179,26 @@ typedef struct {
      * An image format.
      */
     ttfp_raster_image_format format;
+    /**
+     * Indicates that glyph's outline should be drawn under the image.
+     */
+    bool draw_outlines;
+    /**
+     * Horizontal advance.
+     */
+    uint16_t advance;
+    /**
+     * A horizontal distance from the vertical center line to the left edge of the image.
+     */
+    int16_t ver_bearing_x;
+    /**
+     * A vertical distance from the vertical origin to the top edge of the image.
+     */
+    int16_t ver_bearing_y;
+    /**
+     * Vertical advance.
+     */
+    uint16_t ver_advance;
     /**
      * A raw image data as is. It's up to the caller to decode PNG, JPEG, etc.
      */
@@ -18,11 +18,17 @@ fn main() {
     };
 
     println!("Family name: {:?}", font.family_name());
+    println!("Subfamily name: {:?}", font.subfamily_name());
+    println!("Full name: {:?}", font.full_name());
     println!("PostScript name: {:?}", font.post_script_name());
     println!("Units per EM: {:?}", font.units_per_em());
     println!("Ascender: {}", font.ascender());
     println!("Descender: {}", font.descender());
     println!("Line gap: {}", font.line_gap());
+    println!("Line spacing (browser): {:?}",
+             font.line_spacing(ttf_parser::LineSpacingConvention::Browser));
+    println!("Line spacing (DirectWrite): {:?}",
+             font.line_spacing(ttf_parser::LineSpacingConvention::DirectWrite));
     println!("Number of glyphs: {}", font.number_of_glyphs());
     println!("Underline: {:?}", font.underline_metrics());
     println!("X height: {:?}", font.x_height());
@@ -36,6 +42,8 @@ fn main() {
     println!("Subscript: {:?}", font.subscript_metrics());
     println!("Superscript: {:?}", font.superscript_metrics());
     println!("Variable: {:?}", font.is_variable());
+    println!("Monospaced: {}", font.is_monospaced());
+    println!("Italic angle: {:?}", font.italic_angle());
 
     if font.is_variable() {
         println!("Variation axes:");
@@ -0,0 +1,2991 @@
+// Useful links:
+// https://docs.microsoft.com/en-us/typography/opentype/spec/tt_instructions
+// https://docs.microsoft.com/en-us/typography/opentype/spec/tt_graphics_state
+// https://docs.microsoft.com/en-us/typography/cleartype/truetypecleartype
+// https://developer.apple.com/fonts/TrueType-Reference-Manual/RM05/Chap5.html
+//
+// The specification is vague in a lot of places, so we are following FreeType's
+// `ttinterp.c` behavior, since that's what most fonts are tested against.
+
+use core::convert::TryFrom;
+
+use alloc::vec::Vec;
+
+use crate::{Font, GlyphId, OutlineBuilder, Transform, TrueTypeLimits};
+use crate::{GlyphComponents, GlyphPoints};
+use crate::glyf;
+use crate::instructions::{self, Instruction, NPUSHB, NPUSHW, PUSHB_START, PUSHW_END};
+
+/// The maximum amount of work done by the Font Program, the Control Value Program
+/// or a single `outline_glyph` call.
+///
+/// Infinite loops are trivial to write using jumps, so we have to stop somewhere.
+/// Each instruction costs one unit, while instructions that walk a contour
+/// or a whole zone (`IUP`, `SHC`, `SHZ`, `FLIPRGON`/`FLIPRGOFF`) additionally cost
+/// one unit per point, so looping them on a large glyph is bounded as well.
+///
+/// All the glyph programs of a composite glyph share the same budget
+/// and each loaded component costs one unit per point as well,
+/// so components referencing each other multiple times cannot cause an exponential complexity.
+const MAX_INSTRUCTIONS: u32 = 1_000_000;
+
+/// The maximum depth of nested function calls.
+const MAX_CALL_DEPTH: usize = 32;
+
+// Some fonts underestimate the stack size in `maxp`, so FreeType adds a margin.
+const STACK_MARGIN: usize = 32;
+
+// Horizontal and vertical metrics points, which are appended to glyph's points.
+const PHANTOM_POINTS: usize = 4;
+
+const TWILIGHT_ZONE: usize = 0;
+const GLYPH_ZONE: usize = 1;
+
+const ONE_2DOT14: i32 = 0x4000;
+const ONE_16DOT16: i32 = 0x10000;
+
+mod point_flag {
+    pub const ON_CURVE: u8  = 0x01;
+    pub const TOUCHED_X: u8 = 0x02;
+    pub const TOUCHED_Y: u8 = 0x04;
+}
+
+mod opcode {
+    pub const SVTCA_0: u8       = 0x00;
+    pub const SVTCA_1: u8       = 0x01;
+    pub const SPVTCA_0: u8      = 0x02;
+    pub const SPVTCA_1: u8      = 0x03;
+    pub const SFVTCA_0: u8      = 0x04;
+    pub const SFVTCA_1: u8      = 0x05;
+    pub const SPVTL_0: u8       = 0x06;
+    pub const SPVTL_1: u8       = 0x07;
+    pub const SFVTL_0: u8       = 0x08;
+    pub const SFVTL_1: u8       = 0x09;
+    pub const SPVFS: u8         = 0x0A;
+    pub const SFVFS: u8         = 0x0B;
+    pub const GPV: u8           = 0x0C;
+    pub const GFV: u8           = 0x0D;
+    pub const SFVTPV: u8        = 0x0E;
+    pub const ISECT: u8         = 0x0F;
+    pub const SRP0: u8          = 0x10;
+    pub const SRP1: u8          = 0x11;
+    pub const SRP2: u8          = 0x12;
+    pub const SZP0: u8          = 0x13;
+    pub const SZP1: u8          = 0x14;
+    pub const SZP2: u8          = 0x15;
+    pub const SZPS: u8          = 0x16;
+    pub const SLOOP: u8         = 0x17;
+    pub const RTG: u8           = 0x18;
+    pub const RTHG: u8          = 0x19;
+    pub const SMD: u8           = 0x1A;
+    pub const ELSE: u8          = 0x1B;
+    pub const JMPR: u8          = 0x1C;
+    pub const SCVTCI: u8        = 0x1D;
+    pub const SSWCI: u8         = 0x1E;
+    pub const SSW: u8           = 0x1F;
+    pub const DUP: u8           = 0x20;
+    pub const POP: u8           = 0x21;
+    pub const CLEAR: u8         = 0x22;
+    pub const SWAP: u8          = 0x23;
+    pub const DEPTH: u8         = 0x24;
+    pub const CINDEX: u8        = 0x25;
+    pub const MINDEX: u8        = 0x26;
+    pub const ALIGNPTS: u8      = 0x27;
+    pub const UTP: u8           = 0x29;
+    pub const LOOPCALL: u8      = 0x2A;
+    pub const CALL: u8          = 0x2B;
+    pub const FDEF: u8          = 0x2C;
+    pub const ENDF: u8          = 0x2D;
+    pub const MDAP_0: u8        = 0x2E;
+    pub const MDAP_1: u8        = 0x2F;
+    pub const IUP_0: u8         = 0x30;
+    pub const IUP_1: u8         = 0x31;
+    pub const SHP_0: u8         = 0x32;
+    pub const SHP_1: u8         = 0x33;
+    pub const SHC_0: u8         = 0x34;
+    pub const SHC_1: u8         = 0x35;
+    pub const SHZ_0: u8         = 0x36;
+    pub const SHZ_1: u8         = 0x37;
+    pub const SHPIX: u8         = 0x38;
+    pub const IP: u8            = 0x39;
+    pub const MSIRP_0: u8       = 0x3A;
+    pub const MSIRP_1: u8       = 0x3B;
+    pub const ALIGNRP: u8       = 0x3C;
+    pub const RTDG: u8          = 0x3D;
+    pub const MIAP_0: u8        = 0x3E;
+    pub const MIAP_1: u8        = 0x3F;
+    pub const WS: u8            = 0x42;
+    pub const RS: u8            = 0x43;
+    pub const WCVTP: u8         = 0x44;
+    pub const RCVT: u8          = 0x45;
+    pub const GC_0: u8          = 0x46;
+    pub const GC_1: u8          = 0x47;
+    pub const SCFS: u8          = 0x48;
+    pub const MD_0: u8          = 0x49;
+    pub const MD_1: u8          = 0x4A;
+    pub const MPPEM: u8         = 0x4B;
+    pub const MPS: u8           = 0x4C;
+    pub const FLIPON: u8        = 0x4D;
+    pub const FLIPOFF: u8       = 0x4E;
+    pub const DEBUG: u8         = 0x4F;
+    pub const LT: u8            = 0x50;
+    pub const LTEQ: u8          = 0x51;
+    pub const GT: u8            = 0x52;
+    pub const GTEQ: u8          = 0x53;
+    pub const EQ: u8            = 0x54;
+    pub const NEQ: u8           = 0x55;
+    pub const ODD: u8           = 0x56;
+    pub const EVEN: u8          = 0x57;
+    pub const IF: u8            = 0x58;
+    pub const EIF: u8           = 0x59;
+    pub const AND: u8           = 0x5A;
+    pub const OR: u8            = 0x5B;
+    pub const NOT: u8           = 0x5C;
+    pub const DELTAP1: u8       = 0x5D;
+    pub const SDB: u8           = 0x5E;
+    pub const SDS: u8           = 0x5F;
+    pub const ADD: u8           = 0x60;
+    pub const SUB: u8           = 0x61;
+    pub const DIV: u8           = 0x62;
+    pub const MUL: u8           = 0x63;
+    pub const ABS: u8           = 0x64;
+    pub const NEG: u8           = 0x65;
+    pub const FLOOR: u8         = 0x66;
+    pub const CEILING: u8       = 0x67;
+    pub const ROUND_START: u8   = 0x68;
+    pub const ROUND_END: u8     = 0x6B;
+    pub const NROUND_START: u8  = 0x6C;
+    pub const NROUND_END: u8    = 0x6F;
+    pub const WCVTF: u8         = 0x70;
+    pub const DELTAP2: u8       = 0x71;
+    pub const DELTAP3: u8       = 0x72;
+    pub const DELTAC1: u8       = 0x73;
+    pub const DELTAC2: u8       = 0x74;
+    pub const DELTAC3: u8       = 0x75;
+    pub const SROUND: u8        = 0x76;
+    pub const S45ROUND: u8      = 0x77;
+    pub const JROT: u8          = 0x78;
+    pub const JROF: u8          = 0x79;
+    pub const ROFF: u8          = 0x7A;
+    pub const RUTG: u8          = 0x7C;
+    pub const RDTG: u8          = 0x7D;
+    pub const SANGW: u8         = 0x7E;
+    pub const AA: u8            = 0x7F;
+    pub const FLIPPT: u8        = 0x80;
+    pub const FLIPRGON: u8      = 0x81;
+    pub const FLIPRGOFF: u8     = 0x82;
+    pub const SCANCTRL: u8      = 0x85;
+    pub const SDPVTL_0: u8      = 0x86;
+    pub const SDPVTL_1: u8      = 0x87;
+    pub const GETINFO: u8       = 0x88;
+    pub const IDEF: u8          = 0x89;
+    pub const ROLL: u8          = 0x8A;
+    pub const MAX: u8           = 0x8B;
+    pub const MIN: u8           = 0x8C;
+    pub const SCANTYPE: u8      = 0x8D;
+    pub const INSTCTRL: u8      = 0x8E;
+    pub const GETVARIATION: u8  = 0x91;
+    pub const MDRP_START: u8    = 0xC0;
+    pub const MDRP_END: u8      = 0xDF;
+    pub const MIRP_START: u8    = 0xE0;
+    pub const MIRP_END: u8      = 0xFF;
+}
+
+
+/// A TrueType hinting mode.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum HintingMode {
+    /// Grid-fits outlines in both directions.
+    ///
+    /// Behaves like the classic interpreter (FreeType's v35).
+    Full,
+    /// Grid-fits outlines only vertically.
+    ///
+    /// Behaves like FreeType's v40 interpreter, which implements the ClearType
+    /// backward compatibility mode: horizontal movements are ignored and
+    /// the outline is frozen after `IUP` was executed in both directions.
+    /// Fonts can opt out of it using `INSTCTRL` in the Control Value Program.
+    ///
+    /// Best suited for anti-aliased rendering with fractional glyph positioning.
+    Subpixel,
+}
+
+
+/// A TrueType hinting instance.
+///
+/// Executes TrueType instructions to grid-fit glyph outlines for a specific size.
+/// The Font Program and the Control Value Program are executed on creation,
+/// so an instance should be reused for all glyphs of the same size.
+///
+/// Memory usage is bounded by the limits stored in the `maxp` table
+/// and by the number of glyph points. The number of executed instructions is limited,
+/// so malformed fonts cannot cause an infinite loop.
+///
+/// Unlike other parsers in this crate, has to allocate,
+/// since the interpreter state depends on the font.
+///
+/// `cvar` variations are not supported yet.
+#[derive(Clone)]
+pub struct HintingInstance<'a> {
+    font: Font<'a>,
+    ppem: u16,
+    mode: HintingMode,
+    is_enabled: bool,
+    // The state set by the Control Value Program.
+    // Restored before each glyph.
+    gs: GraphicsState,
+    cvt: Vec<i32>,
+    storage: Vec<i32>,
+    twilight: Zone,
+    exec: Executor<'a>,
+}
+
+impl<'a> HintingInstance<'a> {
+    /// Creates a new hinting instance for the specified pixels per em.
+    ///
+    /// Hinting will be disabled when the Font Program or the Control Value Program fail.
+    ///
+    /// Returns `None` when `ppem` is zero or the font doesn't have `glyf` and `loca` tables.
+    pub fn new(font: &Font<'a>, ppem: u16, mode: HintingMode) -> Option<Self> {
+        if ppem == 0 || font.glyf.is_none() || font.loca.is_none() {
+            return None;
+        }
+
+        let units_per_em = font.units_per_em()?;
+        let scale = div_fix(i32::from(ppem) * 64, i32::from(units_per_em));
+
+        let limits = font.maximum_profile().true_type_limits;
+        let limit = |f: fn(&TrueTypeLimits) -> u16| {
+            limits.as_ref().map(f).map(usize::from).unwrap_or(0)
+        };
+
+        let cvt = font.control_values()
+            .map(|values| values.map(|v| mul_fix(i32::from(v), scale)).collect())
+            .unwrap_or_default();
+
+        // Like FreeType, reserve space for the phantom points in the twilight zone.
+        let twilight_len = limit(|l| l.max_twilight_points) + PHANTOM_POINTS;
+        let twilight = Zone {
+            orus: alloc::vec![Vector::default(); twilight_len],
+            org: alloc::vec![Vector::default(); twilight_len],
+            cur: alloc::vec![Vector::default(); twilight_len],
+            flags: alloc::vec![0; twilight_len],
+            contours: Vec::new(),
+        };
+
+        let max_stack = limit(|l| l.max_stack_elements) + STACK_MARGIN;
+        let mut exec = Executor {
+            font_program: font.fpgm.unwrap_or_default(),
+            cvt_program: font.prep.unwrap_or_default(),
+            glyph_program: &[],
+            program: Program::Font,
+            gs: GraphicsState::default(),
+            zones: [twilight, Zone::default()],
+            stack: Vec::with_capacity(max_stack),
+            max_stack,
+            call_stack: Vec::with_capacity(MAX_CALL_DEPTH),
+            cvt,
+            storage: alloc::vec![0; limit(|l| l.max_storage)],
+            functions: alloc::vec![None; limit(|l| l.max_function_defs)],
+            instruction_defs: alloc::vec![None; 256],
+            coords: font.coords().iter().map(|c| i32::from(c.get())).collect(),
+            is_variable: font.is_variable(),
+            ppem: i32::from(ppem),
+            scale,
+            orus_scale: scale,
+            subpixel: mode == HintingMode::Subpixel,
+            backward_compatibility: false,
+            iupx_called: false,
+            iupy_called: false,
+            is_composite: false,
+            budget: 0,
+        };
+
+        let mut is_enabled = true;
+        if !exec.font_program.is_empty() {
+            exec.budget = MAX_INSTRUCTIONS;
+            is_enabled = exec.execute(Program::Font).is_some();
+        }
+
+        exec.gs = GraphicsState::default();
+        if is_enabled && !exec.cvt_program.is_empty() {
+            exec.budget = MAX_INSTRUCTIONS;
+            is_enabled = exec.execute(Program::ControlValue).is_some();
+        }
+
+        // The Control Value Program is not allowed to change some of the values.
+        let mut gs = exec.gs;
+        if gs.instruct_control & 2 != 0 {
+            gs = GraphicsState { instruct_control: gs.instruct_control, ..GraphicsState::default() };
+        }
+
+        gs.dual_vector = X_AXIS;
+        gs.projection_vector = X_AXIS;
+        gs.freedom_vector = X_AXIS;
+        gs.fdotp = ONE_2DOT14;
+        gs.rp0 = 0;
+        gs.rp1 = 0;
+        gs.rp2 = 0;
+        gs.zp0 = GLYPH_ZONE;
+        gs.zp1 = GLYPH_ZONE;
+        gs.zp2 = GLYPH_ZONE;
+        gs.loop_value = 1;
+
+        // Glyph instructions can be disabled by the font itself.
+        if gs.instruct_control & 1 != 0 {
+            is_enabled = false;
+        }
+
+        Some(HintingInstance {
+            font: font.clone(),
+            ppem,
+            mode,
+            is_enabled,
+            gs,
+            cvt: exec.cvt.clone(),
+            storage: exec.storage.clone(),
+            twilight: exec.zones[TWILIGHT_ZONE].clone(),
+            exec,
+        })
+    }
+
+    /// Returns pixels per em.
+    #[inline]
+    pub fn ppem(&self) -> u16 {
+        self.ppem
+    }
+
+    /// Returns the hinting mode.
+    #[inline]
+    pub fn mode(&self) -> HintingMode {
+        self.mode
+    }
+
+    /// Checks that glyph instructions will be executed.
+    ///
+    /// Hinting can be disabled by the font itself, using `INSTCTRL`,
+    /// or when the Font Program or the Control Value Program fail.
+    /// In this case, outlines are only scaled.
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.is_enabled
+    }
+
+    /// Outlines a grid-fitted glyph.
+    ///
+    /// Coordinates are in pixels and the origin is grid-fitted as well.
+    ///
+    /// When glyph instructions fail, the glyph will be outlined without hinting.
+    ///
+    /// Returns `None` when glyph has no outline, exceeds the execution budget or on error.
+    pub fn outline_glyph(&mut self, glyph_id: GlyphId, builder: &mut dyn OutlineBuilder) -> Option<()> {
+        // Glyph programs are allowed to modify the state, but it must not affect other glyphs.
+        self.exec.cvt.clone_from(&self.cvt);
+        self.exec.storage.clone_from(&self.storage);
+        self.exec.zones[TWILIGHT_ZONE].copy_from(&self.twilight);
+        self.exec.budget = MAX_INSTRUCTIONS;
+
+        let mut outline = Zone::default();
+        let phantom = self.load_glyph(glyph_id, 0, &mut outline)?;
+        if outline.cur.is_empty() {
+            return None;
+        }
+
+        let origin = phantom[0].x;
+        let mut b = glyf::Builder::new(Transform::default(), None, builder);
+        let mut start = 0;
+        for end in outline.contours.iter().map(|end| usize::from(*end)) {
+            for i in start..=end {
+                let p = outline.cur[i];
+                b.push_point(
+                    f26dot6_to_f32(p.x.wrapping_sub(origin)),
+                    f26dot6_to_f32(p.y),
+                    outline.flags[i] & point_flag::ON_CURVE != 0,
+                    i == end,
+                );
+            }
+
+            start = end + 1;
+        }
+
+        Some(())
+    }
+
+    // Loads a hinted glyph into `outline` and returns its phantom points.
+    fn load_glyph(
+        &mut self,
+        glyph_id: GlyphId,
+        depth: u8,
+        outline: &mut Zone,
+    ) -> Option<[Vector; PHANTOM_POINTS]> {
+        if depth >= glyf::MAX_COMPONENTS {
+            return None;
+        }
+
+        self.exec.try_charge(1)?;
+
+        if let Some(components) = self.font.glyph_components(glyph_id) {
+            return self.load_composite(glyph_id, components, depth, outline);
+        }
+
+        let points = self.font.glyph_points(glyph_id, true)
+            .or_else(|| self.font.glyph_points(glyph_id, false));
+        if let Some(points) = points {
+            return self.load_simple(glyph_id, points, outline);
+        }
+
+        // An empty glyph still has metrics.
+        Some(self.scaled_phantom_points(glyph_id, 0))
+    }
+
+    fn load_simple(
+        &mut self,
+        glyph_id: GlyphId,
+        points: GlyphPoints<'a>,
+        outline: &mut Zone,
+    ) -> Option<[Vector; PHANTOM_POINTS]> {
+        let zone = &mut self.exec.zones[GLYPH_ZONE];
+        zone.clear();
+
+        for i in 0..points.number_of_contours() {
+            let end = points.contour_end_point(i)?;
+            if let Some(prev) = zone.contours.last() {
+                if end <= *prev {
+                    return None;
+                }
+            }
+
+            zone.contours.push(end);
+        }
+
+        let mut x_min = None;
+        for point in points {
+            let p = Vector::new(round_f32(point.x), round_f32(point.y));
+            x_min = Some(x_min.map_or(p.x, |x: i32| x.min(p.x)));
+            zone.orus.push(p);
+            zone.flags.push(if point.on_curve_point { point_flag::ON_CURVE } else { 0 });
+        }
+
+        let points_len = zone.orus.len();
+        if zone.contours.last().map(|end| usize::from(*end) + 1) != Some(points_len) {
+            return None;
+        }
+
+        self.exec.try_charge(points_len)?;
+
+        let phantom = self.phantom_points(glyph_id, x_min.unwrap_or(0));
+        let scale = self.exec.scale;
+        let zone = &mut self.exec.zones[GLYPH_ZONE];
+        zone.orus.extend_from_slice(&phantom);
+        zone.flags.extend_from_slice(&[0; PHANTOM_POINTS]);
+        zone.org.extend(zone.orus.iter().map(|p| scale_vector(*p, scale)));
+        zone.cur.clone_from(&zone.org);
+        round_phantom_points(zone);
+
+        let program = self.glyph_program(glyph_id);
+        if self.is_enabled && !program.is_empty() {
+            self.hint(program, false);
+        }
+
+        let zone = &self.exec.zones[GLYPH_ZONE];
+        outline.append(zone, points_len)?;
+
+        let mut phantom = [Vector::default(); PHANTOM_POINTS];
+        phantom.copy_from_slice(&zone.cur[points_len..]);
+        Some(phantom)
+    }
+
+    fn load_composite(
+        &mut self,
+        glyph_id: GlyphId,
+        components: GlyphComponents<'a>,
+        depth: u8,
+        outline: &mut Zone,
+    ) -> Option<[Vector; PHANTOM_POINTS]> {
+        // In case of a composite glyph, `gvar` contains components offset adjustments.
+        let mut variation = match self.font.gvar {
+            Some(ref gvar_table) if !self.font.coords().is_empty() => {
+                let components_count = u16::try_from(components.clone().count()).ok()?;
+                gvar_table.points_variation(glyph_id, self.font.coords(), components_count)
+            }
+            _ => None,
+        };
+
+        let x_min = glyf::glyph_bbox(self.font.loca?, self.font.glyf?, glyph_id)
+            .map(|bbox| i32::from(bbox.x_min))
+            .unwrap_or(0);
+        let mut phantom = self.scaled_phantom_points(glyph_id, x_min);
+
+        // Components are hinted separately and then combined.
+        let mut zone = Zone::default();
+        for component in components {
+            let (var_dx, var_dy) = variation.as_mut()
+                .and_then(|tuples| tuples.apply_null())
+                .unwrap_or((0.0, 0.0));
+
+            let start = zone.cur.len();
+            let component_phantom = self.load_glyph(component.glyph_id, depth + 1, &mut zone)?;
+
+            let ts = component.transform;
+            if ts.a != 1.0 || ts.b != 0.0 || ts.c != 0.0 || ts.d != 1.0 {
+                let (a, b, c, d) = (to_fixed(ts.a), to_fixed(ts.b), to_fixed(ts.c), to_fixed(ts.d));
+                for p in &mut zone.cur[start..] {
+                    let (x, y) = (p.x, p.y);
+                    p.x = mul_fix(x, a).wrapping_add(mul_fix(y, c));
+                    p.y = mul_fix(x, b).wrapping_add(mul_fix(y, d));
+                }
+            }
+
+            let offset = if let Some((parent, child)) = component.matched_points {
+                let parent = zone.cur[..start].get(usize::from(parent));
+                let child = zone.cur[start..].get(usize::from(child));
+                match (parent, child) {
+                    (Some(parent), Some(child)) => parent.sub(*child),
+                    _ => Vector::default(),
+                }
+            } else {
+                let scale = self.exec.scale;
+                let mut x = mul_fix(round_f32(ts.e + var_dx), scale);
+                let mut y = mul_fix(round_f32(ts.f + var_dy), scale);
+                if component.flags.round_xy_to_grid() {
+                    // Like FreeType, the horizontal offset is not rounded in the subpixel mode,
+                    // since advances are not grid-fitted as well.
+                    if self.mode == HintingMode::Full {
+                        x = round_to_pixel(x);
+                    }
+
+                    y = round_to_pixel(y);
+                }
+
+                Vector::new(x, y)
+            };
+
+            if offset != Vector::default() {
+                for p in &mut zone.cur[start..] {
+                    *p = p.add(offset);
+                }
+            }
+
+            if component.flags.use_my_metrics() {
+                phantom = component_phantom;
+            }
+        }
+
+        // Composite glyph instructions refer to already hinted components.
+        let program = self.glyph_program(glyph_id);
+        if self.is_enabled && !program.is_empty() {
+            let points_len = zone.cur.len();
+            {
+                let g = &mut self.exec.zones[GLYPH_ZONE];
+                g.clear();
+                g.cur.extend_from_slice(&zone.cur);
+                g.cur.extend_from_slice(&phantom);
+                g.org.clone_from(&g.cur);
+                g.orus.clone_from(&g.cur);
+                g.flags.extend_from_slice(&zone.flags);
+                g.flags.extend_from_slice(&[0; PHANTOM_POINTS]);
+                g.contours.clone_from(&zone.contours);
+            }
+
+            self.hint(program, true);
+
+            let g = &self.exec.zones[GLYPH_ZONE];
+            zone.cur.copy_from_slice(&g.cur[..points_len]);
+            zone.flags.copy_from_slice(&g.flags[..points_len]);
+            phantom.copy_from_slice(&g.cur[points_len..]);
+        }
+
+        let points_len = zone.cur.len();
+        self.exec.try_charge(points_len)?;
+        outline.append(&zone, points_len)?;
+        Some(phantom)
+    }
+
+    // Executes glyph instructions on the glyph zone.
+    fn hint(&mut self, program: &'a [u8], is_composite: bool) {
+        let exec = &mut self.exec;
+        exec.glyph_program = program;
+        exec.gs = self.gs;
+        exec.is_composite = is_composite;
+        // Instructions of a composite glyph operate on already scaled points.
+        exec.orus_scale = if is_composite { ONE_16DOT16 } else { exec.scale };
+
+        if exec.execute(Program::Glyph).is_none() {
+            // Fallback to an unhinted outline.
+            let zone = &mut exec.zones[GLYPH_ZONE];
+            zone.cur.clone_from(&zone.org);
+            for flags in &mut zone.flags {
+                *flags &= point_flag::ON_CURVE;
+            }
+
+            round_phantom_points(zone);
+        }
+    }
+
+    #[inline]
+    fn glyph_program(&self, glyph_id: GlyphId) -> &'a [u8] {
+        self.font.glyph_instructions(glyph_id).map(|i| i.data()).unwrap_or_default()
+    }
+
+    // Returns phantom points in font units.
+    fn phantom_points(&self, glyph_id: GlyphId, x_min: i32) -> [Vector; PHANTOM_POINTS] {
+        let lsb = i32::from(self.font.glyph_hor_side_bearing(glyph_id).unwrap_or(0));
+        let advance = i32::from(self.font.glyph_hor_advance(glyph_id).unwrap_or(0));
+        let x = x_min - lsb;
+        [
+            Vector::new(x, 0),
+            Vector::new(x + advance, 0),
+            Vector::new(0, i32::from(self.font.ascender())),
+            Vector::new(0, i32::from(self.font.descender())),
+        ]
+    }
+
+    fn scaled_phantom_points(&self, glyph_id: GlyphId, x_min: i32) -> [Vector; PHANTOM_POINTS] {
+        let mut points = self.phantom_points(glyph_id, x_min);
+        for p in &mut points {
+            *p = scale_vector(*p, self.exec.scale);
+        }
+
+        points[0].x = round_to_pixel(points[0].x);
+        points[1].x = round_to_pixel(points[1].x);
+        points[2].y = round_to_pixel(points[2].y);
+        points[3].y = round_to_pixel(points[3].y);
+        points
+    }
+}
+
+impl core::fmt::Debug for HintingInstance<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "HintingInstance()")
+    }
+}
+
+
+#[derive(Clone, Copy, PartialEq, Default)]
+struct Vector {
+    x: i32,
+    y: i32,
+}
+
+const X_AXIS: Vector = Vector { x: ONE_2DOT14, y: 0 };
+const Y_AXIS: Vector = Vector { x: 0, y: ONE_2DOT14 };
+
+impl Vector {
+    #[inline]
+    fn new(x: i32, y: i32) -> Self {
+        Vector { x, y }
+    }
+
+    #[inline]
+    fn add(self, other: Self) -> Self {
+        Vector::new(self.x.wrapping_add(other.x), self.y.wrapping_add(other.y))
+    }
+
+    #[inline]
+    fn sub(self, other: Self) -> Self {
+        Vector::new(self.x.wrapping_sub(other.x), self.y.wrapping_sub(other.y))
+    }
+
+    #[inline]
+    fn get(self, x_axis: bool) -> i32 {
+        if x_axis { self.x } else { self.y }
+    }
+
+    #[inline]
+    fn set(&mut self, x_axis: bool, value: i32) {
+        if x_axis { self.x = value } else { self.y = value }
+    }
+}
+
+
+/// A set of points instructions operate on.
+#[derive(Clone, Default)]
+struct Zone {
+    // Unscaled points in font units.
+    orus: Vec<Vector>,
+    // Scaled points before hinting, in 26.6.
+    org: Vec<Vector>,
+    // Scaled points, in 26.6.
+    cur: Vec<Vector>,
+    flags: Vec<u8>,
+    // Contours end points. Phantom points do not belong to any contour.
+    contours: Vec<u16>,
+}
+
+impl Zone {
+    #[inline]
+    fn len(&self) -> usize {
+        self.cur.len()
+    }
+
+    #[inline]
+    fn point(&self, index: i32) -> Option<usize> {
+        usize::try_from(index).ok().filter(|i| *i < self.len())
+    }
+
+    fn clear(&mut self) {
+        self.orus.clear();
+        self.org.clear();
+        self.cur.clear();
+        self.flags.clear();
+        self.contours.clear();
+    }
+
+    // Unlike `clone_from`, reuses allocations.
+    fn copy_from(&mut self, other: &Zone) {
+        self.clear();
+        self.orus.extend_from_slice(&other.orus);
+        self.org.extend_from_slice(&other.org);
+        self.cur.extend_from_slice(&other.cur);
+        self.flags.extend_from_slice(&other.flags);
+        self.contours.extend_from_slice(&other.contours);
+    }
+
+    // Appends current points of another zone. Only on-curve flags are preserved.
+    fn append(&mut self, other: &Zone, points_len: usize) -> Option<()> {
+        let offset = self.cur.len();
+        for end in &other.contours {
+            self.contours.push(u16::try_from(offset + usize::from(*end)).ok()?);
+        }
+
+        self.cur.extend_from_slice(other.cur.get(..points_len)?);
+        self.flags.extend(other.flags.get(..points_len)?.iter().map(|f| f & point_flag::ON_CURVE));
+        Some(())
+    }
+}
+
+
+#[derive(Clone, Copy, PartialEq)]
+enum RoundState {
+    ToHalfGrid,
+    ToGrid,
+    ToDoubleGrid,
+    DownToGrid,
+    UpToGrid,
+    Off,
+    Super,
+    Super45,
+}
+
+
+#[derive(Clone, Copy)]
+struct GraphicsState {
+    auto_flip: bool,
+    control_value_cutin: i32,
+    delta_base: i32,
+    delta_shift: i32,
+    dual_vector: Vector,
+    freedom_vector: Vector,
+    projection_vector: Vector,
+    // The freedom and projection vectors dot product.
+    // Cached, since it's used by each point movement.
+    fdotp: i32,
+    instruct_control: u8,
+    loop_value: u32,
+    minimum_distance: i32,
+    round_state: RoundState,
+    // `SROUND` and `S45ROUND` parameters.
+    period: i32,
+    phase: i32,
+    threshold: i32,
+    rp0: usize,
+    rp1: usize,
+    rp2: usize,
+    single_width_cutin: i32,
+    single_width_value: i32,
+    zp0: usize,
+    zp1: usize,
+    zp2: usize,
+}
+
+impl Default for GraphicsState {
+    fn default() -> Self {
+        GraphicsState {
+            auto_flip: true,
+            control_value_cutin: 68, // 17/16 pixels
+            delta_base: 9,
+            delta_shift: 3,
+            dual_vector: X_AXIS,
+            freedom_vector: X_AXIS,
+            projection_vector: X_AXIS,
+            fdotp: ONE_2DOT14,
+            instruct_control: 0,
+            loop_value: 1,
+            minimum_distance: 64,
+            round_state: RoundState::ToGrid,
+            period: 64,
+            phase: 0,
+            threshold: 32,
+            rp0: 0,
+            rp1: 0,
+            rp2: 0,
+            single_width_cutin: 0,
+            single_width_value: 0,
+            zp0: GLYPH_ZONE,
+            zp1: GLYPH_ZONE,
+            zp2: GLYPH_ZONE,
+        }
+    }
+}
+
+
+#[derive(Clone, Copy, PartialEq)]
+enum Program {
+    Font,
+    ControlValue,
+    Glyph,
+}
+
+/// A function or an instruction defined by `FDEF` or `IDEF`.
+#[derive(Clone, Copy)]
+struct Definition {
+    program: Program,
+    start: usize,
+}
+
+#[derive(Clone, Copy)]
+struct CallFrame {
+    program: Program,
+    return_offset: usize,
+    definition: Definition,
+    count: i32,
+}
+
+
+#[derive(Clone)]
+struct Executor<'a> {
+    font_program: &'a [u8],
+    cvt_program: &'a [u8],
+    glyph_program: &'a [u8],
+    program: Program,
+    gs: GraphicsState,
+    zones: [Zone; 2],
+    stack: Vec<i32>,
+    max_stack: usize,
+    call_stack: Vec<CallFrame>,
+    cvt: Vec<i32>,
+    storage: Vec<i32>,
+    functions: Vec<Option<Definition>>,
+    instruction_defs: Vec<Option<Definition>>,
+    coords: Vec<i32>,
+    is_variable: bool,
+    ppem: i32,
+    // Font units to 26.6 scale, in 16.16.
+    scale: i32,
+    // Scale of the `orus` points.
+    orus_scale: i32,
+    subpixel: bool,
+    backward_compatibility: bool,
+    iupx_called: bool,
+    iupy_called: bool,
+    is_composite: bool,
+    budget: u32,
+}
+
+impl<'a> Executor<'a> {
+    #[inline]
+    fn code(&self, program: Program) -> &'a [u8] {
+        match program {
+            Program::Font => self.font_program,
+            Program::ControlValue => self.cvt_program,
+            Program::Glyph => self.glyph_program,
+        }
+    }
+
+    fn execute(&mut self, program: Program) -> Option<()> {
+        self.program = program;
+        self.stack.clear();
+        self.call_stack.clear();
+        self.backward_compatibility = self.subpixel && self.gs.instruct_control & 4 == 0;
+        self.iupx_called = false;
+        self.iupy_called = false;
+
+        let mut current = program;
+        let mut code = self.code(current);
+        let mut offset = 0;
+        loop {
+            if offset >= code.len() {
+                // A function must end with `ENDF`.
+                return if self.call_stack.is_empty() { Some(()) } else { None };
+            }
+
+            self.budget = self.budget.checked_sub(1)?;
+
+            let (instruction, mut next_offset) = instructions::decode(code, offset)?;
+            let op = instruction.opcode;
+            match op {
+                opcode::IF => {
+                    if self.pop()? == 0 {
+                        next_offset = skip_branch(code, next_offset, true)?;
+                    }
+                }
+                opcode::ELSE => {
+                    next_offset = skip_branch(code, next_offset, false)?;
+                }
+                opcode::EIF => {}
+                opcode::JMPR => {
+                    let n = self.pop()?;
+                    next_offset = jump(code, offset, n)?;
+                }
+                opcode::JROT | opcode::JROF => {
+                    let e = self.pop()?;
+                    let n = self.pop()?;
+                    if (e != 0) == (op == opcode::JROT) {
+                        next_offset = jump(code, offset, n)?;
+                    }
+                }
+                opcode::FDEF | opcode::IDEF => {
+                    // Definitions are not allowed in glyph programs.
+                    if current == Program::Glyph {
+                        return None;
+                    }
+
+                    let n = usize::try_from(self.pop()?).ok()?;
+                    let definition = Some(Definition { program: current, start: next_offset });
+                    if op == opcode::FDEF {
+                        *self.functions.get_mut(n)? = definition;
+                    } else {
+                        *self.instruction_defs.get_mut(n)? = definition;
+                    }
+
+                    next_offset = skip_definition(code, next_offset)?;
+                }
+                opcode::ENDF => {
+                    let mut frame = self.call_stack.pop()?;
+                    if frame.count > 1 {
+                        frame.count -= 1;
+                        next_offset = frame.definition.start;
+                        self.call_stack.push(frame);
+                    } else {
+                        current = frame.program;
+                        code = self.code(current);
+                        next_offset = frame.return_offset;
+                    }
+                }
+                opcode::CALL | opcode::LOOPCALL => {
+                    let n = usize::try_from(self.pop()?).ok()?;
+                    let count = if op == opcode::LOOPCALL { self.pop()? } else { 1 };
+                    let definition = (*self.functions.get(n)?)?;
+                    if count > 0 {
+                        self.enter(definition, current, next_offset, count)?;
+                        current = definition.program;
+                        code = self.code(current);
+                        next_offset = definition.start;
+                    }
+                }
+                _ => {
+                    if !self.execute_instruction(instruction)? {
+                        let definition = (*self.instruction_defs.get(usize::from(op))?)?;
+                        self.enter(definition, current, next_offset, 1)?;
+                        current = definition.program;
+                        code = self.code(current);
+                        next_offset = definition.start;
+                    }
+                }
+            }
+
+            offset = next_offset;
+        }
+    }
+
+    #[inline]
+    fn enter(
+        &mut self,
+        definition: Definition,
+        program: Program,
+        return_offset: usize,
+        count: i32,
+    ) -> Option<()> {
+        if self.call_stack.len() >= MAX_CALL_DEPTH {
+            return None;
+        }
+
+        self.call_stack.push(CallFrame { program, return_offset, definition, count });
+        Some(())
+    }
+
+    // Returns `false` for unknown instructions.
+    fn execute_instruction(&mut self, instruction: Instruction) -> Option<bool> {
+        let op = instruction.opcode;
+        match op {
+            opcode::SVTCA_0 | opcode::SVTCA_1 => {
+                let v = axis_vector(op);
+                self.gs.projection_vector = v;
+                self.gs.dual_vector = v;
+                self.gs.freedom_vector = v;
+                self.update_fdotp();
+            }
+            opcode::SPVTCA_0 | opcode::SPVTCA_1 => {
+                let v = axis_vector(op);
+                self.gs.projection_vector = v;
+                self.gs.dual_vector = v;
+                self.update_fdotp();
+            }
+            opcode::SFVTCA_0 | opcode::SFVTCA_1 => {
+                self.gs.freedom_vector = axis_vector(op);
+                self.update_fdotp();
+            }
+            opcode::SPVTL_0 | opcode::SPVTL_1 => {
+                let p1 = self.pop()?;
+                let p2 = self.pop()?;
+                if let Some(v) = self.line_vector(p1, p2, op & 1 != 0, false) {
+                    self.gs.projection_vector = v;
+                    self.gs.dual_vector = v;
+                    self.update_fdotp();
+                }
+            }
+            opcode::SFVTL_0 | opcode::SFVTL_1 => {
+                let p1 = self.pop()?;
+                let p2 = self.pop()?;
+                if let Some(v) = self.line_vector(p1, p2, op & 1 != 0, false) {
+                    self.gs.freedom_vector = v;
+                    self.update_fdotp();
+                }
+            }
+            opcode::SDPVTL_0 | opcode::SDPVTL_1 => {
+                let p1 = self.pop()?;
+                let p2 = self.pop()?;
+                let dual = self.line_vector(p1, p2, op & 1 != 0, true);
+                let projection = self.line_vector(p1, p2, op & 1 != 0, false);
+                if let (Some(dual), Some(projection)) = (dual, projection) {
+                    self.gs.dual_vector = dual;
+                    self.gs.projection_vector = projection;
+                    self.update_fdotp();
+                }
+            }
+            opcode::SPVFS | opcode::SFVFS => {
+                // Values are F2Dot14, so we have to truncate them.
+                let y = i32::from(self.pop()? as i16);
+                let x = i32::from(self.pop()? as i16);
+                if x != 0 || y != 0 {
+                    let v = normalize(Vector::new(x, y));
+                    if op == opcode::SPVFS {
+                        self.gs.projection_vector = v;
+                        self.gs.dual_vector = v;
+                    } else {
+                        self.gs.freedom_vector = v;
+                    }
+
+                    self.update_fdotp();
+                }
+            }
+            opcode::GPV => {
+                let v = self.gs.projection_vector;
+                self.push(v.x)?;
+                self.push(v.y)?;
+            }
+            opcode::GFV => {
+                let v = self.gs.freedom_vector;
+                self.push(v.x)?;
+                self.push(v.y)?;
+            }
+            opcode::SFVTPV => {
+                self.gs.freedom_vector = self.gs.projection_vector;
+                self.update_fdotp();
+            }
+            opcode::ISECT => {
+                let b1 = self.pop()?;
+                let b0 = self.pop()?;
+                let a1 = self.pop()?;
+                let a0 = self.pop()?;
+                let point = self.pop()?;
+                self.isect(point, a0, a1, b0, b1);
+            }
+            opcode::SRP0 => self.gs.rp0 = usize::try_from(self.pop()?).ok()?,
+            opcode::SRP1 => self.gs.rp1 = usize::try_from(self.pop()?).ok()?,
+            opcode::SRP2 => self.gs.rp2 = usize::try_from(self.pop()?).ok()?,
+            opcode::SZP0 => self.gs.zp0 = zone_index(self.pop()?)?,
+            opcode::SZP1 => self.gs.zp1 = zone_index(self.pop()?)?,
+            opcode::SZP2 => self.gs.zp2 = zone_index(self.pop()?)?,
+            opcode::SZPS => {
+                let zone = zone_index(self.pop()?)?;
+                self.gs.zp0 = zone;
+                self.gs.zp1 = zone;
+                self.gs.zp2 = zone;
+            }
+            opcode::SLOOP => self.gs.loop_value = u32::try_from(self.pop()?).ok()?,
+            opcode::RTG => self.gs.round_state = RoundState::ToGrid,
+            opcode::RTHG => self.gs.round_state = RoundState::ToHalfGrid,
+            opcode::RTDG => self.gs.round_state = RoundState::ToDoubleGrid,
+            opcode::RDTG => self.gs.round_state = RoundState::DownToGrid,
+            opcode::RUTG => self.gs.round_state = RoundState::UpToGrid,
+            opcode::ROFF => self.gs.round_state = RoundState::Off,
+            opcode::SROUND => {
+                let n = self.pop()?;
+                self.set_super_round(ONE_2DOT14, n);
+                self.gs.round_state = RoundState::Super;
+            }
+            opcode::S45ROUND => {
+                // sqrt(2)/2 in 2.14.
+                let n = self.pop()?;
+                self.set_super_round(0x2D41, n);
+                self.gs.round_state = RoundState::Super45;
+            }
+            opcode::SMD => self.gs.minimum_distance = self.pop()?,
+            opcode::SCVTCI => self.gs.control_value_cutin = self.pop()?,
+            opcode::SSWCI => self.gs.single_width_cutin = self.pop()?,
+            opcode::SSW => self.gs.single_width_value = mul_fix(self.pop()?, self.scale),
+            opcode::FLIPON => self.gs.auto_flip = true,
+            opcode::FLIPOFF => self.gs.auto_flip = false,
+            opcode::SDB => self.gs.delta_base = self.pop()?,
+            opcode::SDS => {
+                let n = self.pop()?;
+                if !(0..=6).contains(&n) {
+                    return None;
+                }
+
+                self.gs.delta_shift = n;
+            }
+            opcode::DUP => {
+                let v = *self.stack.last()?;
+                self.push(v)?;
+            }
+            opcode::POP => {
+                self.pop()?;
+            }
+            opcode::CLEAR => self.stack.clear(),
+            opcode::SWAP => {
+                let a = self.pop()?;
+                let b = self.pop()?;
+                self.push(a)?;
+                self.push(b)?;
+            }
+            opcode::DEPTH => {
+                let n = i32::try_from(self.stack.len()).ok()?;
+                self.push(n)?;
+            }
+            opcode::CINDEX | opcode::MINDEX => {
+                let k = usize::try_from(self.pop()?).ok()?;
+                let index = self.stack.len().checked_sub(k)?;
+                let v = *self.stack.get(index)?;
+                if op == opcode::MINDEX {
+                    self.stack.remove(index);
+                }
+
+                self.push(v)?;
+            }
+            opcode::ROLL => {
+                let a = self.pop()?;
+                let b = self.pop()?;
+                let c = self.pop()?;
+                self.push(b)?;
+                self.push(a)?;
+                self.push(c)?;
+            }
+            NPUSHB | NPUSHW | PUSHB_START..=PUSHW_END => {
+                for v in instruction.push_values() {
+                    self.push(v)?;
+                }
+            }
+            opcode::WS => {
+                let v = self.pop()?;
+                let index = self.pop()?;
+                // Like FreeType, ignore invalid indices.
+                if let Some(value) = usize::try_from(index).ok().and_then(|i| self.storage.get_mut(i)) {
+                    *value = v;
+                }
+            }
+            opcode::RS => {
+                let index = self.pop()?;
+                let v = usize::try_from(index).ok().and_then(|i| self.storage.get(i)).cloned();
+                self.push(v.unwrap_or(0))?;
+            }
+            opcode::WCVTP | opcode::WCVTF => {
+                let mut v = self.pop()?;
+                let index = self.pop()?;
+                if op == opcode::WCVTF {
+                    v = mul_fix(v, self.scale);
+                }
+
+                if let Some(value) = usize::try_from(index).ok().and_then(|i| self.cvt.get_mut(i)) {
+                    *value = v;
+                }
+            }
+            opcode::RCVT => {
+                let index = self.pop()?;
+                let v = self.read_cvt(index);
+                self.push(v)?;
+            }
+            opcode::GC_0 | opcode::GC_1 => {
+                let p = self.pop()?;
+                let v = self.coordinate(p, op == opcode::GC_1).unwrap_or(0);
+                self.push(v)?;
+            }
+            opcode::SCFS => {
+                let k = self.pop()?;
+                let p = self.pop()?;
+                self.scfs(p, k);
+            }
+            opcode::MD_0 | opcode::MD_1 => {
+                let k = self.pop()?;
+                let l = self.pop()?;
+                let v = self.measure_distance(l, k, op == opcode::MD_1).unwrap_or(0);
+                self.push(v)?;
+            }
+            opcode::MPPEM | opcode::MPS => {
+                // Like FreeType, we are always returning ppem,
+                // since the point size should not affect hinting.
+                self.push(self.ppem)?;
+            }
+            opcode::GETINFO => {
+                let selector = self.pop()?;
+                let v = self.info(selector);
+                self.push(v)?;
+            }
+            opcode::GETVARIATION => {
+                if !self.is_variable {
+                    return Some(false);
+                }
+
+                if self.stack.len() + self.coords.len() > self.max_stack {
+                    return None;
+                }
+
+                self.stack.extend_from_slice(&self.coords);
+            }
+            opcode::INSTCTRL => {
+                let selector = self.pop()?;
+                let value = self.pop()?;
+                if !(1..=3).contains(&selector) {
+                    return None;
+                }
+
+                // Can be used only in the Control Value Program.
+                if self.program == Program::ControlValue {
+                    let mask = 1 << (selector - 1);
+                    if value != 0 {
+                        self.gs.instruct_control |= mask;
+                    } else {
+                        self.gs.instruct_control &= !mask;
+                    }
+                }
+            }
+            opcode::DEBUG | opcode::SANGW | opcode::AA | opcode::SCANCTRL | opcode::SCANTYPE => {
+                // Obsolete or not applicable.
+                self.pop()?;
+            }
+            opcode::LT..=opcode::NEQ | opcode::AND | opcode::OR => {
+                let b = self.pop()?;
+                let a = self.pop()?;
+                let v = match op {
+                    opcode::LT => a < b,
+                    opcode::LTEQ => a <= b,
+                    opcode::GT => a > b,
+                    opcode::GTEQ => a >= b,
+                    opcode::EQ => a == b,
+                    opcode::NEQ => a != b,
+                    opcode::AND => a != 0 && b != 0,
+                    _ => a != 0 || b != 0,
+                };
+                self.push(i32::from(v))?;
+            }
+            opcode::ODD | opcode::EVEN => {
+                let v = self.pop()?;
+                let v = self.round(v) & 127;
+                self.push(i32::from(if op == opcode::ODD { v == 64 } else { v == 0 }))?;
+            }
+            opcode::NOT => {
+                let v = self.pop()?;
+                self.push(i32::from(v == 0))?;
+            }
+            opcode::ADD | opcode::SUB | opcode::DIV | opcode::MUL | opcode::MAX | opcode::MIN => {
+                let b = self.pop()?;
+                let a = self.pop()?;
+                let v = match op {
+                    opcode::ADD => a.wrapping_add(b),
+                    opcode::SUB => a.wrapping_sub(b),
+                    opcode::DIV => {
+                        if b == 0 {
+                            return None;
+                        }
+
+                        mul_div_no_round(a, 64, b)
+                    }
+                    opcode::MUL => mul_div(a, b, 64),
+                    opcode::MAX => a.max(b),
+                    _ => a.min(b),
+                };
+                self.push(v)?;
+            }
+            opcode::ABS | opcode::NEG | opcode::FLOOR | opcode::CEILING
+                | opcode::ROUND_START..=opcode::ROUND_END => {
+                let v = self.pop()?;
+                let v = match op {
+                    opcode::ABS => v.wrapping_abs(),
+                    opcode::NEG => v.wrapping_neg(),
+                    opcode::FLOOR => v & !63,
+                    opcode::CEILING => v.wrapping_add(63) & !63,
+                    _ => self.round(v),
+                };
+                self.push(v)?;
+            }
+            opcode::NROUND_START..=opcode::NROUND_END => {
+                // Engine compensation is always zero, so this is a no-op.
+                self.stack.last()?;
+            }
+            opcode::DELTAP1 | opcode::DELTAP2 | opcode::DELTAP3 => self.delta_point(op)?,
+            opcode::DELTAC1 | opcode::DELTAC2 | opcode::DELTAC3 => self.delta_cvt(op)?,
+            opcode::MDAP_0 | opcode::MDAP_1 => {
+                let p = self.pop()?;
+                self.mdap(p, op == opcode::MDAP_1);
+            }
+            opcode::MIAP_0 | opcode::MIAP_1 => {
+                let cvt_index = self.pop()?;
+                let p = self.pop()?;
+                self.miap(p, cvt_index, op == opcode::MIAP_1);
+            }
+            opcode::MSIRP_0 | opcode::MSIRP_1 => {
+                let d = self.pop()?;
+                let p = self.pop()?;
+                self.msirp(p, d, op == opcode::MSIRP_1);
+            }
+            opcode::MDRP_START..=opcode::MDRP_END => {
+                let p = self.pop()?;
+                self.mdrp(p, op);
+            }
+            opcode::MIRP_START..=opcode::MIRP_END => {
+                let cvt_index = self.pop()?;
+                let p = self.pop()?;
+                self.mirp(p, cvt_index, op);
+            }
+            opcode::ALIGNRP => self.align_rp()?,
+            opcode::ALIGNPTS => {
+                let p2 = self.pop()?;
+                let p1 = self.pop()?;
+                self.align_points(p1, p2);
+            }
+            opcode::IP => self.interpolate_points()?,
+            opcode::IUP_0 | opcode::IUP_1 => self.interpolate_untouched(op == opcode::IUP_1),
+            opcode::UTP => {
+                let p = self.pop()?;
+                self.untouch(p);
+            }
+            opcode::SHP_0 | opcode::SHP_1 => self.shift_points(op)?,
+            opcode::SHC_0 | opcode::SHC_1 => {
+                let contour = self.pop()?;
+                self.shift_contour(contour, op);
+            }
+            opcode::SHZ_0 | opcode::SHZ_1 => {
+                let zone = zone_index(self.pop()?)?;
+                self.shift_zone(zone, op);
+            }
+            opcode::SHPIX => self.shift_points_by_pixels()?,
+            opcode::FLIPPT => self.flip_points()?,
+            opcode::FLIPRGON | opcode::FLIPRGOFF => {
+                let hi = self.pop()?;
+                let lo = self.pop()?;
+                self.flip_range(lo, hi, op == opcode::FLIPRGON);
+            }
+            _ => return Some(false),
+        }
+
+        Some(true)
+    }
+
+    #[inline]
+    fn pop(&mut self) -> Option<i32> {
+        self.stack.pop()
+    }
+
+    #[inline]
+    fn push(&mut self, v: i32) -> Option<()> {
+        if self.stack.len() >= self.max_stack {
+            return None;
+        }
+
+        self.stack.push(v);
+        Some(())
+    }
+
+    #[inline]
+    fn take_loop(&mut self) -> u32 {
+        let n = self.gs.loop_value;
+        self.gs.loop_value = 1;
+        n
+    }
+
+    #[inline]
+    fn read_cvt(&self, index: i32) -> i32 {
+        // Like FreeType, ignore invalid indices.
+        usize::try_from(index).ok().and_then(|i| self.cvt.get(i)).cloned().unwrap_or(0)
+    }
+
+    fn info(&self, selector: i32) -> i32 {
+        let mut result = 0;
+        if selector & 1 != 0 {
+            result |= if self.subpixel { 40 } else { 35 };
+        }
+
+        if selector & 8 != 0 && self.is_variable {
+            result |= 1 << 10;
+        }
+
+        // We are assuming grayscale anti-aliasing in both modes.
+        if self.subpixel {
+            // ClearType.
+            if selector & 64 != 0 {
+                result |= 1 << 13;
+            }
+
+            // Symmetrical smoothing.
+            if selector & 1024 != 0 {
+                result |= 1 << 17;
+            }
+
+            // ClearType hinting and grayscale rendering.
+            if selector & 2048 != 0 {
+                result |= 1 << 18;
+            }
+        } else if selector & 32 != 0 {
+            // Grayscale rendering.
+            result |= 1 << 12;
+        }
+
+        result
+    }
+
+    fn update_fdotp(&mut self) {
+        let pv = self.gs.projection_vector;
+        let fv = self.gs.freedom_vector;
+        let d = (i64::from(pv.x) * i64::from(fv.x) + i64::from(pv.y) * i64::from(fv.y)) >> 14;
+        // Nearly perpendicular vectors would cause huge movements.
+        self.gs.fdotp = if d.abs() < 0x400 { ONE_2DOT14 } else { d as i32 };
+    }
+
+    #[inline]
+    fn project(&self, v: Vector) -> i32 {
+        dot14(v, self.gs.projection_vector)
+    }
+
+    #[inline]
+    fn dual_project(&self, v: Vector) -> i32 {
+        dot14(v, self.gs.dual_vector)
+    }
+
+    // Returns a normalized vector parallel or perpendicular to the line
+    // from `p1` in `zp2` to `p2` in `zp1`.
+    fn line_vector(&self, p1: i32, p2: i32, perpendicular: bool, original: bool) -> Option<Vector> {
+        let z1 = &self.zones[self.gs.zp2];
+        let z2 = &self.zones[self.gs.zp1];
+        let (p1, p2) = (z1.point(p1)?, z2.point(p2)?);
+        let v = if original {
+            z2.org[p2].sub(z1.org[p1])
+        } else {
+            z2.cur[p2].sub(z1.cur[p1])
+        };
+
+        // Like FreeType, the x-axis is used for coincident points.
+        if v == Vector::default() {
+            return Some(X_AXIS);
+        }
+
+        let v = if perpendicular { Vector::new(v.y.wrapping_neg(), v.x) } else { v };
+        Some(normalize(v))
+    }
+
+    fn set_super_round(&mut self, grid_period: i32, selector: i32) {
+        let period = match selector & 0xC0 {
+            0x00 => grid_period / 2,
+            0x80 => grid_period * 2,
+            _ => grid_period,
+        };
+
+        let phase = match selector & 0x30 {
+            0x00 => 0,
+            0x10 => period / 4,
+            0x20 => period / 2,
+            _ => period * 3 / 4,
+        };
+
+        let threshold = match selector & 0x0F {
+            0 => period - 1,
+            n => (n - 4) * period / 8,
+        };
+
+        // Convert to 26.6.
+        self.gs.period = period >> 8;
+        self.gs.phase = phase >> 8;
+        self.gs.threshold = threshold >> 8;
+    }
+
+    fn round(&self, d: i32) -> i32 {
+        match self.gs.round_state {
+            RoundState::ToGrid => round_with(d, |v| v.wrapping_add(32) & !63),
+            RoundState::ToHalfGrid => round_with(d, |v| (v & !63).wrapping_add(32)),
+            RoundState::ToDoubleGrid => round_with(d, |v| v.wrapping_add(16) & !31),
+            RoundState::DownToGrid => round_with(d, |v| v & !63),
+            RoundState::UpToGrid => round_with(d, |v| v.wrapping_add(63) & !63),
+            RoundState::Off => d,
+            RoundState::Super | RoundState::Super45 => {
+                let period = self.gs.period;
+                let phase = self.gs.phase;
+                let threshold = self.gs.threshold;
+                let to_period = |v: i32| {
+                    if self.gs.round_state == RoundState::Super {
+                        v & period.wrapping_neg()
+                    } else {
+                        v / period * period
+                    }
+                };
+
+                if d >= 0 {
+                    let v = to_period(d.wrapping_add(threshold - phase)).wrapping_add(phase);
+                    if v < 0 { phase } else { v }
+                } else {
+                    let v = to_period((threshold - phase).wrapping_sub(d)).wrapping_neg().wrapping_sub(phase);
+                    if v > 0 { -phase } else { v }
+                }
+            }
+        }
+    }
+
+    // Moves a point along the freedom vector,
+    // so its projection is changed by `distance`.
+    fn move_point(&mut self, zone: usize, point: usize, distance: i32) {
+        let fv = self.gs.freedom_vector;
+        let dx = mul_div(distance, fv.x, self.gs.fdotp);
+        let dy = mul_div(distance, fv.y, self.gs.fdotp);
+        self.move_by(zone, point, dx, dy, true);
+    }
+
+    fn move_by(&mut self, zone: usize, point: usize, dx: i32, dy: i32, touch: bool) {
+        let fv = self.gs.freedom_vector;
+        let move_x = !self.backward_compatibility;
+        let move_y = !(self.backward_compatibility && self.iupx_called && self.iupy_called);
+        let zone = &mut self.zones[zone];
+        if point >= zone.len() {
+            return;
+        }
+
+        if fv.x != 0 {
+            if move_x {
+                zone.cur[point].x = zone.cur[point].x.wrapping_add(dx);
+            }
+
+            if touch {
+                zone.flags[point] |= point_flag::TOUCHED_X;
+            }
+        }
+
+        if fv.y != 0 {
+            if move_y {
+                zone.cur[point].y = zone.cur[point].y.wrapping_add(dy);
+            }
+
+            if touch {
+                zone.flags[point] |= point_flag::TOUCHED_Y;
+            }
+        }
+    }
+
+    fn move_original(&mut self, zone: usize, point: usize, distance: i32) {
+        let fv = self.gs.freedom_vector;
+        let dx = mul_div(distance, fv.x, self.gs.fdotp);
+        let dy = mul_div(distance, fv.y, self.gs.fdotp);
+        if let Some(p) = self.zones[zone].org.get_mut(point) {
+            *p = p.add(Vector::new(dx, dy));
+        }
+    }
+
+    #[inline]
+    fn minimum_distance(&self, org_dist: i32, distance: i32) -> i32 {
+        let min = self.gs.minimum_distance;
+        if org_dist >= 0 {
+            distance.max(min)
+        } else {
+            distance.min(min.wrapping_neg())
+        }
+    }
+
+    #[inline]
+    fn single_width(&self, distance: i32) -> i32 {
+        let value = self.gs.single_width_value;
+        if distance.wrapping_sub(value).wrapping_abs() < self.gs.single_width_cutin {
+            if distance >= 0 { value } else { value.wrapping_neg() }
+        } else {
+            distance
+        }
+    }
+
+    // Bounds checked variants of operations return `None` on invalid point references.
+    // Like FreeType, such instructions are ignored.
+
+    fn coordinate(&self, p: i32, original: bool) -> Option<i32> {
+        let zone = &self.zones[self.gs.zp2];
+        let p = zone.point(p)?;
+        if original {
+            Some(self.dual_project(zone.org[p]))
+        } else {
+            Some(self.project(zone.cur[p]))
+        }
+    }
+
+    fn scfs(&mut self, p: i32, k: i32) -> Option<()> {
+        let zone = self.gs.zp2;
+        let p = self.zones[zone].point(p)?;
+        let distance = k.wrapping_sub(self.project(self.zones[zone].cur[p]));
+        self.move_point(zone, p, distance);
+
+        if zone == TWILIGHT_ZONE {
+            let z = &mut self.zones[zone];
+            z.org[p] = z.cur[p];
+        }
+
+        Some(())
+    }
+
+    fn measure_distance(&self, l: i32, k: i32, original: bool) -> Option<i32> {
+        let (z0, z1) = (&self.zones[self.gs.zp0], &self.zones[self.gs.zp1]);
+        let (l, k) = (z0.point(l)?, z1.point(k)?);
+        let d = if !original {
+            self.project(z0.cur[l].sub(z1.cur[k]))
+        } else if self.gs.zp0 == TWILIGHT_ZONE || self.gs.zp1 == TWILIGHT_ZONE {
+            self.dual_project(z0.org[l].sub(z1.org[k]))
+        } else {
+            mul_fix(self.dual_project(z0.orus[l].sub(z1.orus[k])), self.orus_scale)
+        };
+
+        Some(d)
+    }
+
+    fn isect(&mut self, point: i32, a0: i32, a1: i32, b0: i32, b1: i32) -> Option<()> {
+        let za = &self.zones[self.gs.zp1];
+        let zb = &self.zones[self.gs.zp0];
+        let a0 = za.cur[za.point(a0)?];
+        let a1 = za.cur[za.point(a1)?];
+        let b0 = zb.cur[zb.point(b0)?];
+        let b1 = zb.cur[zb.point(b1)?];
+        let point = self.zones[self.gs.zp2].point(point)?;
+
+        let (dax, day) = (i64::from(a1.x) - i64::from(a0.x), i64::from(a1.y) - i64::from(a0.y));
+        let (dbx, dby) = (i64::from(b1.x) - i64::from(b0.x), i64::from(b1.y) - i64::from(b0.y));
+        let (dx, dy) = (i64::from(b0.x) - i64::from(a0.x), i64::from(b0.y) - i64::from(a0.y));
+
+        let discriminant = dax.wrapping_mul(-dby) / 64 + day.wrapping_mul(dbx) / 64;
+        let dot_product = dax.wrapping_mul(dbx) / 64 + day.wrapping_mul(dby) / 64;
+
+        // Reject nearly parallel lines, like FreeType does.
+        let p = if 19 * discriminant.abs() > dot_product.abs() {
+            let v = dx.wrapping_mul(-dby) / 64 + dy.wrapping_mul(dbx) / 64;
+            let x = v.wrapping_mul(dax) / discriminant;
+            let y = v.wrapping_mul(day) / discriminant;
+            Vector::new(a0.x.wrapping_add(x as i32), a0.y.wrapping_add(y as i32))
+        } else {
+            // Use the middle of the middles.
+            let x = (i64::from(a0.x) + i64::from(a1.x) + i64::from(b0.x) + i64::from(b1.x)) / 4;
+            let y = (i64::from(a0.y) + i64::from(a1.y) + i64::from(b0.y) + i64::from(b1.y)) / 4;
+            Vector::new(x as i32, y as i32)
+        };
+
+        let zone = &mut self.zones[self.gs.zp2];
+        zone.cur[point] = p;
+        zone.flags[point] |= point_flag::TOUCHED_X | point_flag::TOUCHED_Y;
+        Some(())
+    }
+
+    fn mdap(&mut self, p: i32, round: bool) -> Option<()> {
+        let zone = self.gs.zp0;
+        let p = self.zones[zone].point(p)?;
+        let distance = if round {
+            let d = self.project(self.zones[zone].cur[p]);
+            self.round(d).wrapping_sub(d)
+        } else {
+            0
+        };
+
+        self.move_point(zone, p, distance);
+        self.gs.rp0 = p;
+        self.gs.rp1 = p;
+        Some(())
+    }
+
+    fn miap(&mut self, p: i32, cvt_index: i32, round: bool) -> Option<()> {
+        let zone = self.gs.zp0;
+        let p = self.zones[zone].point(p)?;
+        let mut distance = self.read_cvt(cvt_index);
+
+        if zone == TWILIGHT_ZONE {
+            let fv = self.gs.freedom_vector;
+            let v = Vector::new(mul_14(distance, fv.x), mul_14(distance, fv.y));
+            let z = &mut self.zones[zone];
+            z.org[p] = v;
+            z.cur[p] = v;
+        }
+
+        let org_dist = self.project(self.zones[zone].cur[p]);
+        if round {
+            if distance.wrapping_sub(org_dist).wrapping_abs() > self.gs.control_value_cutin {
+                distance = org_dist;
+            }
+
+            distance = self.round(distance);
+        }
+
+        self.move_point(zone, p, distance.wrapping_sub(org_dist));
+        self.gs.rp0 = p;
+        self.gs.rp1 = p;
+        Some(())
+    }
+
+    fn msirp(&mut self, p: i32, d: i32, set_rp0: bool) -> Option<()> {
+        let (z0, z1) = (self.gs.zp0, self.gs.zp1);
+        let p = self.zones[z1].point(p)?;
+        let rp0 = self.gs.rp0;
+        if rp0 >= self.zones[z0].len() {
+            return None;
+        }
+
+        if z1 == TWILIGHT_ZONE {
+            self.zones[z1].org[p] = self.zones[z0].org[rp0];
+            self.move_original(z1, p, d);
+            let z = &mut self.zones[z1];
+            z.cur[p] = z.org[p];
+        }
+
+        let distance = self.project(self.zones[z1].cur[p].sub(self.zones[z0].cur[rp0]));
+        self.move_point(z1, p, d.wrapping_sub(distance));
+
+        self.gs.rp1 = rp0;
+        self.gs.rp2 = p;
+        if set_rp0 {
+            self.gs.rp0 = p;
+        }
+
+        Some(())
+    }
+
+    fn mdrp(&mut self, p: i32, op: u8) -> Option<()> {
+        let (z0, z1) = (self.gs.zp0, self.gs.zp1);
+        let p = self.zones[z1].point(p)?;
+        let rp0 = self.gs.rp0;
+        if rp0 >= self.zones[z0].len() {
+            return None;
+        }
+
+        let org_dist = if z0 == TWILIGHT_ZONE || z1 == TWILIGHT_ZONE {
+            self.dual_project(self.zones[z1].org[p].sub(self.zones[z0].org[rp0]))
+        } else {
+            let d = self.dual_project(self.zones[z1].orus[p].sub(self.zones[z0].orus[rp0]));
+            mul_fix(d, self.orus_scale)
+        };
+        let org_dist = self.single_width(org_dist);
+
+        let mut distance = if op & 4 != 0 { self.round(org_dist) } else { org_dist };
+        if op & 8 != 0 {
+            distance = self.minimum_distance(org_dist, distance);
+        }
+
+        let cur_dist = self.project(self.zones[z1].cur[p].sub(self.zones[z0].cur[rp0]));
+        self.move_point(z1, p, distance.wrapping_sub(cur_dist));
+
+        self.gs.rp1 = rp0;
+        self.gs.rp2 = p;
+        if op & 16 != 0 {
+            self.gs.rp0 = p;
+        }
+
+        Some(())
+    }
+
+    fn mirp(&mut self, p: i32, cvt_index: i32, op: u8) -> Option<()> {
+        let (z0, z1) = (self.gs.zp0, self.gs.zp1);
+        let p = self.zones[z1].point(p)?;
+        let rp0 = self.gs.rp0;
+        if rp0 >= self.zones[z0].len() {
+            return None;
+        }
+
+        // Like FreeType, the -1 index refers to a zero value.
+        let cvt_dist = if cvt_index == -1 { 0 } else { self.read_cvt(cvt_index) };
+        let mut cvt_dist = self.single_width(cvt_dist);
+
+        if z1 == TWILIGHT_ZONE {
+            let fv = self.gs.freedom_vector;
+            let v = self.zones[z0].org[rp0]
+                .add(Vector::new(mul_14(cvt_dist, fv.x), mul_14(cvt_dist, fv.y)));
+            let z = &mut self.zones[z1];
+            z.org[p] = v;
+            z.cur[p] = v;
+        }
+
+        let org_dist = self.dual_project(self.zones[z1].org[p].sub(self.zones[z0].org[rp0]));
+        let cur_dist = self.project(self.zones[z1].cur[p].sub(self.zones[z0].cur[rp0]));
+
+        if self.gs.auto_flip && (org_dist ^ cvt_dist) < 0 {
+            cvt_dist = cvt_dist.wrapping_neg();
+        }
+
+        let mut distance = if op & 4 != 0 {
+            // The cut-in test is performed only when both points are in the same zone.
+            if z0 == z1 && cvt_dist.wrapping_sub(org_dist).wrapping_abs() > self.gs.control_value_cutin {
+                cvt_dist = org_dist;
+            }
+
+            self.round(cvt_dist)
+        } else {
+            cvt_dist
+        };
+
+        if op & 8 != 0 {
+            distance = self.minimum_distance(org_dist, distance);
+        }
+
+        self.move_point(z1, p, distance.wrapping_sub(cur_dist));
+
+        self.gs.rp1 = rp0;
+        self.gs.rp2 = p;
+        if op & 16 != 0 {
+            self.gs.rp0 = p;
+        }
+
+        Some(())
+    }
+
+    fn align_rp(&mut self) -> Option<()> {
+        let (z0, z1) = (self.gs.zp0, self.gs.zp1);
+        let rp0 = self.gs.rp0;
+        for _ in 0..self.take_loop() {
+            let p = self.pop()?;
+            if let (Some(p), true) = (self.zones[z1].point(p), rp0 < self.zones[z0].len()) {
+                let distance = self.project(self.zones[z1].cur[p].sub(self.zones[z0].cur[rp0]));
+                self.move_point(z1, p, distance.wrapping_neg());
+            }
+        }
+
+        Some(())
+    }
+
+    fn align_points(&mut self, p1: i32, p2: i32) -> Option<()> {
+        let (z0, z1) = (self.gs.zp0, self.gs.zp1);
+        let p1 = self.zones[z1].point(p1)?;
+        let p2 = self.zones[z0].point(p2)?;
+        let distance = self.project(self.zones[z0].cur[p2].sub(self.zones[z1].cur[p1])) / 2;
+        self.move_point(z1, p1, distance);
+        self.move_point(z0, p2, distance.wrapping_neg());
+        Some(())
+    }
+
+    fn interpolate_points(&mut self) -> Option<()> {
+        let (z0, z1, z2) = (self.gs.zp0, self.gs.zp1, self.gs.zp2);
+        let (rp1, rp2) = (self.gs.rp1, self.gs.rp2);
+        let count = self.take_loop();
+
+        if rp1 >= self.zones[z0].len() {
+            for _ in 0..count {
+                self.pop()?;
+            }
+
+            return Some(());
+        }
+
+        // Twilight points do not have unscaled coordinates.
+        let twilight = z0 == TWILIGHT_ZONE || z1 == TWILIGHT_ZONE || z2 == TWILIGHT_ZONE;
+        let original = |zone: &Zone, p: usize| if twilight { zone.org[p] } else { zone.orus[p] };
+
+        let orus_base = original(&self.zones[z0], rp1);
+        let cur_base = self.zones[z0].cur[rp1];
+        let (old_range, cur_range) = if rp2 < self.zones[z1].len() {
+            (
+                self.dual_project(original(&self.zones[z1], rp2).sub(orus_base)),
+                self.project(self.zones[z1].cur[rp2].sub(cur_base)),
+            )
+        } else {
+            (0, 0)
+        };
+
+        for _ in 0..count {
+            let p = self.pop()?;
+            let p = match self.zones[z2].point(p) {
+                Some(p) => p,
+                None => continue,
+            };
+
+            let org_dist = self.dual_project(original(&self.zones[z2], p).sub(orus_base));
+            let cur_dist = self.project(self.zones[z2].cur[p].sub(cur_base));
+            let new_dist = if org_dist == 0 {
+                0
+            } else if old_range != 0 {
+                mul_div(org_dist, cur_range, old_range)
+            } else {
+                org_dist
+            };
+
+            self.move_point(z2, p, new_dist.wrapping_sub(cur_dist));
+        }
+
+        Some(())
+    }
+
+    fn interpolate_untouched(&mut self, x_axis: bool) {
+        if self.backward_compatibility {
+            if self.iupx_called && self.iupy_called {
+                return;
+            }
+
+            if x_axis {
+                self.iupx_called = true;
+            } else {
+                self.iupy_called = true;
+            }
+        }
+
+        self.charge(self.zones[GLYPH_ZONE].len());
+
+        let mask = if x_axis { point_flag::TOUCHED_X } else { point_flag::TOUCHED_Y };
+        let zone = &mut self.zones[GLYPH_ZONE];
+        if zone.len() == 0 {
+            return;
+        }
+
+        let mut point = 0;
+        let mut contour = 0;
+        while contour < zone.contours.len() {
+            let end_point = usize::from(zone.contours[contour]).min(zone.len() - 1);
+            let first_point = point;
+
+            while point <= end_point && zone.flags[point] & mask == 0 {
+                point += 1;
+            }
+
+            if point <= end_point {
+                let first_touched = point;
+                let mut cur_touched = point;
+                point += 1;
+
+                while point <= end_point {
+                    if zone.flags[point] & mask != 0 {
+                        iup_interpolate(zone, x_axis, cur_touched + 1, point - 1, cur_touched, point);
+                        cur_touched = point;
+                    }
+
+                    point += 1;
+                }
+
+                if cur_touched == first_touched {
+                    iup_shift(zone, x_axis, first_point, end_point, cur_touched);
+                } else {
+                    iup_interpolate(zone, x_axis, cur_touched + 1, end_point, cur_touched, first_touched);
+                    if first_touched > 0 {
+                        iup_interpolate(zone, x_axis, first_point, first_touched - 1,
+                                        cur_touched, first_touched);
+                    }
+                }
+            }
+
+            contour += 1;
+        }
+    }
+
+    fn untouch(&mut self, p: i32) -> Option<()> {
+        let fv = self.gs.freedom_vector;
+        let zone = &mut self.zones[self.gs.zp0];
+        let p = zone.point(p)?;
+        if fv.x != 0 {
+            zone.flags[p] &= !point_flag::TOUCHED_X;
+        }
+
+        if fv.y != 0 {
+            zone.flags[p] &= !point_flag::TOUCHED_Y;
+        }
+
+        Some(())
+    }
+
+    // Returns the reference point displacement along the freedom vector.
+    fn displacement(&self, op: u8) -> Option<(Vector, usize, usize)> {
+        let (zone, rp) = if op & 1 != 0 {
+            (self.gs.zp0, self.gs.rp1)
+        } else {
+            (self.gs.zp1, self.gs.rp2)
+        };
+
+        let z = &self.zones[zone];
+        if rp >= z.len() {
+            return None;
+        }
+
+        let d = self.project(z.cur[rp].sub(z.org[rp]));
+        let fv = self.gs.freedom_vector;
+        let v = Vector::new(mul_div(d, fv.x, self.gs.fdotp), mul_div(d, fv.y, self.gs.fdotp));
+        Some((v, zone, rp))
+    }
+
+    fn shift_points(&mut self, op: u8) -> Option<()> {
+        let displacement = self.displacement(op);
+        let zone = self.gs.zp2;
+        for _ in 0..self.take_loop() {
+            let p = self.pop()?;
+            if let (Some((d, _, _)), Some(p)) = (displacement, self.zones[zone].point(p)) {
+                self.move_by(zone, p, d.x, d.y, true);
+            }
+        }
+
+        Some(())
+    }
+
+    fn shift_contour(&mut self, contour: i32, op: u8) -> Option<()> {
+        let zone = self.gs.zp2;
+        let (d, ref_zone, rp) = self.displacement(op)?;
+        let contour = usize::try_from(contour).ok()?;
+
+        let z = &self.zones[zone];
+        let (start, end) = if zone == TWILIGHT_ZONE {
+            // The twilight zone is a single contour.
+            if contour != 0 {
+                return None;
+            }
+
+            (0, z.len())
+        } else {
+            let end = usize::from(*z.contours.get(contour)?) + 1;
+            let start = match contour.checked_sub(1) {
+                Some(prev) => usize::from(z.contours[prev]) + 1,
+                None => 0,
+            };
+
+            (start, end.min(z.len()))
+        };
+
+        self.charge(end.saturating_sub(start));
+        for i in start..end {
+            if ref_zone != zone || rp != i {
+                self.move_by(zone, i, d.x, d.y, true);
+            }
+        }
+
+        Some(())
+    }
+
+    fn shift_zone(&mut self, _zone: usize, op: u8) -> Option<()> {
+        // Like FreeType, `zp2` is used instead of the specified zone.
+        let zone = self.gs.zp2;
+        let (d, ref_zone, rp) = self.displacement(op)?;
+
+        // Phantom points are not affected.
+        let z = &self.zones[zone];
+        let end = if zone == TWILIGHT_ZONE {
+            z.len()
+        } else {
+            z.contours.last().map(|end| usize::from(*end) + 1).unwrap_or(0).min(z.len())
+        };
+
+        self.charge(end);
+        for i in 0..end {
+            if ref_zone != zone || rp != i {
+                self.move_by(zone, i, d.x, d.y, false);
+            }
+        }
+
+        Some(())
+    }
+
+    fn shift_points_by_pixels(&mut self) -> Option<()> {
+        let d = self.pop()?;
+        let fv = self.gs.freedom_vector;
+        let (dx, dy) = (mul_14(d, fv.x), mul_14(d, fv.y));
+        let zone = self.gs.zp2;
+        let in_twilight = self.gs.zp0 == TWILIGHT_ZONE
+            && self.gs.zp1 == TWILIGHT_ZONE
+            && self.gs.zp2 == TWILIGHT_ZONE;
+
+        for _ in 0..self.take_loop() {
+            let p = self.pop()?;
+            let p = match self.zones[zone].point(p) {
+                Some(p) => p,
+                None => continue,
+            };
+
+            if self.backward_compatibility {
+                // Like FreeType, treat it like `DELTAP`, except in the twilight zone.
+                if in_twilight || self.is_delta_allowed(zone, p) {
+                    self.move_by(zone, p, 0, dy, true);
+                }
+            } else {
+                self.move_by(zone, p, dx, dy, true);
+            }
+        }
+
+        Some(())
+    }
+
+    // Checks that a delta can be applied in the backward compatibility mode.
+    #[inline]
+    fn is_delta_allowed(&self, zone: usize, p: usize) -> bool {
+        let touched_y = self.zones[zone].flags[p] & point_flag::TOUCHED_Y != 0;
+        !(self.iupx_called && self.iupy_called)
+            && ((self.is_composite && self.gs.freedom_vector.y != 0) || touched_y)
+    }
+
+    // Returns a delta in 26.6 when it should be applied at the current ppem.
+    fn delta_value(&self, arg: i32, range_start: i32) -> Option<i32> {
+        let ppem = ((arg & 0xF0) >> 4) + range_start;
+        if ppem.wrapping_add(self.gs.delta_base) != self.ppem {
+            return None;
+        }
+
+        // Steps are in the -8..=8 range, without zero.
+        let mut steps = (arg & 0xF) - 8;
+        if steps >= 0 {
+            steps += 1;
+        }
+
+        Some(steps * (1 << (6 - self.gs.delta_shift)))
+    }
+
+    fn delta_point(&mut self, op: u8) -> Option<()> {
+        let range_start = match op {
+            opcode::DELTAP1 => 0,
+            opcode::DELTAP2 => 16,
+            _ => 32,
+        };
+
+        let zone = self.gs.zp0;
+        let n = self.pop()?;
+        for _ in 0..n {
+            let p = self.pop()?;
+            let arg = self.pop()?;
+            let p = match self.zones[zone].point(p) {
+                Some(p) => p,
+                None => continue,
+            };
+
+            if let Some(delta) = self.delta_value(arg, range_start) {
+                if !self.backward_compatibility || self.is_delta_allowed(zone, p) {
+                    self.move_point(zone, p, delta);
+                }
+            }
+        }
+
+        Some(())
+    }
+
+    fn delta_cvt(&mut self, op: u8) -> Option<()> {
+        let range_start = match op {
+            opcode::DELTAC1 => 0,
+            opcode::DELTAC2 => 16,
+            _ => 32,
+        };
+
+        let n = self.pop()?;
+        for _ in 0..n {
+            let index = self.pop()?;
+            let arg = self.pop()?;
+            if let Some(delta) = self.delta_value(arg, range_start) {
+                if let Some(v) = usize::try_from(index).ok().and_then(|i| self.cvt.get_mut(i)) {
+                    *v = v.wrapping_add(delta);
+                }
+            }
+        }
+
+        Some(())
+    }
+
+    #[inline]
+    fn is_outline_frozen(&self) -> bool {
+        self.backward_compatibility && self.iupx_called && self.iupy_called
+    }
+
+    fn flip_points(&mut self) -> Option<()> {
+        let frozen = self.is_outline_frozen();
+        let zone = &mut self.zones[self.gs.zp0];
+        for _ in 0..self.gs.loop_value {
+            let p = self.stack.pop()?;
+            if let (Some(p), false) = (zone.point(p), frozen) {
+                zone.flags[p] ^= point_flag::ON_CURVE;
+            }
+        }
+
+        self.gs.loop_value = 1;
+        Some(())
+    }
+
+    /// Charges the execution budget for an instruction that walks `points` points.
+    ///
+    /// An exhausted budget makes the next instruction fail.
+    #[inline]
+    fn charge(&mut self, points: usize) {
+        self.budget = u32::try_from(points).ok()
+            .and_then(|n| self.budget.checked_sub(n))
+            .unwrap_or(0);
+    }
+
+    /// Charges the execution budget for loading a glyph.
+    ///
+    /// Returns `None` when the budget is exhausted.
+    #[inline]
+    fn try_charge(&mut self, points: usize) -> Option<()> {
+        self.budget = self.budget.checked_sub(u32::try_from(points).ok()?)?;
+        Some(())
+    }
+
+    fn flip_range(&mut self, lo: i32, hi: i32, on_curve: bool) -> Option<()> {
+        if self.is_outline_frozen() {
+            return Some(());
+        }
+
+        let zone = &self.zones[self.gs.zp0];
+        let (lo, hi) = (zone.point(lo)?, zone.point(hi)?);
+        self.charge((hi + 1).saturating_sub(lo));
+        let zone = &mut self.zones[self.gs.zp0];
+        for flags in zone.flags.get_mut(lo..=hi)? {
+            if on_curve {
+                *flags |= point_flag::ON_CURVE;
+            } else {
+                *flags &= !point_flag::ON_CURVE;
+            }
+        }
+
+        Some(())
+    }
+}
+
+#[inline]
+fn axis_vector(op: u8) -> Vector {
+    if op & 1 != 0 { X_AXIS } else { Y_AXIS }
+}
+
+#[inline]
+fn zone_index(n: i32) -> Option<usize> {
+    match n {
+        0 => Some(TWILIGHT_ZONE),
+        1 => Some(GLYPH_ZONE),
+        _ => None,
+    }
+}
+
+// Returns an offset of the jump target.
+fn jump(code: &[u8], offset: usize, n: i32) -> Option<usize> {
+    // A zero jump is an infinite loop.
+    if n == 0 {
+        return None;
+    }
+
+    let target = i64::try_from(offset).ok()? + i64::from(n);
+    let target = usize::try_from(target).ok()?;
+    if target <= code.len() { Some(target) } else { None }
+}
+
+// Returns an offset after the matching `ELSE` or `EIF`.
+fn skip_branch(code: &[u8], mut offset: usize, stop_at_else: bool) -> Option<usize> {
+    let mut depth = 0u32;
+    loop {
+        let (instruction, next_offset) = instructions::decode(code, offset)?;
+        match instruction.opcode {
+            opcode::IF => depth += 1,
+            opcode::ELSE if stop_at_else && depth == 0 => return Some(next_offset),
+            opcode::EIF => {
+                if depth == 0 {
+                    return Some(next_offset);
+                }
+
+                depth -= 1;
+            }
+            _ => {}
+        }
+
+        offset = next_offset;
+    }
+}
+
+// Returns an offset after the matching `ENDF`.
+fn skip_definition(code: &[u8], mut offset: usize) -> Option<usize> {
+    loop {
+        let (instruction, next_offset) = instructions::decode(code, offset)?;
+        match instruction.opcode {
+            opcode::ENDF => return Some(next_offset),
+            // Nested definitions are not allowed.
+            opcode::FDEF | opcode::IDEF => return None,
+            _ => {}
+        }
+
+        offset = next_offset;
+    }
+}
+
+fn iup_interpolate(
+    zone: &mut Zone,
+    x_axis: bool,
+    p1: usize,
+    p2: usize,
+    mut ref1: usize,
+    mut ref2: usize,
+) {
+    if p1 > p2 || p2 >= zone.len() || ref1 >= zone.len() || ref2 >= zone.len() {
+        return;
+    }
+
+    let mut orus1 = zone.orus[ref1].get(x_axis);
+    let mut orus2 = zone.orus[ref2].get(x_axis);
+    if orus1 > orus2 {
+        core::mem::swap(&mut orus1, &mut orus2);
+        core::mem::swap(&mut ref1, &mut ref2);
+    }
+
+    let (org1, org2) = (zone.org[ref1].get(x_axis), zone.org[ref2].get(x_axis));
+    let (cur1, cur2) = (zone.cur[ref1].get(x_axis), zone.cur[ref2].get(x_axis));
+    let delta1 = cur1.wrapping_sub(org1);
+    let delta2 = cur2.wrapping_sub(org2);
+
+    let scale = if cur1 == cur2 || orus1 == orus2 {
+        None
+    } else {
+        Some(div_fix(cur2.wrapping_sub(cur1), orus2.wrapping_sub(orus1)))
+    };
+
+    for ((cur, org), orus) in zone.cur[p1..=p2].iter_mut()
+        .zip(&zone.org[p1..=p2])
+        .zip(&zone.orus[p1..=p2])
+    {
+        let x = org.get(x_axis);
+        let x = if x <= org1 {
+            x.wrapping_add(delta1)
+        } else if x >= org2 {
+            x.wrapping_add(delta2)
+        } else if let Some(scale) = scale {
+            cur1.wrapping_add(mul_fix(orus.get(x_axis).wrapping_sub(orus1), scale))
+        } else {
+            cur1
+        };
+
+        cur.set(x_axis, x);
+    }
+}
+
+fn iup_shift(zone: &mut Zone, x_axis: bool, p1: usize, p2: usize, p: usize) {
+    if p1 > p2 || p2 >= zone.len() || p >= zone.len() {
+        return;
+    }
+
+    let delta = zone.cur[p].get(x_axis).wrapping_sub(zone.org[p].get(x_axis));
+    if delta == 0 {
+        return;
+    }
+
+    for (i, cur) in zone.cur[p1..=p2].iter_mut().enumerate() {
+        if p1 + i != p {
+            let x = cur.get(x_axis).wrapping_add(delta);
+            cur.set(x_axis, x);
+        }
+    }
+}
+
+fn round_phantom_points(zone: &mut Zone) {
+    let len = zone.cur.len();
+    if len < PHANTOM_POINTS {
+        return;
+    }
+
+    let points = &mut zone.cur[len - PHANTOM_POINTS..];
+    points[0].x = round_to_pixel(points[0].x);
+    points[1].x = round_to_pixel(points[1].x);
+    points[2].y = round_to_pixel(points[2].y);
+    points[3].y = round_to_pixel(points[3].y);
+}
+
+// Rounds an absolute value and restores the sign. The sign cannot be changed by rounding.
+#[inline]
+fn round_with(d: i32, f: impl Fn(i32) -> i32) -> i32 {
+    if d >= 0 {
+        f(d).max(0)
+    } else {
+        f(d.wrapping_neg()).wrapping_neg().min(0)
+    }
+}
+
+#[inline]
+fn round_to_pixel(v: i32) -> i32 {
+    v.wrapping_add(32) & !63
+}
+
+// We can't use `round()` in `no_std`.
+#[inline]
+fn round_f32(v: f32) -> i32 {
+    if v >= 0.0 { (v + 0.5) as i32 } else { (v - 0.5) as i32 }
+}
+
+#[inline]
+fn to_fixed(v: f32) -> i32 {
+    round_f32(v * ONE_16DOT16 as f32)
+}
+
+#[inline]
+fn f26dot6_to_f32(v: i32) -> f32 {
+    v as f32 / 64.0
+}
+
+#[inline]
+fn scale_vector(v: Vector, scale: i32) -> Vector {
+    Vector::new(mul_fix(v.x, scale), mul_fix(v.y, scale))
+}
+
+// Computes `a * b / c` with rounding.
+#[inline]
+fn mul_div(a: i32, b: i32, c: i32) -> i32 {
+    if c == 0 {
+        return 0;
+    }
+
+    let (a, b, c) = (i64::from(a), i64::from(b), i64::from(c));
+    let v = ((a * b).abs() + c.abs() / 2) / c.abs();
+    (if (a < 0) ^ (b < 0) ^ (c < 0) { -v } else { v }) as i32
+}
+
+#[inline]
+fn mul_div_no_round(a: i32, b: i32, c: i32) -> i32 {
+    if c == 0 {
+        return 0;
+    }
+
+    let (a, b, c) = (i64::from(a), i64::from(b), i64::from(c));
+    let v = (a * b).abs() / c.abs();
+    (if (a < 0) ^ (b < 0) ^ (c < 0) { -v } else { v }) as i32
+}
+
+#[inline]
+fn mul_fix(a: i32, b: i32) -> i32 {
+    mul_div(a, b, ONE_16DOT16)
+}
+
+#[inline]
+fn div_fix(a: i32, b: i32) -> i32 {
+    mul_div(a, ONE_16DOT16, b)
+}
+
+#[inline]
+fn mul_14(a: i32, b: i32) -> i32 {
+    mul_div(a, b, ONE_2DOT14)
+}
+
+#[inline]
+fn dot14(a: Vector, b: Vector) -> i32 {
+    let v = i64::from(a.x) * i64::from(b.x) + i64::from(a.y) * i64::from(b.y);
+    ((v + 0x2000) >> 14) as i32
+}
+
+// Returns a unit vector in 2.14.
+fn normalize(v: Vector) -> Vector {
+    let (mut x, mut y) = (i64::from(v.x), i64::from(v.y));
+    if x == 0 && y == 0 {
+        return X_AXIS;
+    }
+
+    // Scale the vector, so the square root is precise enough.
+    while x.abs().max(y.abs()) >= 0x10000 {
+        x /= 2;
+        y /= 2;
+    }
+
+    while x.abs().max(y.abs()) < 0x8000 {
+        x *= 2;
+        y *= 2;
+    }
+
+    let len = isqrt((x * x + y * y) as u64) as i64;
+    let div = |v: i64| {
+        let r = (v.abs() * i64::from(ONE_2DOT14) + len / 2) / len;
+        (if v < 0 { -r } else { r }) as i32
+    };
+
+    Vector::new(div(x), div(y))
+}
+
+fn isqrt(n: u64) -> u64 {
+    if n == 0 {
+        return 0;
+    }
+
+    let mut x = n;
+    let mut y = x / 2 + x % 2;
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+
+    x
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec::Vec;
+    use std::string::String;
+    use std::fmt::Write;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    struct Builder(String);
+
+    impl OutlineBuilder for Builder {
+        fn move_to(&mut self, x: f32, y: f32) {
+            write!(&mut self.0, "M {} {} ", x, y).unwrap();
+        }
+
+        fn line_to(&mut self, x: f32, y: f32) {
+            write!(&mut self.0, "L {} {} ", x, y).unwrap();
+        }
+
+        fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+            write!(&mut self.0, "Q {} {} {} {} ", x1, y1, x, y).unwrap();
+        }
+
+        fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+            write!(&mut self.0, "C {} {} {} {} {} {} ", x1, y1, x2, y2, x, y).unwrap();
+        }
+
+        fn close(&mut self) {
+            write!(&mut self.0, "Z ").unwrap();
+        }
+    }
+
+    // Glyph 0 is a rectangle with the specified instructions
+    // and glyph 1 is a composite glyph with glyph 0 shifted by 60 units in both directions.
+    // Units per em are 1000, so at 10 ppem a unit is 1/100 of a pixel.
+    fn font_data(instructions: &[u8], extra_tables: &[(&[u8; 4], Vec<u8>)]) -> Vec<u8> {
+        let mut glyph = writer::convert(&[
+            Int16(1), // number of contours
+            Int16(125), Int16(0), Int16(450), Int16(725), // bbox
+            UInt16(3), // end points
+            UInt16(instructions.len() as u16), // instructions length
+        ]);
+        glyph.extend_from_slice(instructions);
+        glyph.extend_from_slice(&writer::convert(&[
+            UInt8(1), UInt8(1), UInt8(1), UInt8(1), // flags
+            Int16(125), Int16(0), Int16(325), Int16(0), // x coordinates
+            Int16(0), Int16(725), Int16(0), Int16(-725), // y coordinates
+        ]));
+
+        font_data_with_glyph(&glyph, extra_tables)
+    }
+
+    // Builds a font where glyph 0 is `glyph` and glyph 1 is a composite referencing it.
+    fn font_data_with_glyph(glyph: &[u8], extra_tables: &[(&[u8; 4], Vec<u8>)]) -> Vec<u8> {
+        let composite = writer::convert(&[
+            Int16(-1), // number of contours
+            Int16(185), Int16(60), Int16(510), Int16(785), // bbox
+            UInt16(0x0001 | 0x0002 | 0x0004), // flags
+            UInt16(0), // glyph ID
+            Int16(60), Int16(60), // offset
+        ]);
+
+        font_data_with_glyphs(&[glyph.to_vec(), composite], extra_tables)
+    }
+
+    fn font_data_with_glyphs(glyphs: &[Vec<u8>], extra_tables: &[(&[u8; 4], Vec<u8>)]) -> Vec<u8> {
+        let head = writer::convert(&[
+            UInt16(1), UInt16(0), // version
+            UInt32(0), // font revision
+            UInt32(0), // checksum adjustment
+            UInt32(0x5F0F3CF5), // magic number
+            UInt16(0), // flags
+            UInt16(1000), // units per em
+            UInt32(0), UInt32(0), // created
+            UInt32(0), UInt32(0), // modified
+            Int16(0), Int16(0), Int16(0), Int16(0), // bbox
+            UInt16(0), // mac style
+            UInt16(0), // lowest rec ppem
+            Int16(0), // font direction hint
+            Int16(1), // index to location format
+            Int16(0), // glyph data format
+        ]);
+
+        let hhea = writer::convert(&[
+            UInt16(1), UInt16(0), // version
+            Int16(800), // ascender
+            Int16(-200), // descender
+            Int16(0), // line gap
+            UInt16(600), // advance width max
+            Int16(0), Int16(0), Int16(0), // min lsb, min rsb, x max extent
+            Int16(1), Int16(0), Int16(0), // caret slope rise, run and offset
+            Int16(0), Int16(0), Int16(0), Int16(0), // reserved
+            Int16(0), // metric data format
+            UInt16(2), // number of h metrics
+        ]);
+
+        let maxp = writer::convert(&[
+            UInt32(0x00010000), // version
+            UInt16(glyphs.len() as u16), // number of glyphs
+            UInt16(4), // max points
+            UInt16(1), // max contours
+            UInt16(4), // max composite points
+            UInt16(1), // max composite contours
+            UInt16(2), // max zones
+            UInt16(4), // max twilight points
+            UInt16(8), // max storage
+            UInt16(8), // max function defs
+            UInt16(0), // max instruction defs
+            UInt16(32), // max stack elements
+            UInt16(64), // max size of instructions
+            UInt16(1), // max component elements
+            UInt16(1), // max component depth
+        ]);
+
+        let mut hmtx = writer::convert(&[
+            UInt16(600), Int16(125),
+            UInt16(600), Int16(185),
+        ]);
+        // Left side bearings of the remaining glyphs.
+        hmtx.resize(8 + glyphs.len().saturating_sub(2) * 2, 0);
+
+        let mut glyf = Vec::new();
+        let mut loca = writer::convert(&[UInt32(0)]);
+        for glyph in glyphs {
+            glyf.extend_from_slice(glyph);
+            loca.extend_from_slice(&writer::convert(&[UInt32(glyf.len() as u32)]));
+        }
+
+        let mut tables = vec![
+            (b"head", head),
+            (b"hhea", hhea),
+            (b"maxp", maxp),
+            (b"hmtx", hmtx),
+            (b"loca", loca),
+            (b"glyf", glyf),
+        ];
+        tables.extend_from_slice(extra_tables);
+
+        let mut data = writer::convert(&[
+            TrueTypeMagic,
+            UInt16(tables.len() as u16), // numTables
+            UInt16(0), // searchRange
+            UInt16(0), // entrySelector
+            UInt16(0), // rangeShift
+        ]);
+
+        let mut offset = data.len() + tables.len() * 16;
+        for (tag, table) in &tables {
+            data.extend_from_slice(*tag);
+            data.extend_from_slice(&writer::convert(&[
+                UInt32(0), // checksum
+                UInt32(offset as u32), // offset
+                UInt32(table.len() as u32), // length
+            ]));
+            offset += table.len();
+        }
+
+        for (_, table) in &tables {
+            data.extend_from_slice(table);
+        }
+
+        data
+    }
+
+    fn outline(data: &[u8], glyph_id: u16, mode: HintingMode) -> String {
+        let font = Font::from_data(data, 0).unwrap();
+        let mut instance = HintingInstance::new(&font, 10, mode).unwrap();
+        let mut builder = Builder(String::new());
+        instance.outline_glyph(GlyphId(glyph_id), &mut builder).unwrap();
+        builder.0
+    }
+
+    // Executes a glyph program and returns the stack.
+    fn execute(program: &[u8], mode: HintingMode) -> Option<Vec<i32>> {
+        let data = font_data(&[], &[]);
+        let font = Font::from_data(&data, 0).unwrap();
+        let mut instance = HintingInstance::new(&font, 10, mode).unwrap();
+        instance.exec.glyph_program = program;
+        instance.exec.budget = MAX_INSTRUCTIONS;
+        instance.exec.execute(Program::Glyph)?;
+        Some(instance.exec.stack.clone())
+    }
+
+    const UNHINTED: &str = "M 1.25 0 L 1.25 7.25 L 4.5 7.25 L 4.5 0 L 1.25 0 Z ";
+
+    // Rounds all points in both directions.
+    const ROUND_POINTS: &[u8] = &[
+        0x01, // SVTCA[x]
+        0xB3, 3, 2, 1, 0, // PUSHB[3]
+        0x2F, 0x2F, 0x2F, 0x2F, // MDAP[1] x4
+        0x00, // SVTCA[y]
+        0xB3, 3, 2, 1, 0, // PUSHB[3]
+        0x2F, 0x2F, 0x2F, 0x2F, // MDAP[1] x4
+    ];
+
+    #[test]
+    fn no_instructions() {
+        let data = font_data(&[], &[]);
+        assert_eq!(outline(&data, 0, HintingMode::Full), UNHINTED);
+    }
+
+    #[test]
+    fn round_points() {
+        let data = font_data(ROUND_POINTS, &[]);
+        assert_eq!(outline(&data, 0, HintingMode::Full),
+                   "M 1 0 L 1 7 L 5 7 L 5 0 L 1 0 Z ");
+    }
+
+    #[test]
+    fn round_points_subpixel() {
+        let data = font_data(ROUND_POINTS, &[]);
+        assert_eq!(outline(&data, 0, HintingMode::Subpixel),
+                   "M 1.25 0 L 1.25 7 L 4.5 7 L 4.5 0 L 1.25 0 Z ");
+    }
+
+    #[test]
+    fn interpolate_untouched() {
+        let data = font_data(&[
+            0x00, // SVTCA[y]
+            0xB1, 1, 0, // PUSHB[1]
+            0x2F, 0x2F, // MDAP[1] x2
+            0x30, // IUP[y]
+        ], &[]);
+        assert_eq!(outline(&data, 0, HintingMode::Full),
+                   "M 1.25 0 L 1.25 7 L 4.5 7 L 4.5 0 L 1.25 0 Z ");
+    }
+
+    const SHIFT_AFTER_IUP: &[u8] = &[
+        0x30, 0x31, // IUP[y], IUP[x]
+        0x00, // SVTCA[y]
+        0xB1, 2, 64, // PUSHB[1]
+        0x38, // SHPIX
+    ];
+
+    #[test]
+    fn shift_after_iup() {
+        let data = font_data(SHIFT_AFTER_IUP, &[]);
+        assert_eq!(outline(&data, 0, HintingMode::Full),
+                   "M 1.25 0 L 1.25 7.25 L 4.5 8.25 L 4.5 0 L 1.25 0 Z ");
+    }
+
+    #[test]
+    fn shift_after_iup_subpixel() {
+        // The outline is frozen after both IUP instructions.
+        let data = font_data(SHIFT_AFTER_IUP, &[]);
+        assert_eq!(outline(&data, 0, HintingMode::Subpixel), UNHINTED);
+    }
+
+    #[test]
+    fn function_with_cvt() {
+        let fpgm = vec![
+            0xB0, 0, // PUSHB[0]
+            0x2C, // FDEF
+            0x00, // SVTCA[y]
+            0xB1, 1, 0, // PUSHB[1]
+            0x3F, // MIAP[1]
+            0x2D, // ENDF
+        ];
+        let cvt = writer::convert(&[Int16(780)]);
+
+        let data = font_data(&[0xB0, 0, 0x2B], &[(b"fpgm", fpgm), (b"cvt ", cvt)]);
+        assert_eq!(outline(&data, 0, HintingMode::Full),
+                   "M 1.25 0 L 1.25 8 L 4.5 7.25 L 4.5 0 L 1.25 0 Z ");
+    }
+
+    #[test]
+    fn disabled_by_instruct_control() {
+        let prep = vec![
+            0xB1, 1, 1, // PUSHB[1]
+            0x8E, // INSTCTRL
+        ];
+
+        let data = font_data(ROUND_POINTS, &[(b"prep", prep)]);
+        let font = Font::from_data(&data, 0).unwrap();
+        assert!(!HintingInstance::new(&font, 10, HintingMode::Full).unwrap().is_enabled());
+        assert_eq!(outline(&data, 0, HintingMode::Full), UNHINTED);
+    }
+
+    #[test]
+    fn native_cleartype() {
+        // Fonts can disable the backward compatibility mode.
+        let prep = vec![
+            0xB1, 4, 3, // PUSHB[1]
+            0x8E, // INSTCTRL
+        ];
+
+        let data = font_data(ROUND_POINTS, &[(b"prep", prep)]);
+        assert_eq!(outline(&data, 0, HintingMode::Subpixel),
+                   "M 1 0 L 1 7 L 5 7 L 5 0 L 1 0 Z ");
+    }
+
+    #[test]
+    fn infinite_loop() {
+        let data = font_data(&[
+            0x00, // SVTCA[y]
+            0xB0, 1, // PUSHB[0]
+            0x2F, // MDAP[1]
+            0xB8, 0xFF, 0xFD, // PUSHW[0] -3
+            0x1C, // JMPR
+        ], &[]);
+
+        // Glyph program failures are ignored.
+        assert_eq!(outline(&data, 0, HintingMode::Full), UNHINTED);
+    }
+
+    #[test]
+    fn infinite_iup_loop() {
+        // A single contour of 10000 points, all at the origin.
+        let large_glyph = |instructions: &[u8]| {
+            let mut glyph = writer::convert(&[
+                Int16(1), // number of contours
+                Int16(0), Int16(0), Int16(0), Int16(0), // bbox
+                UInt16(9999), // end points
+                UInt16(instructions.len() as u16), // instructions length
+            ]);
+            glyph.extend_from_slice(instructions);
+            for _ in 0..39 {
+                glyph.extend_from_slice(&[0x39, 254]); // on curve, same x and y, repeated
+            }
+            glyph.extend_from_slice(&[0x39, 54]);
+            glyph
+        };
+
+        let data = font_data_with_glyph(&large_glyph(&[
+            0x30, // IUP[y]
+            0xB8, 0xFF, 0xFC, // PUSHW[0] -4
+            0x1C, // JMPR
+        ]), &[]);
+
+        // Every `IUP` walks all the points, so the loop must be stopped
+        // long before a million iterations.
+        let unhinted = font_data_with_glyph(&large_glyph(&[]), &[]);
+        assert_eq!(outline(&data, 0, HintingMode::Full), outline(&unhinted, 0, HintingMode::Full));
+    }
+
+    #[test]
+    fn component_fan_out() {
+        // Glyph 0 is a single point with an infinite loop. Glyphs 1..=16 are composite glyphs
+        // made of two copies of the previous one, so glyph 16 loads glyph 0 65536 times.
+        let mut glyphs = vec![writer::convert(&[
+            Int16(1), // number of contours
+            Int16(0), Int16(0), Int16(0), Int16(0), // bbox
+            UInt16(0), // end points
+            UInt16(4), // instructions length
+            UInt8(0xB8), UInt8(0xFF), UInt8(0xFD), // PUSHW[0] -3
+            UInt8(0x1C), // JMPR
+            UInt8(0x31), // flags
+        ])];
+
+        for glyph_id in 0..16 {
+            glyphs.push(writer::convert(&[
+                Int16(-1), // number of contours
+                Int16(0), Int16(0), Int16(0), Int16(0), // bbox
+                UInt16(0x0001 | 0x0002 | 0x0020), // flags
+                UInt16(glyph_id), // glyph ID
+                Int16(0), Int16(0), // offset
+                UInt16(0x0001 | 0x0002), // flags
+                UInt16(glyph_id), // glyph ID
+                Int16(0), Int16(0), // offset
+            ]));
+        }
+
+        let data = font_data_with_glyphs(&glyphs, &[]);
+        let font = Font::from_data(&data, 0).unwrap();
+        let mut instance = HintingInstance::new(&font, 10, HintingMode::Full).unwrap();
+
+        // The single glyph falls back to an unhinted outline.
+        let mut builder = Builder(String::new());
+        instance.outline_glyph(GlyphId(0), &mut builder).unwrap();
+        assert_eq!(builder.0, "M 1 0 L 1 0 Z ");
+
+        // The budget is shared by all the components, so the first one exhausts it.
+        let mut builder = Builder(String::new());
+        assert!(instance.outline_glyph(GlyphId(16), &mut builder).is_none());
+    }
+
+    #[test]
+    fn infinite_recursion() {
+        let fpgm = vec![
+            0xB0, 0, // PUSHB[0]
+            0x2C, // FDEF
+            0xB0, 0, // PUSHB[0]
+            0x2B, // CALL
+            0x2D, // ENDF
+        ];
+        let prep = vec![0xB0, 0, 0x2B];
+
+        let data = font_data(ROUND_POINTS, &[(b"fpgm", fpgm), (b"prep", prep)]);
+        let font = Font::from_data(&data, 0).unwrap();
+        assert!(!HintingInstance::new(&font, 10, HintingMode::Full).unwrap().is_enabled());
+        assert_eq!(outline(&data, 0, HintingMode::Full), UNHINTED);
+    }
+
+    #[test]
+    fn composite_round_xy_to_grid() {
+        let data = font_data(&[], &[]);
+        assert_eq!(outline(&data, 1, HintingMode::Full),
+                   "M 2.25 1 L 2.25 8.25 L 5.5 8.25 L 5.5 1 L 2.25 1 Z ");
+    }
+
+    #[test]
+    fn composite_round_xy_to_grid_subpixel() {
+        let data = font_data(&[], &[]);
+        assert_eq!(outline(&data, 1, HintingMode::Subpixel),
+                   "M 1.84375 1 L 1.84375 8.25 L 5.09375 8.25 L 5.09375 1 L 1.84375 1 Z ");
+    }
+
+    #[test]
+    fn zero_ppem() {
+        let data = font_data(&[], &[]);
+        let font = Font::from_data(&data, 0).unwrap();
+        assert!(HintingInstance::new(&font, 0, HintingMode::Full).is_none());
+    }
+
+    #[test]
+    fn stack_manipulation() {
+        let mode = HintingMode::Full;
+        // PUSHB[2] 1 2 3, ROLL
+        assert_eq!(execute(&[0xB2, 1, 2, 3, 0x8A], mode).unwrap(), &[2, 3, 1]);
+        // PUSHB[2] 1 2 3, SWAP, DEPTH
+        assert_eq!(execute(&[0xB2, 1, 2, 3, 0x23, 0x24], mode).unwrap(), &[1, 3, 2, 3]);
+        // PUSHB[3] 10 20 30 3, CINDEX
+        assert_eq!(execute(&[0xB3, 10, 20, 30, 3, 0x25], mode).unwrap(), &[10, 20, 30, 10]);
+        // PUSHB[3] 10 20 30 3, MINDEX
+        assert_eq!(execute(&[0xB3, 10, 20, 30, 3, 0x26], mode).unwrap(), &[20, 30, 10]);
+        // POP on an empty stack.
+        assert!(execute(&[0x21], mode).is_none());
+    }
+
+    #[test]
+    fn arithmetic() {
+        let mode = HintingMode::Full;
+        // PUSHB[1] 192 128, DIV
+        assert_eq!(execute(&[0xB1, 192, 128, 0x62], mode).unwrap(), &[96]);
+        // PUSHB[1] 96 128, MUL
+        assert_eq!(execute(&[0xB1, 96, 128, 0x63], mode).unwrap(), &[192]);
+        // PUSHB[0] 100, DUP, FLOOR, SWAP, CEILING
+        assert_eq!(execute(&[0xB0, 100, 0x20, 0x66, 0x23, 0x67], mode).unwrap(), &[64, 128]);
+        // PUSHW[0] -5, ABS, DUP, NEG
+        assert_eq!(execute(&[0xB8, 0xFF, 0xFB, 0x64, 0x20, 0x65], mode).unwrap(), &[5, -5]);
+        // PUSHB[1] 1 0, DIV
+        assert!(execute(&[0xB1, 1, 0, 0x62], mode).is_none());
+    }
+
+    #[test]
+    fn branches() {
+        let mode = HintingMode::Full;
+        // PUSHB[0] n, IF, PUSHB[0] 1, ELSE, PUSHB[0] 0, IF, EIF, PUSHB[0] 2, EIF
+        let program = |n| vec![0xB0, n, 0x58, 0xB0, 1, 0x1B, 0xB0, 0, 0x58, 0x59, 0xB0, 2, 0x59];
+        assert_eq!(execute(&program(1), mode).unwrap(), &[1]);
+        assert_eq!(execute(&program(0), mode).unwrap(), &[2]);
+        // Unterminated IF.
+        assert!(execute(&[0xB0, 0, 0x58, 0xB0, 1], mode).is_none());
+    }
+
+    #[test]
+    fn rounding() {
+        let mode = HintingMode::Full;
+        let round = |state, v| execute(&[state, 0xB0, v, 0x68], mode).unwrap()[0];
+        assert_eq!(round(0x18, 100), 128); // RTG
+        assert_eq!(round(0x19, 100), 96); // RTHG
+        assert_eq!(round(0x3D, 100), 96); // RTDG
+        assert_eq!(round(0x7D, 100), 64); // RDTG
+        assert_eq!(round(0x7C, 65), 128); // RUTG
+        assert_eq!(round(0x7A, 100), 100); // ROFF
+
+        // PUSHW[0] -100, ROUND
+        assert_eq!(execute(&[0xB8, 0xFF, 0x9C, 0x68], mode).unwrap(), &[-128]);
+    }
+
+    #[test]
+    fn get_info() {
+        // PUSHB[0] 1, GETINFO
+        assert_eq!(execute(&[0xB0, 1, 0x88], HintingMode::Full).unwrap(), &[35]);
+        assert_eq!(execute(&[0xB0, 1, 0x88], HintingMode::Subpixel).unwrap(), &[40]);
+    }
+
+    #[test]
+    fn function_definition_in_glyph_program() {
+        // PUSHB[0] 0, FDEF, ENDF
+        assert!(execute(&[0xB0, 0, 0x2C, 0x2D], HintingMode::Full).is_none());
+    }
+}
@@ -0,0 +1,274 @@
+// https://docs.microsoft.com/en-us/typography/opentype/spec/tt_instructions
+
+use crate::parser::Stream;
+
+/// A TrueType instruction.
+#[derive(Clone, Copy, Debug)]
+pub struct Instruction<'a> {
+    /// Instruction's offset in the program.
+    pub offset: usize,
+    /// Instruction's opcode.
+    pub opcode: u8,
+    /// Inline data of push instructions.
+    ///
+    /// Empty for all other instructions.
+    pub data: &'a [u8],
+}
+
+impl<'a> Instruction<'a> {
+    /// Returns instruction's mnemonic.
+    ///
+    /// Flags encoded into the opcode, like in `MIRP[abcde]`, are not included.
+    ///
+    /// Returns `None` for unknown opcodes, which can be defined by `IDEF`.
+    #[inline]
+    pub fn name(&self) -> Option<&'static str> {
+        OPCODE_NAMES[usize::from(self.opcode)]
+    }
+
+    /// Returns values pushed by `NPUSHB`, `NPUSHW`, `PUSHB` and `PUSHW` instructions.
+    #[inline]
+    pub fn push_values(&self) -> PushValues<'a> {
+        PushValues {
+            stream: Stream::new(self.data),
+            words: is_push_words(self.opcode),
+        }
+    }
+}
+
+
+/// An iterator over values pushed by an instruction.
+///
+/// Bytes are unsigned and words are signed.
+#[derive(Clone, Copy)]
+pub struct PushValues<'a> {
+    stream: Stream<'a>,
+    words: bool,
+}
+
+impl<'a> Iterator for PushValues<'a> {
+    type Item = i32;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.words {
+            self.stream.read::<i16>().map(i32::from)
+        } else {
+            self.stream.read::<u8>().map(i32::from)
+        }
+    }
+}
+
+impl core::fmt::Debug for PushValues<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "PushValues()")
+    }
+}
+
+
+/// An iterator over TrueType instructions.
+///
+/// Stops at the first truncated instruction.
+#[derive(Clone, Copy, Default)]
+pub struct Instructions<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Instructions<'a> {
+    #[inline]
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        Instructions { data, offset: 0 }
+    }
+
+    /// Returns the raw program.
+    #[inline]
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+impl<'a> Iterator for Instructions<'a> {
+    type Item = Instruction<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (instruction, next_offset) = decode(self.data, self.offset)?;
+        self.offset = next_offset;
+        Some(instruction)
+    }
+}
+
+impl core::fmt::Debug for Instructions<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "Instructions()")
+    }
+}
+
+
+pub(crate) const NPUSHB: u8 = 0x40;
+pub(crate) const NPUSHW: u8 = 0x41;
+pub(crate) const PUSHB_START: u8 = 0xB0;
+pub(crate) const PUSHB_END: u8 = 0xB7;
+pub(crate) const PUSHW_START: u8 = 0xB8;
+pub(crate) const PUSHW_END: u8 = 0xBF;
+
+/// Decodes an instruction at `offset`.
+///
+/// Returns the instruction and the offset of the next one.
+#[inline]
+pub(crate) fn decode(data: &[u8], offset: usize) -> Option<(Instruction<'_>, usize)> {
+    let mut s = Stream::new_at(data, offset)?;
+    let opcode: u8 = s.read()?;
+    let data_len = match opcode {
+        NPUSHB => usize::from(s.read::<u8>()?),
+        NPUSHW => usize::from(s.read::<u8>()?) * 2,
+        PUSHB_START..=PUSHB_END => usize::from(opcode - PUSHB_START + 1),
+        PUSHW_START..=PUSHW_END => usize::from(opcode - PUSHW_START + 1) * 2,
+        _ => 0,
+    };
+
+    let data = s.read_bytes(data_len)?;
+    Some((Instruction { offset, opcode, data }, s.offset()))
+}
+
+#[inline]
+fn is_push_words(opcode: u8) -> bool {
+    opcode == NPUSHW || (PUSHW_START..=PUSHW_END).contains(&opcode)
+}
+
+const OPCODE_NAMES: [Option<&str>; 256] = [
+    // 0x00
+    Some("SVTCA"), Some("SVTCA"), Some("SPVTCA"), Some("SPVTCA"),
+    Some("SFVTCA"), Some("SFVTCA"), Some("SPVTL"), Some("SPVTL"),
+    Some("SFVTL"), Some("SFVTL"), Some("SPVFS"), Some("SFVFS"),
+    Some("GPV"), Some("GFV"), Some("SFVTPV"), Some("ISECT"),
+    // 0x10
+    Some("SRP0"), Some("SRP1"), Some("SRP2"), Some("SZP0"),
+    Some("SZP1"), Some("SZP2"), Some("SZPS"), Some("SLOOP"),
+    Some("RTG"), Some("RTHG"), Some("SMD"), Some("ELSE"),
+    Some("JMPR"), Some("SCVTCI"), Some("SSWCI"), Some("SSW"),
+    // 0x20
+    Some("DUP"), Some("POP"), Some("CLEAR"), Some("SWAP"),
+    Some("DEPTH"), Some("CINDEX"), Some("MINDEX"), Some("ALIGNPTS"),
+    None, Some("UTP"), Some("LOOPCALL"), Some("CALL"),
+    Some("FDEF"), Some("ENDF"), Some("MDAP"), Some("MDAP"),
+    // 0x30
+    Some("IUP"), Some("IUP"), Some("SHP"), Some("SHP"),
+    Some("SHC"), Some("SHC"), Some("SHZ"), Some("SHZ"),
+    Some("SHPIX"), Some("IP"), Some("MSIRP"), Some("MSIRP"),
+    Some("ALIGNRP"), Some("RTDG"), Some("MIAP"), Some("MIAP"),
+    // 0x40
+    Some("NPUSHB"), Some("NPUSHW"), Some("WS"), Some("RS"),
+    Some("WCVTP"), Some("RCVT"), Some("GC"), Some("GC"),
+    Some("SCFS"), Some("MD"), Some("MD"), Some("MPPEM"),
+    Some("MPS"), Some("FLIPON"), Some("FLIPOFF"), Some("DEBUG"),
+    // 0x50
+    Some("LT"), Some("LTEQ"), Some("GT"), Some("GTEQ"),
+    Some("EQ"), Some("NEQ"), Some("ODD"), Some("EVEN"),
+    Some("IF"), Some("EIF"), Some("AND"), Some("OR"),
+    Some("NOT"), Some("DELTAP1"), Some("SDB"), Some("SDS"),
+    // 0x60
+    Some("ADD"), Some("SUB"), Some("DIV"), Some("MUL"),
+    Some("ABS"), Some("NEG"), Some("FLOOR"), Some("CEILING"),
+    Some("ROUND"), Some("ROUND"), Some("ROUND"), Some("ROUND"),
+    Some("NROUND"), Some("NROUND"), Some("NROUND"), Some("NROUND"),
+    // 0x70
+    Some("WCVTF"), Some("DELTAP2"), Some("DELTAP3"), Some("DELTAC1"),
+    Some("DELTAC2"), Some("DELTAC3"), Some("SROUND"), Some("S45ROUND"),
+    Some("JROT"), Some("JROF"), Some("ROFF"), None,
+    Some("RUTG"), Some("RDTG"), Some("SANGW"), Some("AA"),
+    // 0x80
+    Some("FLIPPT"), Some("FLIPRGON"), Some("FLIPRGOFF"), None,
+    None, Some("SCANCTRL"), Some("SDPVTL"), Some("SDPVTL"),
+    Some("GETINFO"), Some("IDEF"), Some("ROLL"), Some("MAX"),
+    Some("MIN"), Some("SCANTYPE"), Some("INSTCTRL"), None,
+    // 0x90
+    None, Some("GETVARIATION"), None, None,
+    None, None, None, None,
+    None, None, None, None,
+    None, None, None, None,
+    // 0xA0
+    None, None, None, None,
+    None, None, None, None,
+    None, None, None, None,
+    None, None, None, None,
+    // 0xB0
+    Some("PUSHB"), Some("PUSHB"), Some("PUSHB"), Some("PUSHB"),
+    Some("PUSHB"), Some("PUSHB"), Some("PUSHB"), Some("PUSHB"),
+    Some("PUSHW"), Some("PUSHW"), Some("PUSHW"), Some("PUSHW"),
+    Some("PUSHW"), Some("PUSHW"), Some("PUSHW"), Some("PUSHW"),
+    // 0xC0
+    Some("MDRP"), Some("MDRP"), Some("MDRP"), Some("MDRP"),
+    Some("MDRP"), Some("MDRP"), Some("MDRP"), Some("MDRP"),
+    Some("MDRP"), Some("MDRP"), Some("MDRP"), Some("MDRP"),
+    Some("MDRP"), Some("MDRP"), Some("MDRP"), Some("MDRP"),
+    // 0xD0
+    Some("MDRP"), Some("MDRP"), Some("MDRP"), Some("MDRP"),
+    Some("MDRP"), Some("MDRP"), Some("MDRP"), Some("MDRP"),
+    Some("MDRP"), Some("MDRP"), Some("MDRP"), Some("MDRP"),
+    Some("MDRP"), Some("MDRP"), Some("MDRP"), Some("MDRP"),
+    // 0xE0
+    Some("MIRP"), Some("MIRP"), Some("MIRP"), Some("MIRP"),
+    Some("MIRP"), Some("MIRP"), Some("MIRP"), Some("MIRP"),
+    Some("MIRP"), Some("MIRP"), Some("MIRP"), Some("MIRP"),
+    Some("MIRP"), Some("MIRP"), Some("MIRP"), Some("MIRP"),
+    // 0xF0
+    Some("MIRP"), Some("MIRP"), Some("MIRP"), Some("MIRP"),
+    Some("MIRP"), Some("MIRP"), Some("MIRP"), Some("MIRP"),
+    Some("MIRP"), Some("MIRP"), Some("MIRP"), Some("MIRP"),
+    Some("MIRP"), Some("MIRP"), Some("MIRP"), Some("MIRP"),
+];
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec::Vec;
+
+    #[test]
+    fn push_instructions() {
+        let data = [
+            0xB1, 0x01, 0xFF, // PUSHB[1] 1 255
+            0xB8, 0xFF, 0xFE, // PUSHW[0] -2
+            0x40, 0x02, 0x03, 0x04, // NPUSHB 3 4
+            0x41, 0x01, 0x01, 0x00, // NPUSHW 256
+            0x2B, // CALL
+        ];
+
+        let instructions: Vec<_> = Instructions::new(&data)
+            .map(|i| (i.offset, i.name().unwrap(), i.push_values().collect::<Vec<_>>()))
+            .collect();
+
+        assert_eq!(instructions, &[
+            (0, "PUSHB", vec![1, 255]),
+            (3, "PUSHW", vec![-2]),
+            (6, "NPUSHB", vec![3, 4]),
+            (10, "NPUSHW", vec![256]),
+            (14, "CALL", vec![]),
+        ]);
+    }
+
+    #[test]
+    fn flags_in_opcode() {
+        let data = [0x31, 0xE5, 0xC0];
+        let names: Vec<_> = Instructions::new(&data).map(|i| (i.opcode, i.name())).collect();
+        assert_eq!(names, &[(0x31, Some("IUP")), (0xE5, Some("MIRP")), (0xC0, Some("MDRP"))]);
+    }
+
+    #[test]
+    fn unknown_opcode() {
+        let data = [0x28, 0xA0];
+        let names: Vec<_> = Instructions::new(&data).map(|i| i.name()).collect();
+        assert_eq!(names, &[None, None]);
+    }
+
+    #[test]
+    fn truncated_push() {
+        let data = [0x2B, 0xB2, 0x01, 0x02];
+        let mut iter = Instructions::new(&data);
+        assert_eq!(iter.next().unwrap().opcode, 0x2B);
+        assert!(iter.next().is_none());
+        assert!(iter.next().is_none());
+    }
+}
@@ -44,10 +44,18 @@ By doing so we can simplify an API quite a lot since otherwise, we will have to
 #[macro_use]
 extern crate std;
 
+// The `alloc` crate requires Rust 1.36, so `std` builds use its re-exports instead.
 #[cfg(feature = "std")]
-use std::string::String;
+extern crate std as alloc;
+
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
 
 use core::fmt;
+use core::convert::TryFrom;
 use core::num::NonZeroU16;
 
 macro_rules! try_opt_or {
@@ -60,22 +68,46 @@ macro_rules! try_opt_or {
 }
 
 mod ggg;
+#[cfg(feature = "alloc")]
+mod hinting;
+mod instructions;
+mod language;
 mod parser;
 mod tables;
 mod var_store;
 
+#[cfg(feature = "alloc")]
+mod type1;
+
+mod mac_encodings;
+
 #[cfg(feature = "std")]
 mod writer;
 
 use tables::*;
 use parser::{Stream, FromData, NumFrom, TryNumFrom, i16_bound, f32_bound};
 use head::IndexToLocationFormat;
+pub use cff::{CFFTopDict, CFFPrivateDict, FontMatrix, BlueZone, BlueZones, StemSnaps};
+pub use cvt::ControlValues;
+pub use cmap::{GlyphVariationResult, VariationSelectors, VariationSelector, VariationSequences};
 pub use fvar::{VariationAxes, VariationAxis};
 pub use gdef::GlyphClass;
+pub use glyf::{GlyphComponent, GlyphComponents, CompositeGlyphFlags, Transform};
+pub use glyf::{GlyphPoint, GlyphPoints};
+pub use head::{FontHeader, FontHeaderFlags, MacStyle};
+pub use hhea::HorizontalHeader;
+#[cfg(feature = "alloc")]
+pub use hinting::{HintingInstance, HintingMode};
+pub use instructions::{Instruction, Instructions, PushValues};
+pub use maxp::{MaximumProfile, TrueTypeLimits};
+pub use post::PostScriptHeader;
+pub use svg::{SvgDocument, SvgDocuments};
 pub use ggg::*;
 pub use name::*;
 pub use os2::*;
 pub use tables::kern;
+#[cfg(feature = "alloc")]
+pub use type1::Type1Font;
 
 
 /// A type-safe wrapper for glyph ID.
@@ -290,6 +322,71 @@ pub struct LineMetrics {
 }
 
 
+/// A line spacing.
+///
+/// Returned by `Font::line_spacing`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct LineSpacing {
+    /// Ascender.
+    pub ascender: i16,
+
+    /// Descender. Usually negative.
+    pub descender: i16,
+
+    /// Line gap.
+    pub line_gap: i16,
+
+    /// Metrics that were used.
+    pub source: LineSpacingSource,
+}
+
+impl LineSpacing {
+    /// Returns a distance between two baselines.
+    #[inline]
+    pub fn line_height(&self) -> i32 {
+        i32::from(self.ascender) - i32::from(self.descender) + i32::from(self.line_gap)
+    }
+}
+
+
+/// A source of line spacing metrics.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum LineSpacingSource {
+    /// `ascender`, `descender` and `lineGap` from the `hhea` table.
+    HorizontalHeader,
+    /// `sTypoAscender`, `sTypoDescender` and `sTypoLineGap` from the `OS/2` table.
+    Typographic,
+    /// `usWinAscent` and `usWinDescent` from the `OS/2` table.
+    Windows,
+}
+
+
+/// Rules used to select line spacing metrics.
+///
+/// `Browser` and `DirectWrite` prefer typographic metrics when `USE_TYPO_METRICS` flag is set.
+/// `CoreText` ignores it.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum LineSpacingConvention {
+    /// Used by web browsers, FreeType and HarfBuzz.
+    ///
+    /// Uses `hhea` metrics. When `hhea` ascender and descender are zero,
+    /// fallbacks to typographic and then to Windows metrics.
+    Browser,
+
+    /// Used by DirectWrite and GDI.
+    ///
+    /// Uses Windows metrics with a line gap equal to the `hhea` line height
+    /// minus the Windows one, but not negative.
+    /// Fallbacks to `hhea` metrics when `OS/2` table is not present.
+    DirectWrite,
+
+    /// Used by Core Text.
+    ///
+    /// Always uses `hhea` metrics, even when `USE_TYPO_METRICS` flag is set.
+    CoreText,
+}
+
+
 /// A rectangle.
 #[repr(C)]
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -386,7 +483,69 @@ pub trait OutlineBuilder {
 }
 
 
-struct DummyOutline;
+/// A trait for glyph outline construction with PostScript hints.
+///
+/// Only `CFF` and `CFF2` glyphs have hints. Stems are reported in the order
+/// they are defined in a charstring. So the N-th bit of a hint mask,
+/// starting from the most significant bit of the first byte,
+/// refers to the N-th reported stem. Horizontal stems always precede vertical ones.
+///
+/// All methods do nothing by default.
+pub trait HintedOutlineBuilder: OutlineBuilder {
+    /// Appends a horizontal stem hint.
+    ///
+    /// `y` is the bottom edge and `dy` is the stem height.
+    /// Ghost stems have a height of -20 or -21.
+    fn hstem(&mut self, _y: f32, _dy: f32) {}
+
+    /// Appends a vertical stem hint.
+    ///
+    /// `x` is the left edge and `dx` is the stem width.
+    fn vstem(&mut self, _x: f32, _dx: f32) {}
+
+    /// Enables stems for the following path segments.
+    ///
+    /// Contains a bit for each stem reported so far.
+    fn hint_mask(&mut self, _mask: &[u8]) {}
+
+    /// Groups stems for counter control.
+    ///
+    /// Contains a bit for each stem reported so far.
+    fn counter_mask(&mut self, _mask: &[u8]) {}
+}
+
+
+// Allows to use `OutlineBuilder` where `HintedOutlineBuilder` is expected.
+pub(crate) struct UnhintedOutlineBuilder<'a>(pub &'a mut dyn OutlineBuilder);
+
+impl OutlineBuilder for UnhintedOutlineBuilder<'_> {
+    #[inline] fn move_to(&mut self, x: f32, y: f32) { self.0.move_to(x, y) }
+    #[inline] fn line_to(&mut self, x: f32, y: f32) { self.0.line_to(x, y) }
+    #[inline] fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) { self.0.quad_to(x1, y1, x, y) }
+    #[inline] fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.0.curve_to(x1, y1, x2, y2, x, y)
+    }
+    #[inline] fn close(&mut self) { self.0.close() }
+}
+
+impl HintedOutlineBuilder for UnhintedOutlineBuilder<'_> {}
+
+
+// Trait objects upcasting is not supported, so we have to use a wrapper.
+struct HintedOutlineBuilderRef<'a>(&'a mut dyn HintedOutlineBuilder);
+
+impl OutlineBuilder for HintedOutlineBuilderRef<'_> {
+    #[inline] fn move_to(&mut self, x: f32, y: f32) { self.0.move_to(x, y) }
+    #[inline] fn line_to(&mut self, x: f32, y: f32) { self.0.line_to(x, y) }
+    #[inline] fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) { self.0.quad_to(x1, y1, x, y) }
+    #[inline] fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.0.curve_to(x1, y1, x2, y2, x, y)
+    }
+    #[inline] fn close(&mut self) { self.0.close() }
+}
+
+
+pub(crate) struct DummyOutline;
 impl OutlineBuilder for DummyOutline {
     fn move_to(&mut self, _: f32, _: f32) {}
     fn line_to(&mut self, _: f32, _: f32) {}
@@ -397,10 +556,48 @@ impl OutlineBuilder for DummyOutline {
 
 
 /// A glyph raster image format.
-#[allow(missing_docs)]
+///
+/// Uncompressed bitmaps are stored row by row, from top to bottom,
+/// with the most significant bit first. Rows of byte-aligned bitmaps are padded
+/// to a byte boundary, while *packed* ones have no padding at all.
+/// In grayscale bitmaps, zero is white and the maximum value is black.
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum RasterImageFormat {
+    /// A PNG image.
     PNG,
+
+    /// A JPEG image.
+    JPEG,
+
+    /// A TIFF image.
+    TIFF,
+
+    /// A 1-bit monochrome byte-aligned bitmap.
+    BitmapMono,
+
+    /// A 1-bit monochrome bit-aligned bitmap.
+    BitmapMonoPacked,
+
+    /// A 2-bit grayscale byte-aligned bitmap.
+    BitmapGray2,
+
+    /// A 2-bit grayscale bit-aligned bitmap.
+    BitmapGray2Packed,
+
+    /// A 4-bit grayscale byte-aligned bitmap.
+    BitmapGray4,
+
+    /// A 4-bit grayscale bit-aligned bitmap.
+    BitmapGray4Packed,
+
+    /// An 8-bit grayscale bitmap.
+    BitmapGray8,
+
+    /// A composite bitmap.
+    ///
+    /// Contains no pixels, only references to other glyphs' bitmaps.
+    /// Use `RasterGlyphImage::components` to iterate over them.
+    BitmapComposite,
 }
 
 
@@ -431,10 +628,101 @@ pub struct RasterGlyphImage<'a> {
     /// An image format.
     pub format: RasterImageFormat,
 
+    /// Indicates that glyph's outline should be drawn under the image.
+    ///
+    /// Set by the `sbix` table's `drawOutlines` flag.
+    pub draw_outlines: bool,
+
+    /// Horizontal advance.
+    ///
+    /// Set only by `CBDT` and `EBDT` tables.
+    pub advance: Option<u16>,
+
+    /// Vertical metrics.
+    ///
+    /// Set only by `CBDT` and `EBDT` tables and only when
+    /// bitmap uses big glyph metrics.
+    pub vertical_metrics: Option<RasterImageVerticalMetrics>,
+
     /// A raw image data. It's up to the caller to decode it.
     pub data: &'a [u8],
 }
 
+impl<'a> RasterGlyphImage<'a> {
+    /// Returns an iterator over composite bitmap components.
+    ///
+    /// The iterator is empty when format is not `RasterImageFormat::BitmapComposite`.
+    #[inline]
+    pub fn components(&self) -> RasterImageComponents<'a> {
+        let data = if self.format == RasterImageFormat::BitmapComposite { self.data } else { &[] };
+        RasterImageComponents {
+            components: parser::LazyArray16::new(data),
+            index: 0,
+        }
+    }
+}
+
+
+/// Raster image's vertical metrics.
+///
+/// Note, that metrics are in pixels and not in font units.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct RasterImageVerticalMetrics {
+    /// A horizontal distance from the vertical center line to the left edge of the image.
+    pub bearing_x: i16,
+
+    /// A vertical distance from the vertical origin to the top edge of the image.
+    pub bearing_y: i16,
+
+    /// Vertical advance.
+    pub advance: u16,
+}
+
+
+/// A composite bitmap component.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct RasterImageComponent {
+    /// Component's glyph ID.
+    ///
+    /// Its image should be loaded from the same strike, i.e. using the same `pixels_per_em`.
+    pub glyph_id: GlyphId,
+
+    /// A horizontal offset in pixels relative to the composite image's origin.
+    pub x: i8,
+
+    /// A vertical offset in pixels relative to the composite image's origin.
+    pub y: i8,
+}
+
+
+/// An iterator over composite bitmap components.
+#[derive(Clone, Copy)]
+pub struct RasterImageComponents<'a> {
+    components: parser::LazyArray16<'a, cbdt::Component>,
+    index: u16,
+}
+
+impl Iterator for RasterImageComponents<'_> {
+    type Item = RasterImageComponent;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let component = self.components.get(self.index)?;
+        self.index += 1;
+        Some(RasterImageComponent {
+            glyph_id: component.glyph_id,
+            x: component.x,
+            y: component.y,
+        })
+    }
+}
+
+impl fmt::Debug for RasterImageComponents<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RasterImageComponents()")
+    }
+}
+
 
 /// A table name.
 #[repr(C)]
@@ -447,6 +735,11 @@ pub enum TableName {
     ColorBitmapLocation,
     CompactFontFormat,
     CompactFontFormat2,
+    ControlValue,
+    ControlValueProgram,
+    EmbeddedBitmapData,
+    EmbeddedBitmapLocation,
+    FontProgram,
     FontVariations,
     GlyphData,
     GlyphDefinition,
@@ -527,6 +820,10 @@ pub struct Font<'a> {
     cff_: Option<cff::Metadata<'a>>,
     cff2: Option<cff2::Metadata<'a>>,
     cmap: Option<cmap::Table<'a>>,
+    cvt_: Option<&'a [u8]>,
+    ebdt: Option<&'a [u8]>,
+    eblc: Option<&'a [u8]>,
+    fpgm: Option<&'a [u8]>,
     fvar: Option<fvar::Table<'a>>,
     gdef: Option<gdef::Table<'a>>,
     glyf: Option<&'a [u8]>,
@@ -537,14 +834,16 @@ pub struct Font<'a> {
     hvar: Option<hvar::Table<'a>>,
     kern: Option<kern::Subtables<'a>>,
     loca: Option<loca::Table<'a>>,
+    maxp: &'a [u8],
     mvar: Option<mvar::Table<'a>>,
     name: Option<name::Names<'a>>,
     os_2: Option<os2::Table<'a>>,
     post: Option<post::Table<'a>>,
+    prep: Option<&'a [u8]>,
     vhea: Option<&'a [u8]>,
     vmtx: Option<hmtx::Table<'a>>,
     sbix: Option<&'a [u8]>,
-    svg_: Option<&'a [u8]>,
+    svg_: Option<svg::SvgDocuments<'a>>,
     vorg: Option<vorg::Table<'a>>,
     vvar: Option<hvar::Table<'a>>,
     number_of_glyphs: NonZeroU16,
@@ -606,6 +905,10 @@ impl<'a> Font<'a> {
             cff_: None,
             cff2: None,
             cmap: None,
+            cvt_: None,
+            ebdt: None,
+            eblc: None,
+            fpgm: None,
             fvar: None,
             gdef: None,
             glyf: None,
@@ -616,10 +919,12 @@ impl<'a> Font<'a> {
             hvar: None,
             kern: None,
             loca: None,
+            maxp: &[],
             mvar: None,
             name: None,
             os_2: None,
             post: None,
+            prep: None,
             vhea: None,
             vmtx: None,
             sbix: None,
@@ -630,7 +935,6 @@ impl<'a> Font<'a> {
             coordinates: VarCoords::default(),
         };
 
-        let mut number_of_glyphs = None;
         let mut hmtx = None;
         let mut vmtx = None;
         let mut loca = None;
@@ -645,15 +949,19 @@ impl<'a> Font<'a> {
                 b"CBLC" => font.cblc = data.get(range),
                 b"CFF " => font.cff_ = data.get(range).and_then(|data| cff::parse_metadata(data)),
                 b"CFF2" => font.cff2 = data.get(range).and_then(|data| cff2::parse_metadata(data)),
+                b"EBDT" => font.ebdt = data.get(range),
+                b"EBLC" => font.eblc = data.get(range),
                 b"GDEF" => font.gdef = data.get(range).and_then(|data| gdef::Table::parse(data)),
                 b"HVAR" => font.hvar = data.get(range).and_then(|data| hvar::Table::parse(data)),
                 b"MVAR" => font.mvar = data.get(range).and_then(|data| mvar::Table::parse(data)),
                 b"OS/2" => font.os_2 = data.get(range).and_then(|data| os2::Table::parse(data)),
-                b"SVG " => font.svg_ = data.get(range),
+                b"SVG " => font.svg_ = data.get(range).and_then(svg::parse),
                 b"VORG" => font.vorg = data.get(range).and_then(|data| vorg::Table::parse(data)),
                 b"VVAR" => font.vvar = data.get(range).and_then(|data| hvar::Table::parse(data)),
                 b"avar" => font.avar = data.get(range).and_then(|data| avar::Table::parse(data)),
                 b"cmap" => font.cmap = data.get(range).and_then(|data| cmap::Table::parse(data)),
+                b"cvt " => font.cvt_ = data.get(range),
+                b"fpgm" => font.fpgm = data.get(range),
                 b"fvar" => font.fvar = data.get(range).and_then(|data| fvar::Table::parse(data)),
                 b"glyf" => font.glyf = data.get(range),
                 b"gvar" => font.gvar = data.get(range).and_then(|data| gvar::Table::parse(data)),
@@ -662,9 +970,10 @@ impl<'a> Font<'a> {
                 b"hmtx" => hmtx = data.get(range),
                 b"kern" => font.kern = data.get(range).and_then(|data| kern::parse(data)),
                 b"loca" => loca = data.get(range),
-                b"maxp" => number_of_glyphs = data.get(range).and_then(|data| maxp::parse(data)),
+                b"maxp" => font.maxp = data.get(range).unwrap_or_default(),
                 b"name" => font.name = data.get(range).and_then(|data| name::parse(data)),
                 b"post" => font.post = data.get(range).and_then(|data| post::Table::parse(data)),
+                b"prep" => font.prep = data.get(range),
                 b"sbix" => font.sbix = data.get(range),
                 b"vhea" => font.vhea = data.get(range).and_then(|data| vhea::parse(data)),
                 b"vmtx" => vmtx = data.get(range),
@@ -672,11 +981,11 @@ impl<'a> Font<'a> {
             }
         }
 
-        if font.head.is_empty() || font.hhea.is_empty() || number_of_glyphs.is_none() {
+        if font.head.is_empty() || font.hhea.is_empty() {
             return None;
         }
 
-        font.number_of_glyphs = number_of_glyphs?;
+        font.number_of_glyphs = maxp::number_of_glyphs(font.maxp)?;
 
         if let Some(ref fvar) = font.fvar {
             font.coordinates.len = fvar.axes().count().min(MAX_VAR_COORDS as usize) as u8;
@@ -718,6 +1027,11 @@ impl<'a> Font<'a> {
             TableName::ColorBitmapLocation          => self.cblc.is_some(),
             TableName::CompactFontFormat            => self.cff_.is_some(),
             TableName::CompactFontFormat2           => self.cff2.is_some(),
+            TableName::ControlValue                 => self.cvt_.is_some(),
+            TableName::ControlValueProgram          => self.prep.is_some(),
+            TableName::EmbeddedBitmapData           => self.ebdt.is_some(),
+            TableName::EmbeddedBitmapLocation       => self.eblc.is_some(),
+            TableName::FontProgram                  => self.fpgm.is_some(),
             TableName::FontVariations               => self.fvar.is_some(),
             TableName::GlyphData                    => self.glyf.is_some(),
             TableName::GlyphDefinition              => self.gdef.is_some(),
@@ -745,35 +1059,71 @@ impl<'a> Font<'a> {
     ///
     /// [Name Records]: https://docs.microsoft.com/en-us/typography/opentype/spec/name#name-records
     #[inline]
-    pub fn names(&self) -> Names {
+    pub fn names(&self) -> Names<'a> {
         self.name.unwrap_or_default()
     }
 
     /// Returns font's family name.
     ///
-    /// *Typographic Family* is preferred over *Family*.
+    /// Resolved using the *Typographic Family* → *WWS Family* → *Family* fallback chain.
+    /// So all the weights and widths of a typeface will share the same name.
+    ///
+    /// English names are preferred. Use [`name()`] to get a name in a different language.
     ///
     /// Note that font can have multiple names. You can use [`names()`] to list them all.
     ///
+    /// [`name()`]: #method.name
     /// [`names()`]: #method.names
-    #[cfg(feature = "std")]
+    #[cfg(feature = "alloc")]
     #[inline]
     pub fn family_name(&self) -> Option<String> {
-        let mut idx = None;
-        let mut iter = self.names();
-        for (i, name) in iter.enumerate() {
-            if name.name_id() == name_id::TYPOGRAPHIC_FAMILY && name.is_unicode() {
-                // Break the loop as soon as we reached 'Typographic Family'.
-                idx = Some(i);
-                break;
-            } else if name.name_id() == name_id::FAMILY && name.is_unicode() {
-                idx = Some(i);
-                // Do not break the loop since 'Typographic Family' can be set later
-                // and it has a higher priority.
-            }
-        }
+        self.resolve_name(&[
+            name_id::TYPOGRAPHIC_FAMILY,
+            name_id::WWS_FAMILY,
+            name_id::FAMILY,
+        ])
+    }
 
-        iter.nth(idx?).and_then(|name| name.name_from_utf16_be())
+    /// Returns font's subfamily name.
+    ///
+    /// Resolved using the *Typographic Subfamily* → *WWS Subfamily* → *Subfamily*
+    /// fallback chain. Like `Semibold Italic` and not just `Italic`.
+    ///
+    /// English names are preferred. Use [`name()`] to get a name in a different language.
+    ///
+    /// [`name()`]: #method.name
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn subfamily_name(&self) -> Option<String> {
+        self.resolve_name(&[
+            name_id::TYPOGRAPHIC_SUBFAMILY,
+            name_id::WWS_SUBFAMILY,
+            name_id::SUBFAMILY,
+        ])
+    }
+
+    /// Returns font's full name.
+    ///
+    /// English names are preferred. Use [`name()`] to get a name in a different language.
+    ///
+    /// [`name()`]: #method.name
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn full_name(&self) -> Option<String> {
+        self.name(name_id::FULL_NAME, &[])
+    }
+
+    /// Returns font's unique identifier.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn unique_id(&self) -> Option<String> {
+        self.name(name_id::UNIQUE_ID, &[])
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn resolve_name(&self, name_ids: &[u16]) -> Option<String> {
+        name_ids.iter().filter_map(|id| self.name(*id, &[])).next()
     }
 
     /// Returns font's PostScript name.
@@ -781,12 +1131,90 @@ impl<'a> Font<'a> {
     /// Note that font can have multiple names. You can use [`names()`] to list them all.
     ///
     /// [`names()`]: #method.names
-    #[cfg(feature = "std")]
+    #[cfg(feature = "alloc")]
     #[inline]
     pub fn post_script_name(&self) -> Option<String> {
+        self.find_name(name_id::POST_SCRIPT_NAME)
+    }
+
+    /// Returns a name in the most appropriate language.
+    ///
+    /// `preferred_locales` is a list of [BCP 47](https://tools.ietf.org/html/bcp47)
+    /// language ranges, like `["ja-JP", "en"]`, in the order of preference.
+    /// Each range is matched using the
+    /// [Lookup](https://tools.ietf.org/html/rfc4647#section-3.4) scheme,
+    /// so `ja-JP` will fallback to `ja` and `zh-Hant-TW` to `zh-Hant` and `zh`.
+    /// On each step, a name tagged with a more specific tag is also accepted,
+    /// so `zh` matches `zh-TW`.
+    ///
+    /// When no name matches the preferred locales, English is used.
+    /// And when there is no English name, the first name with a supported
+    /// encoding is returned.
+    ///
+    /// Unicode names are preferred over the Macintosh ones.
+    ///
+    /// A predefined list of name ID's can be found in the [`name_id`](name_id/index.html) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ttf_parser::{Font, name_id};
+    ///
+    /// let data = std::fs::read("fonts/SourceSansPro-Regular-Tiny.ttf").unwrap();
+    /// let font = Font::from_data(&data, 0).unwrap();
+    /// let name = font.name(name_id::FAMILY, &["ja-JP", "en"]);
+    /// assert_eq!(name.as_ref().map(|s| s.as_str()), Some("Source Sans Pro"));
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn name(&self, name_id: u16, preferred_locales: &[&str]) -> Option<String> {
+        const FALLBACK_LOCALE: &str = "en";
+
+        let locales = preferred_locales.iter().chain(core::iter::once(&FALLBACK_LOCALE));
+        for locale in locales {
+            let mut range = *locale;
+            loop {
+                let name = self.find_localized_name(name_id, |tag| tag.eq_ignore_ascii_case(range))
+                    .or_else(|| self.find_localized_name(name_id, |tag| tag.matches(range)));
+                if let Some(name) = name {
+                    return name.name_utf8();
+                }
+
+                // Truncate the range from the end.
+                match range.rfind('-') {
+                    Some(idx) => range = &range[..idx],
+                    None => break,
+                }
+            }
+        }
+
+        self.find_name(name_id)
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn find_localized_name<F>(&self, name_id: u16, f: F) -> Option<Name<'a>>
+        where F: Fn(&LanguageTag) -> bool
+    {
+        let is_match = |name: &Name| {
+            name.name_id() == name_id && name.language_tag().map(|tag| f(&tag)).unwrap_or(false)
+        };
+
+        // Unicode names have a higher priority than the Macintosh ones.
+        self.names()
+            .find(|name| is_match(name) && name.is_unicode())
+            .or_else(|| self.names().find(|name| is_match(name) && name.is_supported_encoding()))
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline(never)]
+    fn find_name(&self, name_id: u16) -> Option<String> {
+        // Unicode names have a higher priority than the Macintosh ones.
         self.names()
-            .find(|name| name.name_id() == name_id::POST_SCRIPT_NAME && name.is_unicode())
-            .and_then(|name| name.name_from_utf16_be())
+            .find(|name| name.name_id() == name_id && name.is_unicode())
+            .or_else(|| {
+                self.names().find(|name| name.name_id() == name_id && name.is_supported_encoding())
+            })
+            .and_then(|name| name.name_utf8())
     }
 
     /// Checks that font is marked as *Regular*.
@@ -847,7 +1275,7 @@ impl<'a> Font<'a> {
     }
 
     #[inline]
-    fn use_typo_metrics(&self) -> Option<os2::Table> {
+    fn use_typo_metrics(&self) -> Option<os2::Table<'_>> {
         self.os_2.filter(|table| table.is_use_typo_metrics())
     }
 
@@ -898,6 +1326,78 @@ impl<'a> Font<'a> {
         }
     }
 
+    /// Returns line spacing metrics selected using the specified convention.
+    ///
+    /// Unlike `ascender()`, `descender()` and `line_gap()`, which are based only
+    /// on the `USE_TYPO_METRICS` flag, this method follows platform-specific heuristics.
+    ///
+    /// This method is affected by variation axes.
+    /// `MVAR` doesn't have dedicated `hhea` tags, so `hhea` metrics are adjusted
+    /// using the typographic ones, like HarfBuzz does.
+    pub fn line_spacing(&self, convention: LineSpacingConvention) -> LineSpacing {
+        let hhea = LineSpacing {
+            ascender: self.apply_metrics_variation(Tag::from_bytes(b"hasc"), hhea::ascender(self.hhea)),
+            descender: self.apply_metrics_variation(Tag::from_bytes(b"hdsc"), hhea::descender(self.hhea)),
+            line_gap: self.apply_metrics_variation(Tag::from_bytes(b"hlgp"), hhea::line_gap(self.hhea)),
+            source: LineSpacingSource::HorizontalHeader,
+        };
+
+        let typo = self.os_2.map(|_| LineSpacing {
+            ascender: self.typographic_ascender().unwrap_or(0),
+            descender: self.typographic_descender().unwrap_or(0),
+            line_gap: self.typographic_line_gap().unwrap_or(0),
+            source: LineSpacingSource::Typographic,
+        });
+
+        if convention == LineSpacingConvention::CoreText {
+            return hhea;
+        }
+
+        if let Some(typo) = typo {
+            if self.is_use_typo_metrics() {
+                return typo;
+            }
+        }
+
+        let win = match (self.windows_ascender(), self.windows_descender()) {
+            (Some(ascender), Some(descender)) => Some(LineSpacing {
+                ascender,
+                descender,
+                line_gap: 0,
+                source: LineSpacingSource::Windows,
+            }),
+            _ => None,
+        };
+
+        match convention {
+            LineSpacingConvention::Browser => {
+                if hhea.ascender != 0 || hhea.descender != 0 {
+                    return hhea;
+                }
+
+                if let Some(typo) = typo {
+                    if typo.ascender != 0 || typo.descender != 0 {
+                        return typo;
+                    }
+                }
+
+                win.unwrap_or(hhea)
+            }
+            LineSpacingConvention::DirectWrite => {
+                match win {
+                    Some(mut win) => {
+                        // Matches GDI's external leading.
+                        let gap = hhea.line_height() - (i32::from(win.ascender) - i32::from(win.descender));
+                        win.line_gap = i16::try_from(gap.max(0)).unwrap_or(0x7FFF);
+                        win
+                    }
+                    None => hhea,
+                }
+            }
+            LineSpacingConvention::CoreText => hhea,
+        }
+    }
+
     // TODO: does this affected by USE_TYPO_METRICS?
 
     /// Returns a vertical font ascender.
@@ -935,6 +1435,89 @@ impl<'a> Font<'a> {
             .map(|v| self.apply_metrics_variation(Tag::from_bytes(b"vlgp"), v))
     }
 
+    /// Returns a font header.
+    ///
+    /// Values are returned as is, without any validation.
+    #[inline]
+    pub fn font_header(&self) -> FontHeader {
+        FontHeader::parse(self.head)
+    }
+
+    /// Returns a horizontal header.
+    ///
+    /// Values are returned as is and are not affected by variation axes.
+    #[inline]
+    pub fn horizontal_header(&self) -> HorizontalHeader {
+        HorizontalHeader::parse(self.hhea)
+    }
+
+    /// Returns a maximum profile.
+    #[inline]
+    pub fn maximum_profile(&self) -> MaximumProfile {
+        MaximumProfile::parse(self.maxp).unwrap_or(MaximumProfile {
+            number_of_glyphs: self.number_of_glyphs,
+            true_type_limits: None,
+        })
+    }
+
+    /// Returns a PostScript header.
+    ///
+    /// Values are returned as is and are not affected by variation axes.
+    ///
+    /// Returns `None` when `post` table is not present.
+    #[inline]
+    pub fn post_script_header(&self) -> Option<PostScriptHeader> {
+        self.post.map(|post| post.header())
+    }
+
+    /// Returns `CFF` Top DICT values.
+    ///
+    /// Returns `None` when `CFF` table is not present.
+    #[inline]
+    pub fn cff_top_dict(&self) -> Option<CFFTopDict<'_>> {
+        self.cff_.as_ref().map(cff::top_dict)
+    }
+
+    /// Returns `CFF` Private DICT values used by a glyph.
+    ///
+    /// In CID-keyed fonts each glyph can reference a different Private DICT.
+    /// Otherwise, all glyphs share the same one.
+    ///
+    /// Returns `None` when `CFF` table or Private DICT is not present.
+    #[inline]
+    pub fn cff_private_dict(&self, glyph_id: GlyphId) -> Option<CFFPrivateDict> {
+        self.cff_.as_ref().and_then(|metadata| cff::private_dict(metadata, glyph_id))
+    }
+
+    /// Returns glyph's advance width stored in a `CFF` charstring.
+    ///
+    /// A charstring can optionally start with a width, which is added to `nominalWidthX`.
+    /// Otherwise, `defaultWidthX` is used.
+    ///
+    /// Unlike `glyph_hor_advance()`, doesn't use `hmtx`, so it can be used to validate it.
+    ///
+    /// Returns `None` when `CFF` table is not present or on error.
+    #[inline]
+    pub fn cff_glyph_width(&self, glyph_id: GlyphId) -> Option<f32> {
+        self.cff_.as_ref().and_then(|metadata| cff::glyph_width(metadata, glyph_id))
+    }
+
+    /// Returns font's italic angle in counter-clockwise degrees from the vertical.
+    ///
+    /// Returns `None` when `post` table is not present.
+    #[inline]
+    pub fn italic_angle(&self) -> Option<f32> {
+        self.post.map(|post| post.header().italic_angle)
+    }
+
+    /// Checks that font is monospaced.
+    ///
+    /// Returns `false` when `post` table is not present.
+    #[inline]
+    pub fn is_monospaced(&self) -> bool {
+        try_opt_or!(self.post, false).header().is_fixed_pitch
+    }
+
     /// Returns font's units per EM.
     ///
     /// Returns `None` when value is not in a 16..=16384 range.
@@ -954,71 +1537,243 @@ impl<'a> Font<'a> {
             .map(|v| self.apply_metrics_variation(Tag::from_bytes(b"xhgt"), v))
     }
 
-    /// Returns font's underline metrics.
+    /// Returns font's capital height.
     ///
     /// This method is affected by variation axes.
     ///
-    /// Returns `None` when `post` table is not present.
+    /// Returns `None` when OS/2 table is not present or when its version is < 2.
     #[inline]
-    pub fn underline_metrics(&self) -> Option<LineMetrics> {
-        let mut metrics = self.post?.underline_metrics();
-        if self.is_variable() {
-            self.apply_metrics_variation_to(Tag::from_bytes(b"undo"), &mut metrics.position);
-            self.apply_metrics_variation_to(Tag::from_bytes(b"unds"), &mut metrics.thickness);
-        }
-        Some(metrics)
+    pub fn capital_height(&self) -> Option<i16> {
+        self.os_2.and_then(|os_2| os_2.capital_height())
+            .map(|v| self.apply_metrics_variation(Tag::from_bytes(b"cpht"), v))
     }
 
-    /// Returns font's strikeout metrics.
+    /// Returns a typographic ascender from the OS/2 table.
+    ///
+    /// Unlike `ascender()`, doesn't depend on the `USE_TYPO_METRICS` flag.
     ///
     /// This method is affected by variation axes.
     ///
     /// Returns `None` when OS/2 table is not present.
     #[inline]
-    pub fn strikeout_metrics(&self) -> Option<LineMetrics> {
-        let mut metrics = self.os_2?.strikeout_metrics();
-        if self.is_variable() {
-            self.apply_metrics_variation_to(Tag::from_bytes(b"stro"), &mut metrics.position);
-            self.apply_metrics_variation_to(Tag::from_bytes(b"strs"), &mut metrics.thickness);
-        }
-        Some(metrics)
+    pub fn typographic_ascender(&self) -> Option<i16> {
+        self.os_2.map(|os_2| os_2.typo_ascender())
+            .map(|v| self.apply_metrics_variation(Tag::from_bytes(b"hasc"), v))
     }
 
-    /// Returns font's subscript metrics.
+    /// Returns a typographic descender from the OS/2 table.
+    ///
+    /// Unlike `descender()`, doesn't depend on the `USE_TYPO_METRICS` flag.
     ///
     /// This method is affected by variation axes.
     ///
     /// Returns `None` when OS/2 table is not present.
     #[inline]
-    pub fn subscript_metrics(&self) -> Option<ScriptMetrics> {
-        let mut metrics = self.os_2?.subscript_metrics();
-        if self.is_variable() {
-            self.apply_metrics_variation_to(Tag::from_bytes(b"sbxs"), &mut metrics.x_size);
-            self.apply_metrics_variation_to(Tag::from_bytes(b"sbys"), &mut metrics.y_size);
-            self.apply_metrics_variation_to(Tag::from_bytes(b"sbxo"), &mut metrics.x_offset);
-            self.apply_metrics_variation_to(Tag::from_bytes(b"sbyo"), &mut metrics.y_offset);
-        }
-        Some(metrics)
+    pub fn typographic_descender(&self) -> Option<i16> {
+        self.os_2.map(|os_2| os_2.typo_descender())
+            .map(|v| self.apply_metrics_variation(Tag::from_bytes(b"hdsc"), v))
     }
 
-    /// Returns font's superscript metrics.
+    /// Returns a typographic line gap from the OS/2 table.
+    ///
+    /// Unlike `line_gap()`, doesn't depend on the `USE_TYPO_METRICS` flag.
     ///
     /// This method is affected by variation axes.
     ///
     /// Returns `None` when OS/2 table is not present.
     #[inline]
-    pub fn superscript_metrics(&self) -> Option<ScriptMetrics> {
-        let mut metrics = self.os_2?.superscript_metrics();
-        if self.is_variable() {
-            self.apply_metrics_variation_to(Tag::from_bytes(b"spxs"), &mut metrics.x_size);
-            self.apply_metrics_variation_to(Tag::from_bytes(b"spys"), &mut metrics.y_size);
-            self.apply_metrics_variation_to(Tag::from_bytes(b"spxo"), &mut metrics.x_offset);
-            self.apply_metrics_variation_to(Tag::from_bytes(b"spyo"), &mut metrics.y_offset);
-        }
-        Some(metrics)
+    pub fn typographic_line_gap(&self) -> Option<i16> {
+        self.os_2.map(|os_2| os_2.typo_line_gap())
+            .map(|v| self.apply_metrics_variation(Tag::from_bytes(b"hlgp"), v))
     }
 
-    /// Returns a total number of glyphs in the font.
+    /// Returns a Windows ascender (`usWinAscent`) from the OS/2 table.
+    ///
+    /// This method is affected by variation axes.
+    ///
+    /// Returns `None` when OS/2 table is not present or when value doesn't fit into `i16`.
+    #[inline]
+    pub fn windows_ascender(&self) -> Option<i16> {
+        self.os_2.and_then(|os_2| i16::try_from(os_2.windows_ascent()).ok())
+            .map(|v| self.apply_metrics_variation(Tag::from_bytes(b"hcla"), v))
+    }
+
+    /// Returns a Windows descender (`usWinDescent`) from the OS/2 table.
+    ///
+    /// Unlike the stored value, which is positive, the returned one is negative
+    /// for consistency with `descender()`.
+    ///
+    /// This method is affected by variation axes.
+    ///
+    /// Returns `None` when OS/2 table is not present or when value doesn't fit into `i16`.
+    #[inline]
+    pub fn windows_descender(&self) -> Option<i16> {
+        self.os_2.and_then(|os_2| i16::try_from(os_2.windows_descent()).ok())
+            .and_then(|v| self.apply_metrics_variation(Tag::from_bytes(b"hcld"), v).checked_neg())
+    }
+
+    /// Checks that font has the `USE_TYPO_METRICS` flag set.
+    ///
+    /// Returns `false` when OS/2 table is not present or when its version is < 4.
+    #[inline]
+    pub fn is_use_typo_metrics(&self) -> bool {
+        try_opt_or!(self.os_2, false).is_use_typo_metrics()
+    }
+
+    /// Returns font's embedding permissions.
+    ///
+    /// For OS/2 table versions < 3, when multiple permissions are set,
+    /// the least restrictive one is returned.
+    ///
+    /// Returns `None` when OS/2 table is not present or when value is invalid.
+    #[inline]
+    pub fn permissions(&self) -> Option<Permissions> {
+        self.os_2?.permissions()
+    }
+
+    /// Checks that font can be subsetted before embedding.
+    ///
+    /// Returns `true` when OS/2 table is not present or when its version is < 2.
+    #[inline]
+    pub fn is_subsetting_allowed(&self) -> bool {
+        try_opt_or!(self.os_2, true).is_subsetting_allowed()
+    }
+
+    /// Checks that only font's bitmaps can be embedded.
+    ///
+    /// Returns `false` when OS/2 table is not present or when its version is < 2.
+    #[inline]
+    pub fn is_bitmap_embedding_only(&self) -> bool {
+        try_opt_or!(self.os_2, false).is_bitmap_embedding_only()
+    }
+
+    /// Returns font's vendor ID.
+    ///
+    /// Returns `None` when OS/2 table is not present.
+    #[inline]
+    pub fn vendor_id(&self) -> Option<Tag> {
+        self.os_2.map(|os_2| os_2.vendor_id())
+    }
+
+    /// Returns font's PANOSE classification.
+    ///
+    /// Returns `None` when OS/2 table is not present.
+    #[inline]
+    pub fn panose(&self) -> Option<Panose> {
+        self.os_2.map(|os_2| os_2.panose())
+    }
+
+    /// Returns Unicode ranges supported by the font.
+    ///
+    /// Returns an empty set when OS/2 table is not present.
+    #[inline]
+    pub fn unicode_ranges(&self) -> UnicodeRanges {
+        try_opt_or!(self.os_2, UnicodeRanges::default()).unicode_ranges()
+    }
+
+    /// Returns code pages supported by the font.
+    ///
+    /// Returns `None` when OS/2 table is not present or when its version is < 1.
+    #[inline]
+    pub fn code_page_ranges(&self) -> Option<CodePageRanges> {
+        self.os_2?.code_page_ranges()
+    }
+
+    /// Returns a code point that should be used for missing characters.
+    ///
+    /// Zero means that glyph 0 should be used.
+    ///
+    /// Returns `None` when OS/2 table is not present or when its version is < 2.
+    #[inline]
+    pub fn default_char(&self) -> Option<u16> {
+        self.os_2?.default_char()
+    }
+
+    /// Returns a code point of the word break character.
+    ///
+    /// Returns `None` when OS/2 table is not present or when its version is < 2.
+    #[inline]
+    pub fn break_char(&self) -> Option<u16> {
+        self.os_2?.break_char()
+    }
+
+    /// Returns a range of point sizes the font was designed for.
+    ///
+    /// The lower bound is inclusive and the upper one is exclusive.
+    ///
+    /// Returns `None` when OS/2 table is not present or when its version is < 5.
+    #[inline]
+    pub fn optical_point_size_range(&self) -> Option<core::ops::Range<f32>> {
+        // Values are stored in TWIPs, 1/20 of a point.
+        let (lower, upper) = self.os_2?.optical_point_size_range()?;
+        Some(f32::from(lower) / 20.0 .. f32::from(upper) / 20.0)
+    }
+
+    /// Returns font's underline metrics.
+    ///
+    /// This method is affected by variation axes.
+    ///
+    /// Returns `None` when `post` table is not present.
+    #[inline]
+    pub fn underline_metrics(&self) -> Option<LineMetrics> {
+        let mut metrics = self.post?.underline_metrics();
+        if self.is_variable() {
+            self.apply_metrics_variation_to(Tag::from_bytes(b"undo"), &mut metrics.position);
+            self.apply_metrics_variation_to(Tag::from_bytes(b"unds"), &mut metrics.thickness);
+        }
+        Some(metrics)
+    }
+
+    /// Returns font's strikeout metrics.
+    ///
+    /// This method is affected by variation axes.
+    ///
+    /// Returns `None` when OS/2 table is not present.
+    #[inline]
+    pub fn strikeout_metrics(&self) -> Option<LineMetrics> {
+        let mut metrics = self.os_2?.strikeout_metrics();
+        if self.is_variable() {
+            self.apply_metrics_variation_to(Tag::from_bytes(b"stro"), &mut metrics.position);
+            self.apply_metrics_variation_to(Tag::from_bytes(b"strs"), &mut metrics.thickness);
+        }
+        Some(metrics)
+    }
+
+    /// Returns font's subscript metrics.
+    ///
+    /// This method is affected by variation axes.
+    ///
+    /// Returns `None` when OS/2 table is not present.
+    #[inline]
+    pub fn subscript_metrics(&self) -> Option<ScriptMetrics> {
+        let mut metrics = self.os_2?.subscript_metrics();
+        if self.is_variable() {
+            self.apply_metrics_variation_to(Tag::from_bytes(b"sbxs"), &mut metrics.x_size);
+            self.apply_metrics_variation_to(Tag::from_bytes(b"sbys"), &mut metrics.y_size);
+            self.apply_metrics_variation_to(Tag::from_bytes(b"sbxo"), &mut metrics.x_offset);
+            self.apply_metrics_variation_to(Tag::from_bytes(b"sbyo"), &mut metrics.y_offset);
+        }
+        Some(metrics)
+    }
+
+    /// Returns font's superscript metrics.
+    ///
+    /// This method is affected by variation axes.
+    ///
+    /// Returns `None` when OS/2 table is not present.
+    #[inline]
+    pub fn superscript_metrics(&self) -> Option<ScriptMetrics> {
+        let mut metrics = self.os_2?.superscript_metrics();
+        if self.is_variable() {
+            self.apply_metrics_variation_to(Tag::from_bytes(b"spxs"), &mut metrics.x_size);
+            self.apply_metrics_variation_to(Tag::from_bytes(b"spys"), &mut metrics.y_size);
+            self.apply_metrics_variation_to(Tag::from_bytes(b"spxo"), &mut metrics.x_offset);
+            self.apply_metrics_variation_to(Tag::from_bytes(b"spyo"), &mut metrics.y_offset);
+        }
+        Some(metrics)
+    }
+
+    /// Returns a total number of glyphs in the font.
     ///
     /// Never zero.
     ///
@@ -1038,24 +1793,59 @@ impl<'a> Font<'a> {
         cmap::glyph_index(self.cmap.as_ref()?, c)
     }
 
+    /// Resolves a Glyph ID for a single-byte character code.
+    ///
+    /// Uses the `CFF` Encoding as a source. Unlike `glyph_index()`,
+    /// doesn't require a `cmap` table, which is useful for PostScript-based
+    /// and symbol fonts.
+    ///
+    /// Returns `None` when there is no `CFF` table, for CID-keyed fonts
+    /// or when `code` is not encoded.
+    #[inline]
+    pub fn glyph_index_by_code(&self, code: u8) -> Option<GlyphId> {
+        cff::glyph_index(self.cff_.as_ref()?, code)
+    }
+
     /// Resolves a variation of a Glyph ID from two code points.
     ///
     /// Implemented according to
     /// [Unicode Variation Sequences](
     /// https://docs.microsoft.com/en-us/typography/opentype/spec/cmap#format-14-unicode-variation-sequences).
     ///
-    /// Returns `None` instead of `0` when glyph is not found.
+    /// Returns `None` when the sequence is not supported by the font.
+    /// And `GlyphVariationResult::UseDefault` when the default glyph,
+    /// the one returned by `glyph_index()`, should be used.
     #[inline]
-    pub fn glyph_variation_index(&self, c: char, variation: char) -> Option<GlyphId> {
+    pub fn glyph_variation_index(&self, c: char, variation: char) -> Option<GlyphVariationResult> {
         cmap::glyph_variation_index(self.cmap.as_ref()?, c, variation)
     }
 
+    /// Returns an iterator over
+    /// [Unicode Variation Sequences](
+    /// https://docs.microsoft.com/en-us/typography/opentype/spec/cmap#format-14-unicode-variation-sequences)
+    /// variation selectors.
+    ///
+    /// An iterator can be empty.
+    #[inline]
+    pub fn variation_selectors(&self) -> VariationSelectors<'a> {
+        self.cmap.as_ref().map(cmap::variation_selectors).unwrap_or_default()
+    }
+
     /// Returns glyph's horizontal advance.
     ///
+    /// Fallbacks to `cff_glyph_width()` when `hmtx` table is not present.
+    ///
     /// This method is affected by variation axes.
     #[inline]
     pub fn glyph_hor_advance(&self, glyph_id: GlyphId) -> Option<u16> {
-        let mut advance = self.hmtx?.advance(glyph_id)? as f32;
+        let hmtx = match self.hmtx {
+            Some(v) => v,
+            None => {
+                return self.cff_.as_ref().and_then(|metadata| cff::glyph_advance(metadata, glyph_id));
+            }
+        };
+
+        let mut advance = hmtx.advance(glyph_id)? as f32;
 
         if self.is_variable() {
             // Ignore variation offset when `hvar` is not set.
@@ -1130,12 +1920,17 @@ impl<'a> Font<'a> {
 
     /// Returns glyph's name.
     ///
-    /// Uses the `post` table as a source.
+    /// Uses the `post` table as a source. Fallbacks to the `CFF` charset
+    /// and String INDEX when the `post` table has no names.
     ///
     /// Returns `None` when no name is associated with a `glyph`.
     #[inline]
     pub fn glyph_name(&self, glyph_id: GlyphId) -> Option<&str> {
-        self.post.and_then(|post| post.glyph_name(glyph_id))
+        if let Some(name) = self.post.and_then(|post| post.glyph_name(glyph_id)) {
+            return Some(name);
+        }
+
+        self.cff_.as_ref().and_then(|metadata| cff::glyph_name(metadata, glyph_id))
     }
 
     /// Checks that font has
@@ -1263,6 +2058,92 @@ impl<'a> Font<'a> {
         None
     }
 
+    /// Outlines a glyph and reports its PostScript hints.
+    ///
+    /// Stem hints and hint masks are reported at the positions they occur
+    /// in a charstring, so they can be matched with the following path segments.
+    ///
+    /// Works just like `outline_glyph()` for fonts without `CFF` or `CFF2` tables,
+    /// i.e. no hints will be reported.
+    #[inline]
+    pub fn outline_glyph_with_hints(
+        &self,
+        glyph_id: GlyphId,
+        builder: &mut dyn HintedOutlineBuilder,
+    ) -> Option<Rect> {
+        if self.gvar.is_none() && self.glyf.is_none() {
+            if let Some(ref metadata) = self.cff_ {
+                return cff::outline_with_hints(metadata, glyph_id, builder);
+            }
+
+            if let Some(ref metadata) = self.cff2 {
+                return cff2::outline_with_hints(metadata, self.coords(), glyph_id, builder);
+            }
+        }
+
+        self.outline_glyph(glyph_id, &mut HintedOutlineBuilderRef(builder))
+    }
+
+    /// Returns glyph instructions.
+    ///
+    /// Returns an empty iterator when glyph has no instructions.
+    ///
+    /// Returns `None` for empty glyphs or when `glyf` table is not present.
+    #[inline]
+    pub fn glyph_instructions(&self, glyph_id: GlyphId) -> Option<Instructions<'a>> {
+        glyf::instructions(self.loca?, self.glyf?, glyph_id).map(Instructions::new)
+    }
+
+    /// Returns the *Font Program* stored in the `fpgm` table.
+    #[inline]
+    pub fn font_program(&self) -> Option<Instructions<'a>> {
+        self.fpgm.map(Instructions::new)
+    }
+
+    /// Returns the *Control Value Program* stored in the `prep` table.
+    #[inline]
+    pub fn control_value_program(&self) -> Option<Instructions<'a>> {
+        self.prep.map(Instructions::new)
+    }
+
+    /// Returns control values stored in the `cvt ` table.
+    #[inline]
+    pub fn control_values(&self) -> Option<ControlValues<'a>> {
+        self.cvt_.map(ControlValues::parse)
+    }
+
+    /// Returns composite glyph components.
+    ///
+    /// Nested composite glyphs are not resolved.
+    ///
+    /// Returns `None` when glyph is not a composite one or `glyf` table is not present.
+    #[inline]
+    pub fn glyph_components(&self, glyph_id: GlyphId) -> Option<GlyphComponents<'a>> {
+        glyf::components(self.loca?, self.glyf?, glyph_id)
+    }
+
+    /// Returns simple glyph points as stored in the `glyf` table.
+    ///
+    /// Unlike `outline_glyph()`, implied on-curve points are not generated,
+    /// so point indices are the same as the ones used by TrueType instructions.
+    ///
+    /// When `apply_variations` is set and the font has a `gvar` table,
+    /// points will be adjusted using the current variation coordinates.
+    ///
+    /// Returns `None` when glyph is not a simple one or `glyf` table is not present.
+    #[inline]
+    pub fn glyph_points(&self, glyph_id: GlyphId, apply_variations: bool) -> Option<GlyphPoints<'a>> {
+        let mut points = glyf::points(self.loca?, self.glyf?, glyph_id)?;
+        if apply_variations {
+            if let Some(ref gvar_table) = self.gvar {
+                let points_len = points.number_of_points();
+                points.variation = Some(gvar_table.points_variation(glyph_id, self.coords(), points_len)?);
+            }
+        }
+
+        Some(points)
+    }
+
     /// Returns a tight glyph bounding box.
     ///
     /// Unless the current font has a `glyf` table, this is just a shorthand for `outline_glyph()`
@@ -1296,13 +2177,14 @@ impl<'a> Font<'a> {
     /// Note that this method will return an encoded image. It should be decoded
     /// by the caller. We don't validate or preprocess it in any way.
     ///
-    /// Currently, only PNG images are supported.
+    /// Currently, only PNG, JPEG and TIFF images and uncompressed bitmaps are supported.
+    /// Composite bitmaps are returned as is, see `RasterGlyphImage::components`.
     ///
     /// Also, a font can contain both: images and outlines. So when this method returns `None`
     /// you should also try `outline_glyph()` afterwards.
     ///
     /// There are multiple ways an image can be stored in a TrueType font
-    /// and this method supports only `sbix`, `CBLC`+`CBDT`.
+    /// and this method supports only `sbix`, `CBLC`+`CBDT` and `EBLC`+`EBDT`.
     #[inline]
     pub fn glyph_raster_image(&self, glyph_id: GlyphId, pixels_per_em: u16) -> Option<RasterGlyphImage> {
         if let Some(sbix_data) = self.sbix {
@@ -1314,6 +2196,11 @@ impl<'a> Font<'a> {
             return cbdt::parse(cbdt_data, location);
         }
 
+        if let (Some(eblc_data), Some(ebdt_data)) = (self.eblc, self.ebdt) {
+            let location = cblc::find_location(eblc_data, glyph_id, pixels_per_em)?;
+            return cbdt::parse(ebdt_data, location);
+        }
+
         None
     }
 
@@ -1330,7 +2217,23 @@ impl<'a> Font<'a> {
     /// you should also try `outline_glyph()` afterwards.
     #[inline]
     pub fn glyph_svg_image(&self, glyph_id: GlyphId) -> Option<&'a [u8]> {
-        self.svg_.and_then(|svg_data| svg::parse(svg_data, glyph_id))
+        self.glyph_svg_document(glyph_id).map(|doc| doc.data)
+    }
+
+    /// Returns an SVG document that contains a specified glyph.
+    ///
+    /// Unlike `glyph_svg_image()`, also returns a range of glyphs stored in the same document.
+    #[inline]
+    pub fn glyph_svg_document(&self, glyph_id: GlyphId) -> Option<SvgDocument<'a>> {
+        self.svg_?.find(glyph_id)
+    }
+
+    /// Returns an iterator over SVG documents.
+    ///
+    /// Multiple records can reference the same document, so documents can repeat.
+    #[inline]
+    pub fn svg_documents(&self) -> SvgDocuments<'a> {
+        self.svg_.unwrap_or_default()
     }
 
     /// Returns an iterator over variation axes.
@@ -1422,9 +2325,160 @@ pub fn fonts_in_collection(data: &[u8]) -> Option<u32> {
 }
 
 
+/// A standalone `CFF` or `CFF2` font handle.
+///
+/// Unlike `Font`, doesn't require an OpenType wrapper.
+/// Useful for fonts embedded into PDF as `FontFile3`.
+#[derive(Clone, Copy)]
+pub struct CFFFont<'a> {
+    kind: CFFFontKind<'a>,
+}
+
+#[derive(Clone, Copy)]
+enum CFFFontKind<'a> {
+    Cff(cff::Metadata<'a>),
+    Cff2(cff2::Metadata<'a>),
+}
+
+impl<'a> CFFFont<'a> {
+    /// Creates a `CFFFont` object from a raw `CFF` or `CFF2` data.
+    ///
+    /// Returns `None` when the data is malformed or has an unsupported version.
+    pub fn from_data(data: &'a [u8]) -> Option<Self> {
+        let kind = match data.first()? {
+            1 => CFFFontKind::Cff(cff::parse_metadata(data)?),
+            2 => CFFFontKind::Cff2(cff2::parse_metadata(data)?),
+            _ => return None,
+        };
+
+        Some(CFFFont { kind })
+    }
+
+    /// Checks that the font is stored using `CFF2`.
+    #[inline]
+    pub fn is_cff2(&self) -> bool {
+        match self.kind {
+            CFFFontKind::Cff(_) => false,
+            CFFFontKind::Cff2(_) => true,
+        }
+    }
+
+    /// Returns a total number of glyphs in the font.
+    #[inline]
+    pub fn number_of_glyphs(&self) -> u16 {
+        match self.kind {
+            CFFFontKind::Cff(ref metadata) => cff::number_of_glyphs(metadata),
+            CFFFontKind::Cff2(ref metadata) => cff2::number_of_glyphs(metadata),
+        }
+    }
+
+    /// Returns Top DICT values.
+    ///
+    /// Returns `None` for `CFF2`.
+    #[inline]
+    pub fn top_dict(&self) -> Option<CFFTopDict<'a>> {
+        match self.kind {
+            CFFFontKind::Cff(ref metadata) => Some(cff::top_dict(metadata)),
+            CFFFontKind::Cff2(_) => None,
+        }
+    }
+
+    /// Returns Private DICT values used by a glyph.
+    ///
+    /// Returns `None` for `CFF2`.
+    #[inline]
+    pub fn private_dict(&self, glyph_id: GlyphId) -> Option<CFFPrivateDict> {
+        match self.kind {
+            CFFFontKind::Cff(ref metadata) => cff::private_dict(metadata, glyph_id),
+            CFFFontKind::Cff2(_) => None,
+        }
+    }
+
+    /// Returns a font matrix.
+    ///
+    /// `CFF2` fonts always use the default one.
+    #[inline]
+    pub fn font_matrix(&self) -> FontMatrix {
+        self.top_dict().map(|dict| dict.font_matrix).unwrap_or_default()
+    }
+
+    /// Resolves a Glyph ID for a single-byte character code.
+    ///
+    /// Uses the Encoding. Returns `None` for `CFF2` and CID-keyed fonts.
+    #[inline]
+    pub fn glyph_index_by_code(&self, code: u8) -> Option<GlyphId> {
+        match self.kind {
+            CFFFontKind::Cff(ref metadata) => cff::glyph_index(metadata, code),
+            CFFFontKind::Cff2(_) => None,
+        }
+    }
+
+    /// Returns glyph's name.
+    ///
+    /// Returns `None` for `CFF2` and CID-keyed fonts.
+    #[inline]
+    pub fn glyph_name(&self, glyph_id: GlyphId) -> Option<&'a str> {
+        match self.kind {
+            CFFFontKind::Cff(ref metadata) => cff::glyph_name(metadata, glyph_id),
+            CFFFontKind::Cff2(_) => None,
+        }
+    }
+
+    /// Returns glyph's horizontal advance in font units.
+    ///
+    /// Uses the width stored in a charstring, `nominalWidthX` and `defaultWidthX`.
+    ///
+    /// Returns `None` for `CFF2`, since it doesn't store widths.
+    #[inline]
+    pub fn glyph_hor_advance(&self, glyph_id: GlyphId) -> Option<u16> {
+        match self.kind {
+            CFFFontKind::Cff(ref metadata) => cff::glyph_advance(metadata, glyph_id),
+            CFFFontKind::Cff2(_) => None,
+        }
+    }
+
+    /// Outlines a glyph and returns its tight bounding box.
+    ///
+    /// `CFF2` glyphs are outlined using default variation coordinates.
+    ///
+    /// Returns `None` when glyph has no outline or on error.
+    #[inline]
+    pub fn outline_glyph(&self, glyph_id: GlyphId, builder: &mut dyn OutlineBuilder) -> Option<Rect> {
+        self.outline_glyph_with_hints(glyph_id, &mut UnhintedOutlineBuilder(builder))
+    }
+
+    /// Outlines a glyph and reports its hints.
+    ///
+    /// See `Font::outline_glyph_with_hints()` for details.
+    #[inline]
+    pub fn outline_glyph_with_hints(
+        &self,
+        glyph_id: GlyphId,
+        builder: &mut dyn HintedOutlineBuilder,
+    ) -> Option<Rect> {
+        match self.kind {
+            CFFFontKind::Cff(ref metadata) => cff::outline_with_hints(metadata, glyph_id, builder),
+            CFFFontKind::Cff2(ref metadata) => {
+                // An empty coordinates list would fully apply all deltas,
+                // so we have to pass zeros explicitly.
+                let coords = [NormalizedCoord::default(); MAX_VAR_COORDS as usize];
+                cff2::outline_with_hints(metadata, &coords, glyph_id, builder)
+            }
+        }
+    }
+}
+
+impl fmt::Debug for CFFFont<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CFFFont()")
+    }
+}
+
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::vec::Vec;
     use crate::writer;
     use writer::TtfType::*;
 
@@ -1551,4 +2605,360 @@ mod tests {
         assert_eq!(fonts_in_collection(&data), Some(std::u32::MAX));
         assert!(Font::from_data(&data, std::u32::MAX - 1).is_none());
     }
+
+    #[test]
+    fn cff_glyph_width_without_hmtx() {
+        let cff = writer::convert(&[
+            // Header
+            UInt8(1), // major version
+            UInt8(0), // minor version
+            UInt8(4), // header size
+            UInt8(0), // absolute offset
+            // Name INDEX
+            UInt16(0), // count
+            // Top DICT INDEX
+            UInt16(1), // count
+            UInt8(1), // offset size
+            UInt8(1), // index[0]
+            UInt8(6), // index[1]
+            // Item 0
+            CFFInt(20),
+            UInt8(17), // CharStrings
+            CFFInt(8), // length
+            CFFInt(45), // offset
+            UInt8(18), // Private
+            // String INDEX
+            UInt16(0), // count
+            // Global Subroutines INDEX
+            UInt16(0), // count
+            // CharString INDEX (offset 20)
+            UInt16(4), // count
+            UInt8(1), // offset size
+            UInt8(1), UInt8(5), UInt8(8), UInt8(13), UInt8(18), // offsets
+            // Glyph 0
+            CFFInt(-10), // width
+            CFFInt(10), UInt8(22), // hmoveto
+            UInt8(14), // endchar
+            // Glyph 1
+            CFFInt(10), UInt8(22), // hmoveto
+            UInt8(14), // endchar
+            // Glyph 2
+            CFFInt(-501), // width
+            CFFInt(10), UInt8(22), // hmoveto
+            UInt8(14), // endchar
+            // Glyph 3
+            CFFInt(-500), // width
+            CFFInt(10), UInt8(22), // hmoveto
+            UInt8(14), // endchar
+            // Private DICT (offset 45)
+            CFFInt(300),
+            UInt8(20), // defaultWidthX
+            UInt8(30), Raw(&[0x50, 0x0a, 0x4f]), // 500.4
+            UInt8(21), // nominalWidthX
+        ]);
+
+        let data = writer::font(&[
+            (b"CFF ", &cff),
+            (b"head", &writer::head(1000, 0)),
+            (b"hhea", &writer::hhea(800, -200, 0, 0)),
+            (b"maxp", &writer::maxp(4)),
+        ]);
+        let font = Font::from_data(&data, 0).unwrap();
+
+        // nominalWidthX + width
+        assert_eq!(font.cff_glyph_width(GlyphId(0)), Some(490.4));
+        assert_eq!(font.glyph_hor_advance(GlyphId(0)), Some(490));
+        // defaultWidthX
+        assert_eq!(font.cff_glyph_width(GlyphId(1)), Some(300.0));
+        assert_eq!(font.glyph_hor_advance(GlyphId(1)), Some(300));
+        // Negative widths are rounded away from zero and cannot be represented.
+        assert!(font.glyph_hor_advance(GlyphId(2)).is_none());
+        assert_eq!(font.glyph_hor_advance(GlyphId(3)), Some(0));
+        assert!(font.glyph_hor_advance(GlyphId(4)).is_none());
+    }
+
+    fn line_spacing_font(hhea: (i16, i16, i16), os_2: Option<&[u8]>) -> Vec<u8> {
+        let hhea = writer::hhea(hhea.0, hhea.1, hhea.2, 0);
+        let mut tables: Vec<(&[u8; 4], &[u8])> = Vec::new();
+        if let Some(os_2) = os_2 {
+            tables.push((b"OS/2", os_2));
+        }
+        let head = writer::head(1000, 0);
+        let maxp = writer::maxp(1);
+        tables.push((b"head", &head));
+        tables.push((b"hhea", &hhea));
+        tables.push((b"maxp", &maxp));
+        writer::font(&tables)
+    }
+
+    fn line_spacing(data: &[u8], convention: LineSpacingConvention) -> (i16, i16, i16, LineSpacingSource) {
+        let font = Font::from_data(data, 0).unwrap();
+        let m = font.line_spacing(convention);
+        (m.ascender, m.descender, m.line_gap, m.source)
+    }
+
+    const USE_TYPO_METRICS: u16 = 1 << 7;
+
+    #[test]
+    fn line_spacing_browser() {
+        use LineSpacingConvention::Browser;
+        use LineSpacingSource::*;
+
+        let os_2 = writer::os2(4, 0, 0, (700, -300, 50), (900, 250));
+        let data = line_spacing_font((800, -200, 100), Some(&os_2));
+        assert_eq!(line_spacing(&data, Browser), (800, -200, 100, HorizontalHeader));
+
+        let os_2 = writer::os2(4, 0, USE_TYPO_METRICS, (700, -300, 50), (900, 250));
+        let data = line_spacing_font((800, -200, 100), Some(&os_2));
+        assert_eq!(line_spacing(&data, Browser), (700, -300, 50, Typographic));
+
+        // Zero `hhea` metrics fallback to typographic and then to Windows ones.
+        let os_2 = writer::os2(4, 0, 0, (700, -300, 50), (900, 250));
+        let data = line_spacing_font((0, 0, 100), Some(&os_2));
+        assert_eq!(line_spacing(&data, Browser), (700, -300, 50, Typographic));
+
+        let os_2 = writer::os2(4, 0, 0, (0, 0, 50), (900, 250));
+        let data = line_spacing_font((0, 0, 100), Some(&os_2));
+        assert_eq!(line_spacing(&data, Browser), (900, -250, 0, Windows));
+
+        let data = line_spacing_font((0, 0, 100), None);
+        assert_eq!(line_spacing(&data, Browser), (0, 0, 100, HorizontalHeader));
+    }
+
+    #[test]
+    fn line_spacing_direct_write() {
+        use LineSpacingConvention::DirectWrite;
+        use LineSpacingSource::*;
+
+        // Line gap is the `hhea` line height minus the Windows one.
+        let os_2 = writer::os2(4, 0, 0, (700, -300, 50), (900, 250));
+        let data = line_spacing_font((800, -200, 200), Some(&os_2));
+        assert_eq!(line_spacing(&data, DirectWrite), (900, -250, 50, Windows));
+
+        let os_2 = writer::os2(4, 0, USE_TYPO_METRICS, (700, -300, 50), (900, 250));
+        let data = line_spacing_font((800, -200, 200), Some(&os_2));
+        assert_eq!(line_spacing(&data, DirectWrite), (700, -300, 50, Typographic));
+
+        // A negative line gap is clamped.
+        let os_2 = writer::os2(4, 0, 0, (700, -300, 50), (900, 250));
+        let data = line_spacing_font((0, 0, 0), Some(&os_2));
+        assert_eq!(line_spacing(&data, DirectWrite), (900, -250, 0, Windows));
+
+        let data = line_spacing_font((800, -200, 200), None);
+        assert_eq!(line_spacing(&data, DirectWrite), (800, -200, 200, HorizontalHeader));
+    }
+
+    #[test]
+    fn line_spacing_core_text() {
+        use LineSpacingConvention::CoreText;
+        use LineSpacingSource::*;
+
+        let os_2 = writer::os2(4, 0, 0, (700, -300, 50), (900, 250));
+        let data = line_spacing_font((800, -200, 100), Some(&os_2));
+        assert_eq!(line_spacing(&data, CoreText), (800, -200, 100, HorizontalHeader));
+
+        // `USE_TYPO_METRICS` is ignored.
+        let os_2 = writer::os2(4, 0, USE_TYPO_METRICS, (700, -300, 50), (900, 250));
+        let data = line_spacing_font((800, -200, 100), Some(&os_2));
+        assert_eq!(line_spacing(&data, CoreText), (800, -200, 100, HorizontalHeader));
+
+        // No fallbacks.
+        let os_2 = writer::os2(4, 0, 0, (700, -300, 50), (900, 250));
+        let data = line_spacing_font((0, 0, 0), Some(&os_2));
+        assert_eq!(line_spacing(&data, CoreText), (0, 0, 0, HorizontalHeader));
+    }
+
+    #[test]
+    fn line_spacing_use_typo_metrics_before_v4() {
+        // The flag was introduced in version 4.
+        let os_2 = writer::os2(3, 0, USE_TYPO_METRICS, (700, -300, 50), (900, 250));
+        let data = line_spacing_font((800, -200, 100), Some(&os_2));
+        assert_eq!(line_spacing(&data, LineSpacingConvention::Browser),
+                   (800, -200, 100, LineSpacingSource::HorizontalHeader));
+    }
+
+    // Builds a font with a `name` table containing English Windows names.
+    #[cfg(feature = "alloc")]
+    fn names_font(names: &[(u16, &str)]) -> Vec<u8> {
+        let mut records = Vec::new();
+        let mut storage = Vec::new();
+        for (name_id, name) in names {
+            let start = storage.len();
+            for c in name.encode_utf16() {
+                storage.extend_from_slice(&writer::convert(&[UInt16(c)]));
+            }
+
+            records.extend_from_slice(&[
+                UInt16(3), // platformID
+                UInt16(1), // encodingID
+                UInt16(0x0409), // languageID
+                UInt16(*name_id), // nameID
+                UInt16((storage.len() - start) as u16), // length
+                UInt16(start as u16), // offset
+            ]);
+        }
+
+        let mut name = writer::convert(&[
+            UInt16(0), // version
+            UInt16(names.len() as u16), // count
+            UInt16(6 + records.len() as u16 * 2), // storageOffset
+        ]);
+        name.extend_from_slice(&writer::convert(&records));
+        name.extend_from_slice(&storage);
+
+        writer::font(&[
+            (b"head", &writer::head(1000, 0)),
+            (b"hhea", &writer::hhea(800, -200, 0, 0)),
+            (b"maxp", &writer::maxp(1)),
+            (b"name", &name),
+        ])
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn family_name_fallback() {
+        let data = names_font(&[
+            (name_id::FAMILY, "Family"),
+            (name_id::TYPOGRAPHIC_FAMILY, "Typographic"),
+            (name_id::WWS_FAMILY, "WWS"),
+        ]);
+        let font = Font::from_data(&data, 0).unwrap();
+        assert_eq!(font.family_name().unwrap(), "Typographic");
+
+        let data = names_font(&[
+            (name_id::FAMILY, "Family"),
+            (name_id::WWS_FAMILY, "WWS"),
+        ]);
+        let font = Font::from_data(&data, 0).unwrap();
+        assert_eq!(font.family_name().unwrap(), "WWS");
+
+        let data = names_font(&[(name_id::FAMILY, "Family")]);
+        let font = Font::from_data(&data, 0).unwrap();
+        assert_eq!(font.family_name().unwrap(), "Family");
+
+        let data = names_font(&[(name_id::SUBFAMILY, "Subfamily")]);
+        let font = Font::from_data(&data, 0).unwrap();
+        assert!(font.family_name().is_none());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn subfamily_name_fallback() {
+        let data = names_font(&[
+            (name_id::SUBFAMILY, "Italic"),
+            (name_id::TYPOGRAPHIC_SUBFAMILY, "Semibold Italic"),
+            (name_id::WWS_SUBFAMILY, "Semibold Oblique"),
+        ]);
+        let font = Font::from_data(&data, 0).unwrap();
+        assert_eq!(font.subfamily_name().unwrap(), "Semibold Italic");
+
+        let data = names_font(&[
+            (name_id::SUBFAMILY, "Italic"),
+            (name_id::WWS_SUBFAMILY, "Semibold Oblique"),
+        ]);
+        let font = Font::from_data(&data, 0).unwrap();
+        assert_eq!(font.subfamily_name().unwrap(), "Semibold Oblique");
+
+        let data = names_font(&[(name_id::SUBFAMILY, "Italic")]);
+        let font = Font::from_data(&data, 0).unwrap();
+        assert_eq!(font.subfamily_name().unwrap(), "Italic");
+
+        let data = names_font(&[(name_id::FAMILY, "Family")]);
+        let font = Font::from_data(&data, 0).unwrap();
+        assert!(font.subfamily_name().is_none());
+    }
+
+    struct HintsBuilder(std::string::String);
+
+    impl OutlineBuilder for HintsBuilder {
+        fn move_to(&mut self, x: f32, y: f32) {
+            self.0 += &std::format!("M {} {} ", x, y);
+        }
+
+        fn line_to(&mut self, x: f32, y: f32) {
+            self.0 += &std::format!("L {} {} ", x, y);
+        }
+
+        fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+            self.0 += &std::format!("Q {} {} {} {} ", x1, y1, x, y);
+        }
+
+        fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+            self.0 += &std::format!("C {} {} {} {} {} {} ", x1, y1, x2, y2, x, y);
+        }
+
+        fn close(&mut self) {
+            self.0 += "Z ";
+        }
+    }
+
+    impl HintedOutlineBuilder for HintsBuilder {
+        fn hstem(&mut self, y: f32, dy: f32) {
+            self.0 += &std::format!("HS {} {} ", y, dy);
+        }
+
+        fn vstem(&mut self, x: f32, dx: f32) {
+            self.0 += &std::format!("VS {} {} ", x, dx);
+        }
+
+        fn hint_mask(&mut self, mask: &[u8]) {
+            self.0 += &std::format!("HM {:?} ", mask);
+        }
+
+        fn counter_mask(&mut self, mask: &[u8]) {
+            self.0 += &std::format!("CM {:?} ", mask);
+        }
+    }
+
+    #[test]
+    fn cff2_hints() {
+        let char_string = writer::convert(&[
+            CFFInt(10), CFFInt(20), CFFInt(30), CFFInt(40),
+            UInt8(18), // hstemhm
+            CFFInt(15), CFFInt(25),
+            UInt8(20), UInt8(0b1110_0000), // cntrmask with an implicit vstem
+            UInt8(19), UInt8(0b1010_0000), // hintmask
+            CFFInt(10), CFFInt(20), UInt8(21), // rmoveto
+            UInt8(19), UInt8(0b0110_0000), // hintmask
+            CFFInt(30), CFFInt(40), UInt8(5), // rlineto
+        ]);
+
+        let mut cff2 = writer::convert(&[
+            // Header
+            UInt8(2), // major version
+            UInt8(0), // minor version
+            UInt8(5), // header size
+            UInt16(6), // Top DICT length
+            // Top DICT
+            UInt8(29), Int32(15), // CharStrings offset
+            UInt8(17),
+            // Global Subroutines INDEX
+            UInt32(0), // count
+            // CharStrings INDEX (offset 15)
+            UInt32(1), // count
+            UInt8(1), // offset size
+            UInt8(1), // index[0]
+            UInt8(char_string.len() as u8 + 1), // index[1]
+        ]);
+        cff2.extend_from_slice(&char_string);
+
+        let data = writer::font(&[
+            (b"CFF2", &cff2),
+            (b"head", &writer::head(1000, 0)),
+            (b"hhea", &writer::hhea(800, -200, 0, 0)),
+            (b"maxp", &writer::maxp(1)),
+        ]);
+        let font = Font::from_data(&data, 0).unwrap();
+
+        let mut builder = HintsBuilder(std::string::String::new());
+        let bbox = font.outline_glyph_with_hints(GlyphId(0), &mut builder).unwrap();
+        assert_eq!(builder.0, "HS 10 20 HS 60 40 VS 15 25 CM [224] HM [160] M 10 20 \
+                               HM [96] L 40 60 ");
+        assert_eq!(bbox, Rect { x_min: 10, y_min: 20, x_max: 40, y_max: 60 });
+
+        // Hints are skipped by the regular outlining.
+        let mut builder = HintsBuilder(std::string::String::new());
+        font.outline_glyph(GlyphId(0), &mut builder).unwrap();
+        assert_eq!(builder.0, "M 10 20 L 40 60 ");
+    }
 }
@@ -0,0 +1,217 @@
+//! Decoders for the Macintosh platform
+//! [encodings](https://docs.microsoft.com/en-us/typography/opentype/spec/name#macintosh-encoding-ids-script-manager-codes).
+//!
+//! Single-byte encodings are built-in. CJK encodings are decoded via `encoding_rs`
+//! and require the `mac-cjk` feature.
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+// https://docs.microsoft.com/en-us/typography/opentype/spec/name#macintosh-encoding-ids-script-manager-codes
+const ROMAN_ENCODING_ID: u16 = 0;
+#[cfg(feature = "mac-cjk")]
+const JAPANESE_ENCODING_ID: u16 = 1;
+#[cfg(feature = "mac-cjk")]
+const CHINESE_TRADITIONAL_ENCODING_ID: u16 = 2;
+#[cfg(feature = "mac-cjk")]
+const KOREAN_ENCODING_ID: u16 = 3;
+const GREEK_ENCODING_ID: u16 = 6;
+const RUSSIAN_ENCODING_ID: u16 = 7;
+#[cfg(feature = "mac-cjk")]
+const CHINESE_SIMPLIFIED_ENCODING_ID: u16 = 25;
+const SLAVIC_ENCODING_ID: u16 = 29;
+
+
+/// A single-byte encoding table for the 0x80..=0xFF range.
+pub type SingleByteTable = [u16; 128];
+
+/// Checks that a Macintosh encoding can be decoded.
+#[inline]
+pub fn is_supported(encoding_id: u16) -> bool {
+    single_byte_table(encoding_id).is_some() || is_supported_cjk(encoding_id)
+}
+
+/// Decodes a Macintosh-encoded string.
+///
+/// Returns `None` for an unsupported encoding or a malformed data.
+#[cfg(feature = "alloc")]
+pub fn decode(encoding_id: u16, data: &[u8]) -> Option<String> {
+    if let Some(table) = single_byte_table(encoding_id) {
+        return Some(data.iter().map(|&b| decode_single_byte(table, b)).collect());
+    }
+
+    decode_cjk(encoding_id, data)
+}
+
+/// Returns a table for a single-byte encoding.
+///
+/// Returns `None` for an unsupported or a multi-byte encoding.
+#[inline]
+pub fn single_byte_table(encoding_id: u16) -> Option<&'static SingleByteTable> {
+    match encoding_id {
+        ROMAN_ENCODING_ID => Some(&MAC_ROMAN),
+        GREEK_ENCODING_ID => Some(&MAC_GREEK),
+        RUSSIAN_ENCODING_ID => Some(&MAC_CYRILLIC),
+        SLAVIC_ENCODING_ID => Some(&MAC_CENTRAL_EUROPEAN),
+        _ => None,
+    }
+}
+
+/// Decodes a single byte using the provided table.
+#[inline]
+pub fn decode_single_byte(table: &SingleByteTable, b: u8) -> char {
+    if b < 0x80 {
+        char::from(b)
+    } else {
+        // All table values are valid BMP code points.
+        let c = table[usize::from(b - 0x80)];
+        core::char::from_u32(u32::from(c)).unwrap_or(core::char::REPLACEMENT_CHARACTER)
+    }
+}
+
+#[cfg(feature = "mac-cjk")]
+#[inline]
+fn cjk_encoding(encoding_id: u16) -> Option<&'static encoding_rs::Encoding> {
+    // Mac CJK encodings are Apple's extensions of the common ones,
+    // so we are using the closest supersets.
+    match encoding_id {
+        JAPANESE_ENCODING_ID => Some(encoding_rs::SHIFT_JIS),
+        CHINESE_TRADITIONAL_ENCODING_ID => Some(encoding_rs::BIG5),
+        KOREAN_ENCODING_ID => Some(encoding_rs::EUC_KR),
+        CHINESE_SIMPLIFIED_ENCODING_ID => Some(encoding_rs::GBK),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "mac-cjk")]
+#[inline]
+fn is_supported_cjk(encoding_id: u16) -> bool {
+    cjk_encoding(encoding_id).is_some()
+}
+
+#[cfg(not(feature = "mac-cjk"))]
+#[inline]
+fn is_supported_cjk(_: u16) -> bool {
+    false
+}
+
+#[cfg(feature = "mac-cjk")]
+fn decode_cjk(encoding_id: u16, data: &[u8]) -> Option<String> {
+    cjk_encoding(encoding_id)?
+        .decode_without_bom_handling_and_without_replacement(data)
+        .map(|s| s.into_owned())
+}
+
+#[cfg(all(feature = "alloc", not(feature = "mac-cjk")))]
+fn decode_cjk(_: u16, _: &[u8]) -> Option<String> {
+    None
+}
+
+
+// https://www.unicode.org/Public/MAPPINGS/VENDORS/APPLE/ROMAN.TXT
+const MAC_ROMAN: SingleByteTable = [
+    0x00C4, 0x00C5, 0x00C7, 0x00C9, 0x00D1, 0x00D6, 0x00DC, 0x00E1,
+    0x00E0, 0x00E2, 0x00E4, 0x00E3, 0x00E5, 0x00E7, 0x00E9, 0x00E8,
+    0x00EA, 0x00EB, 0x00ED, 0x00EC, 0x00EE, 0x00EF, 0x00F1, 0x00F3,
+    0x00F2, 0x00F4, 0x00F6, 0x00F5, 0x00FA, 0x00F9, 0x00FB, 0x00FC,
+    0x2020, 0x00B0, 0x00A2, 0x00A3, 0x00A7, 0x2022, 0x00B6, 0x00DF,
+    0x00AE, 0x00A9, 0x2122, 0x00B4, 0x00A8, 0x2260, 0x00C6, 0x00D8,
+    0x221E, 0x00B1, 0x2264, 0x2265, 0x00A5, 0x00B5, 0x2202, 0x2211,
+    0x220F, 0x03C0, 0x222B, 0x00AA, 0x00BA, 0x03A9, 0x00E6, 0x00F8,
+    0x00BF, 0x00A1, 0x00AC, 0x221A, 0x0192, 0x2248, 0x2206, 0x00AB,
+    0x00BB, 0x2026, 0x00A0, 0x00C0, 0x00C3, 0x00D5, 0x0152, 0x0153,
+    0x2013, 0x2014, 0x201C, 0x201D, 0x2018, 0x2019, 0x00F7, 0x25CA,
+    0x00FF, 0x0178, 0x2044, 0x20AC, 0x2039, 0x203A, 0xFB01, 0xFB02,
+    0x2021, 0x00B7, 0x201A, 0x201E, 0x2030, 0x00C2, 0x00CA, 0x00C1,
+    0x00CB, 0x00C8, 0x00CD, 0x00CE, 0x00CF, 0x00CC, 0x00D3, 0x00D4,
+    0xF8FF, 0x00D2, 0x00DA, 0x00DB, 0x00D9, 0x0131, 0x02C6, 0x02DC,
+    0x00AF, 0x02D8, 0x02D9, 0x02DA, 0x00B8, 0x02DD, 0x02DB, 0x02C7,
+];
+
+// https://www.unicode.org/Public/MAPPINGS/VENDORS/APPLE/GREEK.TXT
+const MAC_GREEK: SingleByteTable = [
+    0x00C4, 0x00B9, 0x00B2, 0x00C9, 0x00B3, 0x00D6, 0x00DC, 0x0385,
+    0x00E0, 0x00E2, 0x00E4, 0x0384, 0x00A8, 0x00E7, 0x00E9, 0x00E8,
+    0x00EA, 0x00EB, 0x00A3, 0x2122, 0x00EE, 0x00EF, 0x2022, 0x00BD,
+    0x2030, 0x00F4, 0x00F6, 0x00A6, 0x20AC, 0x00F9, 0x00FB, 0x00FC,
+    0x2020, 0x0393, 0x0394, 0x0398, 0x039B, 0x039E, 0x03A0, 0x00DF,
+    0x00AE, 0x00A9, 0x03A3, 0x03AA, 0x00A7, 0x2260, 0x00B0, 0x00B7,
+    0x0391, 0x00B1, 0x2264, 0x2265, 0x00A5, 0x0392, 0x0395, 0x0396,
+    0x0397, 0x0399, 0x039A, 0x039C, 0x03A6, 0x03AB, 0x03A8, 0x03A9,
+    0x03AC, 0x039D, 0x00AC, 0x039F, 0x03A1, 0x2248, 0x03A4, 0x00AB,
+    0x00BB, 0x2026, 0x00A0, 0x03A5, 0x03A7, 0x0386, 0x0388, 0x0153,
+    0x2013, 0x2015, 0x201C, 0x201D, 0x2018, 0x2019, 0x00F7, 0x0389,
+    0x038A, 0x038C, 0x038E, 0x03AD, 0x03AE, 0x03AF, 0x03CC, 0x038F,
+    0x03CD, 0x03B1, 0x03B2, 0x03C8, 0x03B4, 0x03B5, 0x03C6, 0x03B3,
+    0x03B7, 0x03B9, 0x03BE, 0x03BA, 0x03BB, 0x03BC, 0x03BD, 0x03BF,
+    0x03C0, 0x03CE, 0x03C1, 0x03C3, 0x03C4, 0x03B8, 0x03C9, 0x03C2,
+    0x03C7, 0x03C5, 0x03B6, 0x03CA, 0x03CB, 0x0390, 0x03B0, 0x00AD,
+];
+
+// https://www.unicode.org/Public/MAPPINGS/VENDORS/APPLE/CYRILLIC.TXT
+const MAC_CYRILLIC: SingleByteTable = [
+    0x0410, 0x0411, 0x0412, 0x0413, 0x0414, 0x0415, 0x0416, 0x0417,
+    0x0418, 0x0419, 0x041A, 0x041B, 0x041C, 0x041D, 0x041E, 0x041F,
+    0x0420, 0x0421, 0x0422, 0x0423, 0x0424, 0x0425, 0x0426, 0x0427,
+    0x0428, 0x0429, 0x042A, 0x042B, 0x042C, 0x042D, 0x042E, 0x042F,
+    0x2020, 0x00B0, 0x0490, 0x00A3, 0x00A7, 0x2022, 0x00B6, 0x0406,
+    0x00AE, 0x00A9, 0x2122, 0x0402, 0x0452, 0x2260, 0x0403, 0x0453,
+    0x221E, 0x00B1, 0x2264, 0x2265, 0x0456, 0x00B5, 0x0491, 0x0408,
+    0x0404, 0x0454, 0x0407, 0x0457, 0x0409, 0x0459, 0x040A, 0x045A,
+    0x0458, 0x0405, 0x00AC, 0x221A, 0x0192, 0x2248, 0x2206, 0x00AB,
+    0x00BB, 0x2026, 0x00A0, 0x040B, 0x045B, 0x040C, 0x045C, 0x0455,
+    0x2013, 0x2014, 0x201C, 0x201D, 0x2018, 0x2019, 0x00F7, 0x201E,
+    0x040E, 0x045E, 0x040F, 0x045F, 0x2116, 0x0401, 0x0451, 0x044F,
+    0x0430, 0x0431, 0x0432, 0x0433, 0x0434, 0x0435, 0x0436, 0x0437,
+    0x0438, 0x0439, 0x043A, 0x043B, 0x043C, 0x043D, 0x043E, 0x043F,
+    0x0440, 0x0441, 0x0442, 0x0443, 0x0444, 0x0445, 0x0446, 0x0447,
+    0x0448, 0x0449, 0x044A, 0x044B, 0x044C, 0x044D, 0x044E, 0x20AC,
+];
+
+// https://www.unicode.org/Public/MAPPINGS/VENDORS/APPLE/CENTEURO.TXT
+const MAC_CENTRAL_EUROPEAN: SingleByteTable = [
+    0x00C4, 0x0100, 0x0101, 0x00C9, 0x0104, 0x00D6, 0x00DC, 0x00E1,
+    0x0105, 0x010C, 0x00E4, 0x010D, 0x0106, 0x0107, 0x00E9, 0x0179,
+    0x017A, 0x010E, 0x00ED, 0x010F, 0x0112, 0x0113, 0x0116, 0x00F3,
+    0x0117, 0x00F4, 0x00F6, 0x00F5, 0x00FA, 0x011A, 0x011B, 0x00FC,
+    0x2020, 0x00B0, 0x0118, 0x00A3, 0x00A7, 0x2022, 0x00B6, 0x00DF,
+    0x00AE, 0x00A9, 0x2122, 0x0119, 0x00A8, 0x2260, 0x0123, 0x012E,
+    0x012F, 0x012A, 0x2264, 0x2265, 0x012B, 0x0136, 0x2202, 0x2211,
+    0x0142, 0x013B, 0x013C, 0x013D, 0x013E, 0x0139, 0x013A, 0x0145,
+    0x0146, 0x0143, 0x00AC, 0x221A, 0x0144, 0x0147, 0x2206, 0x00AB,
+    0x00BB, 0x2026, 0x00A0, 0x0148, 0x0150, 0x00D5, 0x0151, 0x014C,
+    0x2013, 0x2014, 0x201C, 0x201D, 0x2018, 0x2019, 0x00F7, 0x25CA,
+    0x014D, 0x0154, 0x0155, 0x0158, 0x2039, 0x203A, 0x0159, 0x0156,
+    0x0157, 0x0160, 0x201A, 0x201E, 0x0161, 0x015A, 0x015B, 0x00C1,
+    0x0164, 0x0165, 0x00CD, 0x017D, 0x017E, 0x016A, 0x00D3, 0x00D4,
+    0x016B, 0x016E, 0x00DA, 0x016F, 0x0170, 0x0171, 0x0172, 0x0173,
+    0x00DD, 0x00FD, 0x0137, 0x017B, 0x0141, 0x017C, 0x0122, 0x02C7,
+];
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roman() {
+        assert_eq!(decode(ROMAN_ENCODING_ID, b"Caf\x8E \xA9 \xDB").unwrap(), "Café © €");
+    }
+
+    #[test]
+    fn cyrillic() {
+        assert_eq!(decode(RUSSIAN_ENCODING_ID, b"\x92\xE8\xEF").unwrap(), "Тип");
+    }
+
+    #[test]
+    fn unsupported() {
+        assert!(!is_supported(32));
+        assert!(decode(32, b"Test").is_none());
+    }
+
+    #[cfg(feature = "mac-cjk")]
+    #[test]
+    fn japanese() {
+        assert_eq!(decode(JAPANESE_ENCODING_ID, b"\x96\xBE\x92\xA9").unwrap(), "明朝");
+    }
+}
@@ -68,6 +68,15 @@ impl FromData for i32 {
     }
 }
 
+impl FromData for i64 {
+    const SIZE: usize = 8;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        data.try_into().ok().map(i64::from_be_bytes)
+    }
+}
+
 
 // https://docs.microsoft.com/en-us/typography/opentype/spec/otff#data-types
 #[derive(Clone, Copy, Debug)]
@@ -462,6 +471,16 @@ pub struct LazyArrayIter32<'a, T> {
     index: u32,
 }
 
+impl<T: FromData> Default for LazyArrayIter32<'_, T> {
+    #[inline]
+    fn default() -> Self {
+        LazyArrayIter32 {
+            data: LazyArray32::new(&[]),
+            index: 0,
+        }
+    }
+}
+
 impl<'a, T: FromData> Iterator for LazyArrayIter32<'a, T> {
     type Item = T;
 
@@ -509,11 +528,6 @@ impl<'a> Stream<'a> {
         self.offset >= self.data.len()
     }
 
-    #[inline]
-    pub fn jump_to_end(&mut self) {
-        self.offset = self.data.len();
-    }
-
     #[inline]
     pub fn offset(&self) -> usize {
         self.offset
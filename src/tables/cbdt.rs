@@ -1,8 +1,11 @@
 // https://docs.microsoft.com/en-us/typography/opentype/spec/cbdt
+// https://docs.microsoft.com/en-us/typography/opentype/spec/ebdt
+//
+// `EBDT` and `CBDT` tables have the same structure, so we are using the same code for both.
 
-use crate::{RasterGlyphImage, RasterImageFormat};
-use crate::parser::{Stream, NumFrom};
-use super::cblc::{BitmapFormat, Location};
+use crate::{GlyphId, RasterGlyphImage, RasterImageFormat, RasterImageVerticalMetrics};
+use crate::parser::{Stream, FromData, NumFrom};
+use super::cblc::{BitmapFormat, Location, Metrics};
 
 pub fn parse(
     data: &[u8],
@@ -10,60 +13,268 @@ pub fn parse(
 ) -> Option<RasterGlyphImage> {
     let mut s = Stream::new_at(data, location.offset)?;
     match location.format {
+        BitmapFormat::Format1 => {
+            let metrics = Metrics::parse_small(&mut s)?;
+            parse_bitmap(&mut s, metrics, location, true)
+        }
+        BitmapFormat::Format2 => {
+            let metrics = Metrics::parse_small(&mut s)?;
+            parse_bitmap(&mut s, metrics, location, false)
+        }
+        BitmapFormat::Format5 => {
+            parse_bitmap(&mut s, location.metrics, location, false)
+        }
+        BitmapFormat::Format6 => {
+            let metrics = Metrics::parse_big(&mut s)?;
+            parse_bitmap(&mut s, metrics, location, true)
+        }
+        BitmapFormat::Format7 => {
+            let metrics = Metrics::parse_big(&mut s)?;
+            parse_bitmap(&mut s, metrics, location, false)
+        }
+        BitmapFormat::Format8 => {
+            let metrics = Metrics::parse_small(&mut s)?;
+            s.skip::<u8>(); // pad
+            parse_composite(&mut s, metrics, location)
+        }
+        BitmapFormat::Format9 => {
+            let metrics = Metrics::parse_big(&mut s)?;
+            parse_composite(&mut s, metrics, location)
+        }
         BitmapFormat::Format17 => {
-            let height: u8 = s.read()?;
-            let width: u8 = s.read()?;
-            let bearing_x: i8 = s.read()?;
-            let bearing_y: i8 = s.read()?;
-            s.skip::<u8>(); // advance
-            let data_len: u32 = s.read()?;
-            let data = s.read_bytes(usize::num_from(data_len))?;
-            Some(RasterGlyphImage {
-                x: i16::from(bearing_x),
-                // `y` in CBDT is a bottom bound, not top one.
-                y: i16::from(bearing_y) - i16::from(height),
-                width: u16::from(width),
-                height: u16::from(height),
-                pixels_per_em: location.ppem,
-                format: RasterImageFormat::PNG,
-                data,
-            })
+            let metrics = Metrics::parse_small(&mut s)?;
+            parse_png(&mut s, metrics, location)
         }
         BitmapFormat::Format18 => {
-            let height: u8 = s.read()?;
-            let width: u8 = s.read()?;
-            let hor_bearing_x: i8 = s.read()?;
-            let hor_bearing_y: i8 = s.read()?;
-            s.skip::<u8>(); // hor_advance
-            s.skip::<i8>(); // ver_bearing_x
-            s.skip::<i8>(); // ver_bearing_y
-            s.skip::<u8>(); // ver_advance
-            let data_len: u32 = s.read()?;
-            let data = s.read_bytes(usize::num_from(data_len))?;
-            Some(RasterGlyphImage {
-                x: i16::from(hor_bearing_x),
-                // `y` in CBDT is a bottom bound, not top one.
-                y: i16::from(hor_bearing_y) - i16::from(height),
-                width: u16::from(width),
-                height: u16::from(height),
-                pixels_per_em: location.ppem,
-                format: RasterImageFormat::PNG,
-                data,
-            })
+            let metrics = Metrics::parse_big(&mut s)?;
+            parse_png(&mut s, metrics, location)
         }
         BitmapFormat::Format19 => {
-            let data_len: u32 = s.read()?;
-            let data = s.read_bytes(usize::num_from(data_len))?;
-            Some(RasterGlyphImage {
-                x: i16::from(location.metrics.x),
-                // `y` in CBDT is a bottom bound, not top one.
-                y: i16::from(location.metrics.y) - i16::from(location.metrics.height),
-                width: u16::from(location.metrics.width),
-                height: u16::from(location.metrics.height),
-                pixels_per_em: location.ppem,
-                format: RasterImageFormat::PNG,
-                data,
-            })
+            parse_png(&mut s, location.metrics, location)
+        }
+    }
+}
+
+fn parse_png<'a>(
+    s: &mut Stream<'a>,
+    metrics: Metrics,
+    location: Location,
+) -> Option<RasterGlyphImage<'a>> {
+    let data_len: u32 = s.read()?;
+    let data = s.read_bytes(usize::num_from(data_len))?;
+    Some(make_image(metrics, location, RasterImageFormat::PNG, data))
+}
+
+fn parse_bitmap<'a>(
+    s: &mut Stream<'a>,
+    metrics: Metrics,
+    location: Location,
+    byte_aligned: bool,
+) -> Option<RasterGlyphImage<'a>> {
+    let format = match (location.bit_depth, byte_aligned) {
+        (1, true) => RasterImageFormat::BitmapMono,
+        (1, false) => RasterImageFormat::BitmapMonoPacked,
+        (2, true) => RasterImageFormat::BitmapGray2,
+        (2, false) => RasterImageFormat::BitmapGray2Packed,
+        (4, true) => RasterImageFormat::BitmapGray4,
+        (4, false) => RasterImageFormat::BitmapGray4Packed,
+        (8, _) => RasterImageFormat::BitmapGray8,
+        _ => return None,
+    };
+
+    // Bitmaps do not store their data length, so we have to calculate it.
+    let width = usize::from(metrics.width);
+    let height = usize::from(metrics.height);
+    let bit_depth = usize::from(location.bit_depth);
+    let data_len = if byte_aligned {
+        bits_to_bytes(width * bit_depth) * height
+    } else {
+        bits_to_bytes(width * height * bit_depth)
+    };
+
+    let data = s.read_bytes(data_len)?;
+    Some(make_image(metrics, location, format, data))
+}
+
+#[inline]
+fn bits_to_bytes(n: usize) -> usize {
+    n / 8 + usize::from(n & 7 != 0)
+}
+
+fn parse_composite<'a>(
+    s: &mut Stream<'a>,
+    metrics: Metrics,
+    location: Location,
+) -> Option<RasterGlyphImage<'a>> {
+    let count: u16 = s.read()?;
+    let data = s.read_bytes(usize::from(count) * Component::SIZE)?;
+    Some(make_image(metrics, location, RasterImageFormat::BitmapComposite, data))
+}
+
+fn make_image(
+    metrics: Metrics,
+    location: Location,
+    format: RasterImageFormat,
+    data: &[u8],
+) -> RasterGlyphImage<'_> {
+    RasterGlyphImage {
+        x: i16::from(metrics.x),
+        // `y` in CBDT is a bottom bound, not top one.
+        y: i16::from(metrics.y) - i16::from(metrics.height),
+        width: u16::from(metrics.width),
+        height: u16::from(metrics.height),
+        pixels_per_em: location.ppem,
+        format,
+        draw_outlines: false,
+        advance: Some(u16::from(metrics.advance)),
+        vertical_metrics: metrics.vertical.map(|v| RasterImageVerticalMetrics {
+            bearing_x: i16::from(v.x),
+            bearing_y: i16::from(v.y),
+            advance: u16::from(v.advance),
+        }),
+        data,
+    }
+}
+
+
+#[derive(Clone, Copy)]
+pub struct Component {
+    pub glyph_id: GlyphId,
+    pub x: i8,
+    pub y: i8,
+}
+
+impl FromData for Component {
+    const SIZE: usize = 4;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        Some(Component {
+            glyph_id: s.read()?,
+            x: s.read()?,
+            y: s.read()?,
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    fn location(format: BitmapFormat, bit_depth: u8) -> Location {
+        Location {
+            format,
+            offset: 0,
+            metrics: Metrics::default(),
+            ppem: 12,
+            bit_depth,
         }
     }
+
+    #[test]
+    fn format_1_byte_aligned() {
+        let data = writer::convert(&[
+            UInt8(2), // height
+            UInt8(9), // width
+            Int8(1), // bearingX
+            Int8(8), // bearingY
+            UInt8(10), // advance
+            UInt16(0xFF80), // row 1
+            UInt16(0x8080), // row 2
+        ]);
+
+        let image = parse(&data, location(BitmapFormat::Format1, 1)).unwrap();
+        assert_eq!(image.format, RasterImageFormat::BitmapMono);
+        assert_eq!(image.x, 1);
+        assert_eq!(image.y, 6);
+        assert_eq!(image.width, 9);
+        assert_eq!(image.height, 2);
+        assert_eq!(image.data, &[0xFF, 0x80, 0x80, 0x80]);
+    }
+
+    #[test]
+    fn format_2_bit_aligned() {
+        let data = writer::convert(&[
+            UInt8(2), // height
+            UInt8(9), // width
+            Int8(1), // bearingX
+            Int8(8), // bearingY
+            UInt8(10), // advance
+            UInt16(0xFFC0), // 18 bits
+            UInt8(0x00),
+            UInt8(0xAA), // trailing data
+        ]);
+
+        let image = parse(&data, location(BitmapFormat::Format2, 1)).unwrap();
+        assert_eq!(image.format, RasterImageFormat::BitmapMonoPacked);
+        assert_eq!(image.data, &[0xFF, 0xC0, 0x00]);
+    }
+
+    #[test]
+    fn format_7_grayscale() {
+        let data = writer::convert(&[
+            UInt8(1), // height
+            UInt8(3), // width
+            Int8(0), // horiBearingX
+            Int8(1), // horiBearingY
+            UInt8(3), // horiAdvance
+            Int8(0), // vertBearingX
+            Int8(0), // vertBearingY
+            UInt8(0), // vertAdvance
+            UInt16(0x12F0),
+        ]);
+
+        let image = parse(&data, location(BitmapFormat::Format7, 4)).unwrap();
+        assert_eq!(image.format, RasterImageFormat::BitmapGray4Packed);
+        assert_eq!(image.data, &[0x12, 0xF0]);
+    }
+
+    #[test]
+    fn format_8_composite() {
+        let data = writer::convert(&[
+            UInt8(10), // height
+            UInt8(10), // width
+            Int8(0), // bearingX
+            Int8(10), // bearingY
+            UInt8(10), // advance
+            UInt8(0), // pad
+            UInt16(2), // numComponents
+            UInt16(5), // glyphID
+            Int8(0), // xOffset
+            Int8(0), // yOffset
+            UInt16(7), // glyphID
+            Int8(3), // xOffset
+            Int8(-2), // yOffset
+        ]);
+
+        let image = parse(&data, location(BitmapFormat::Format8, 1)).unwrap();
+        assert_eq!(image.format, RasterImageFormat::BitmapComposite);
+
+        let mut components = image.components();
+        let component = components.next().unwrap();
+        assert_eq!(component.glyph_id, GlyphId(5));
+        let component = components.next().unwrap();
+        assert_eq!(component.glyph_id, GlyphId(7));
+        assert_eq!(component.x, 3);
+        assert_eq!(component.y, -2);
+        assert!(components.next().is_none());
+    }
+
+    #[test]
+    fn unsupported_bit_depth() {
+        let data = writer::convert(&[
+            UInt8(1), // height
+            UInt8(1), // width
+            Int8(0), // bearingX
+            Int8(0), // bearingY
+            UInt8(1), // advance
+            UInt8(0),
+        ]);
+
+        assert!(parse(&data, location(BitmapFormat::Format1, 3)).is_none());
+    }
 }
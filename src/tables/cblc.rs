@@ -1,10 +1,20 @@
 // https://docs.microsoft.com/en-us/typography/opentype/spec/cblc
+// https://docs.microsoft.com/en-us/typography/opentype/spec/eblc
+//
+// `EBLC` and `CBLC` tables have the same structure, so we are using the same code for both.
 
 use crate::GlyphId;
 use crate::parser::{Stream, FromData, Offset, Offset16, Offset32, NumFrom};
 
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum BitmapFormat {
+    Format1,
+    Format2,
+    Format5,
+    Format6,
+    Format7,
+    Format8,
+    Format9,
     Format17,
     Format18,
     Format19,
@@ -16,6 +26,40 @@ pub struct Metrics {
     pub y: i8,
     pub width: u8,
     pub height: u8,
+    pub advance: u8,
+    pub vertical: Option<VerticalMetrics>,
+}
+
+impl Metrics {
+    // https://docs.microsoft.com/en-us/typography/opentype/spec/eblc#smallglyphmetrics
+    pub fn parse_small(s: &mut Stream) -> Option<Self> {
+        Some(Metrics {
+            height: s.read()?,
+            width: s.read()?,
+            x: s.read()?,
+            y: s.read()?,
+            advance: s.read()?,
+            vertical: None,
+        })
+    }
+
+    // https://docs.microsoft.com/en-us/typography/opentype/spec/eblc#bigglyphmetrics
+    pub fn parse_big(s: &mut Stream) -> Option<Self> {
+        let mut metrics = Metrics::parse_small(s)?;
+        metrics.vertical = Some(VerticalMetrics {
+            x: s.read()?,
+            y: s.read()?,
+            advance: s.read()?,
+        });
+        Some(metrics)
+    }
+}
+
+#[derive(Clone, Copy, Default, Debug)]
+pub struct VerticalMetrics {
+    pub x: i8,
+    pub y: i8,
+    pub advance: u8,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -24,6 +68,7 @@ pub struct Location {
     pub offset: usize,
     pub metrics: Metrics,
     pub ppem: u16,
+    pub bit_depth: u8,
 }
 
 pub fn find_location(
@@ -49,16 +94,21 @@ pub fn find_location(
     let mut image_offset = s.read::<Offset32>()?.to_usize();
 
     let image_format = match image_format {
+        1 => BitmapFormat::Format1,
+        2 => BitmapFormat::Format2,
+        5 => BitmapFormat::Format5,
+        6 => BitmapFormat::Format6,
+        7 => BitmapFormat::Format7,
+        8 => BitmapFormat::Format8,
+        9 => BitmapFormat::Format9,
         17 => BitmapFormat::Format17,
         18 => BitmapFormat::Format18,
         19 => BitmapFormat::Format19,
         _ => return None, // Invalid format.
     };
 
-    // TODO: I wasn't able to find fonts with index 4 and 5, so they are untested.
-
     let glyph_diff = glyph_id.0.checked_sub(info.start_glyph_id.0)?;
-    let metrics = Metrics::default();
+    let mut metrics = Metrics::default();
     match index_format {
         1 => {
             s.advance(usize::from(glyph_diff) * Offset32::SIZE);
@@ -67,6 +117,7 @@ pub fn find_location(
         }
         2 => {
             let image_size: u32 = s.read()?;
+            metrics = Metrics::parse_big(&mut s)?;
             image_offset += usize::from(glyph_diff).checked_mul(usize::num_from(image_size))?;
         }
         3 => {
@@ -83,7 +134,7 @@ pub fn find_location(
         }
         5 => {
             let image_size: u32 = s.read()?;
-            s.advance(8); // big metrics
+            metrics = Metrics::parse_big(&mut s)?;
             let num_glyphs: u32 = s.read()?;
             let glyphs = s.read_array32::<GlyphId>(num_glyphs)?;
             let (index, _) = glyphs.binary_search(&glyph_id)?;
@@ -98,6 +149,7 @@ pub fn find_location(
         offset: image_offset,
         metrics,
         ppem: size_table.ppem,
+        bit_depth: size_table.bit_depth,
     })
 }
 
@@ -107,6 +159,7 @@ struct BitmapSizeTable {
     subtable_array_offset: Offset32,
     number_of_subtables: u32,
     ppem: u16,
+    bit_depth: u8,
     // Many fields are omitted.
 }
 
@@ -120,6 +173,7 @@ fn select_bitmap_size_table(
 
     let mut idx = None;
     let mut max_ppem = 0;
+    let mut bit_depth = 0;
     for i in 0..subtable_count {
         // The BitmapSize Table is larger than 32 bytes, so we cannot use scripts/gen-tables.py
 
@@ -128,9 +182,11 @@ fn select_bitmap_size_table(
         let start_glyph_id: GlyphId = s.read()?;
         let end_glyph_id: GlyphId = s.read()?;
         let ppem = u16::from(s.read::<u8>()?);
+        s.skip::<u8>(); // ppemY
+        let depth: u8 = s.read()?;
+        s.skip::<u8>(); // flags
 
         if !(start_glyph_id..=end_glyph_id).contains(&glyph_id) {
-            s.advance(4); // Jump to the end of the subtable.
             continue;
         }
 
@@ -138,6 +194,7 @@ fn select_bitmap_size_table(
         if (pixels_per_em <= ppem && ppem < max_ppem) || (pixels_per_em > max_ppem && ppem > max_ppem) {
             idx = Some(usize::num_from(i));
             max_ppem = ppem;
+            bit_depth = depth;
         }
     }
 
@@ -152,6 +209,7 @@ fn select_bitmap_size_table(
         subtable_array_offset,
         number_of_subtables,
         ppem: max_ppem,
+        bit_depth,
     })
 }
 
@@ -204,3 +262,99 @@ impl FromData for GlyphIdOffsetPair {
         })
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+    use super::*;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    fn header(start_glyph_id: u16, end_glyph_id: u16) -> Vec<u8> {
+        writer::convert(&[
+            UInt32(0x00030000), // version
+            UInt32(1), // numSizes
+            // BitmapSize
+            UInt32(56), // indexSubTableArrayOffset
+            UInt32(0), // indexTablesSize
+            UInt32(1), // numberOfIndexSubTables
+            UInt32(0), // colorRef
+            Raw(&[0; 12]), // hori
+            Raw(&[0; 12]), // vert
+            UInt16(start_glyph_id), // startGlyphIndex
+            UInt16(end_glyph_id), // endGlyphIndex
+            UInt8(20), // ppemX
+            UInt8(20), // ppemY
+            UInt8(32), // bitDepth
+            UInt8(1), // flags
+            // IndexSubTableArray
+            UInt16(start_glyph_id), // firstGlyphIndex
+            UInt16(end_glyph_id), // lastGlyphIndex
+            UInt32(8), // additionalOffsetToIndexSubtable
+        ])
+    }
+
+    #[test]
+    fn index_format_4() {
+        let mut data = header(3, 5);
+        data.extend_from_slice(&writer::convert(&[
+            UInt16(4), // indexFormat
+            UInt16(17), // imageFormat
+            UInt32(100), // imageDataOffset
+            UInt32(2), // numGlyphs
+            UInt16(3), // glyphID
+            UInt16(0), // sbitOffset
+            UInt16(5), // glyphID
+            UInt16(10), // sbitOffset
+            UInt16(0), // glyphID
+            UInt16(20), // sbitOffset
+        ]));
+
+        let location = find_location(&data, GlyphId(5), 20).unwrap();
+        assert_eq!(location.format, BitmapFormat::Format17);
+        assert_eq!(location.offset, 110);
+        assert_eq!(location.ppem, 20);
+        assert_eq!(location.bit_depth, 32);
+
+        assert!(find_location(&data, GlyphId(4), 20).is_none());
+    }
+
+    #[test]
+    fn index_format_5() {
+        let mut data = header(2, 9);
+        data.extend_from_slice(&writer::convert(&[
+            UInt16(5), // indexFormat
+            UInt16(19), // imageFormat
+            UInt32(200), // imageDataOffset
+            UInt32(16), // imageSize
+            // BigGlyphMetrics
+            UInt8(10), // height
+            UInt8(12), // width
+            Int8(1), // horiBearingX
+            Int8(9), // horiBearingY
+            UInt8(14), // horiAdvance
+            Int8(-6), // vertBearingX
+            Int8(2), // vertBearingY
+            UInt8(13), // vertAdvance
+            UInt32(3), // numGlyphs
+            UInt16(2), // glyphIdArray
+            UInt16(4),
+            UInt16(9),
+        ]));
+
+        let location = find_location(&data, GlyphId(9), 20).unwrap();
+        assert_eq!(location.format, BitmapFormat::Format19);
+        assert_eq!(location.offset, 232);
+        assert_eq!(location.metrics.width, 12);
+        assert_eq!(location.metrics.height, 10);
+        assert_eq!(location.metrics.advance, 14);
+
+        let vertical = location.metrics.vertical.unwrap();
+        assert_eq!(vertical.x, -6);
+        assert_eq!(vertical.y, 2);
+        assert_eq!(vertical.advance, 13);
+
+        assert!(find_location(&data, GlyphId(3), 20).is_none());
+    }
+}
@@ -6,8 +6,8 @@
 use core::convert::TryFrom;
 use core::ops::Range;
 
-use crate::{GlyphId, OutlineBuilder, Rect, BBox};
-use crate::parser::{Stream, U24, Fixed, FromData, NumFrom, TryNumFrom};
+use crate::{GlyphId, OutlineBuilder, HintedOutlineBuilder, UnhintedOutlineBuilder, DummyOutline, Rect, BBox};
+use crate::parser::{Stream, U24, Fixed, FromData, NumFrom, TryNumFrom, LazyArray16};
 
 // Limits according to the Adobe Technical Note #5176, chapter 4 DICT Data.
 const MAX_OPERANDS_LEN: u8 = 48;
@@ -20,6 +20,10 @@ const END_OF_FLOAT_FLAG: u8 = 0xf;
 
 const TWO_BYTE_OPERATOR_MARK: u8 = 12;
 
+// The number of Font DICTs in a CID font whose Private DICTs are parsed in advance.
+// Most fonts have just a few of them. The rest are parsed on demand.
+const MAX_CACHED_PRIVATE_DICTS: usize = 4;
+
 /// Enumerates some operators defined in the Adobe Technical Note #5177.
 mod operator {
     pub const HORIZONTAL_STEM: u8           = 1;
@@ -56,14 +60,51 @@ mod operator {
 /// Enumerates some operators defined in the Adobe Technical Note #5176,
 /// Table 9 Top DICT Operator Entries
 mod top_dict_operator {
+    pub const VERSION: u16                      = 0;
+    pub const NOTICE: u16                       = 1;
+    pub const FULL_NAME: u16                    = 2;
+    pub const FAMILY_NAME: u16                  = 3;
+    pub const WEIGHT: u16                       = 4;
+    pub const FONT_BBOX: u16                    = 5;
+    pub const UNIQUE_ID: u16                    = 13;
+    pub const CHARSET_OFFSET: u16               = 15;
+    pub const ENCODING_OFFSET: u16              = 16;
     pub const CHAR_STRINGS_OFFSET: u16          = 17;
     pub const PRIVATE_DICT_SIZE_AND_OFFSET: u16 = 18;
+    pub const COPYRIGHT: u16                    = 1200;
+    pub const IS_FIXED_PITCH: u16               = 1201;
+    pub const ITALIC_ANGLE: u16                 = 1202;
+    pub const UNDERLINE_POSITION: u16           = 1203;
+    pub const UNDERLINE_THICKNESS: u16          = 1204;
+    pub const PAINT_TYPE: u16                   = 1205;
+    pub const CHAR_STRING_TYPE: u16             = 1206;
+    pub const FONT_MATRIX: u16                  = 1207;
+    pub const STROKE_WIDTH: u16                 = 1208;
+    pub const ROS: u16                          = 1230;
+    pub const FD_ARRAY: u16                     = 1236;
+    pub const FD_SELECT: u16                    = 1237;
 }
 
 /// Enumerates some operators defined in the Adobe Technical Note #5176,
 /// Table 23 Private DICT Operators
 mod private_dict_operator {
+    pub const BLUE_VALUES: u16              = 6;
+    pub const OTHER_BLUES: u16              = 7;
+    pub const FAMILY_BLUES: u16             = 8;
+    pub const FAMILY_OTHER_BLUES: u16       = 9;
+    pub const STD_HW: u16                   = 10;
+    pub const STD_VW: u16                   = 11;
     pub const LOCAL_SUBROUTINES_OFFSET: u16 = 19;
+    pub const DEFAULT_WIDTH_X: u16          = 20;
+    pub const NOMINAL_WIDTH_X: u16          = 21;
+    pub const BLUE_SCALE: u16               = 1209;
+    pub const BLUE_SHIFT: u16               = 1210;
+    pub const BLUE_FUZZ: u16                = 1211;
+    pub const STEM_SNAP_H: u16              = 1212;
+    pub const STEM_SNAP_V: u16              = 1213;
+    pub const FORCE_BOLD: u16               = 1214;
+    pub const LANGUAGE_GROUP: u16           = 1217;
+    pub const EXPANSION_FACTOR: u16         = 1218;
 }
 
 
@@ -82,6 +123,8 @@ pub enum CFFError {
     BboxOverflow,
     MissingMoveTo,
     InvalidSubroutineIndex,
+    NoLocalSubroutines,
+    InvalidSeacCode,
     InvalidItemVariationDataIndex,
     InvalidNumberOfBlendOperands,
     BlendRegionsLimitReached,
@@ -90,9 +133,53 @@ pub enum CFFError {
 
 #[derive(Clone, Copy, Default, Debug)]
 pub struct Metadata<'a> {
+    // The whole CFF table.
+    // Used to resolve a local subroutine in a CID font.
+    table_data: &'a [u8],
+    top_dict_data: &'a [u8],
+    strings: DataIndex<'a>,
     global_subrs: DataIndex<'a>,
-    local_subrs: DataIndex<'a>,
     char_strings: DataIndex<'a>,
+    // `None` when a custom charset is malformed.
+    charset: Option<Charset<'a>>,
+    // `None` when a custom encoding is malformed.
+    encoding: Option<Encoding<'a>>,
+    kind: FontKind<'a>,
+    // Private DICTs of the first `MAX_CACHED_PRIVATE_DICTS` Font DICTs in a CID font.
+    // Stored outside of `CIDMetadata` to keep `FontKind` variants of a similar size.
+    cid_private_dicts: [CIDPrivateDict<'a>; MAX_CACHED_PRIVATE_DICTS],
+}
+
+#[derive(Clone, Copy, Debug)]
+enum FontKind<'a> {
+    Sid(SIDMetadata<'a>),
+    Cid(CIDMetadata<'a>),
+}
+
+impl Default for FontKind<'_> {
+    #[inline]
+    fn default() -> Self {
+        FontKind::Sid(SIDMetadata::default())
+    }
+}
+
+#[derive(Clone, Copy, Default, Debug)]
+struct SIDMetadata<'a> {
+    private_dict_data: &'a [u8],
+    local_subrs: DataIndex<'a>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct CIDMetadata<'a> {
+    fd_array: DataIndex<'a>,
+    fd_select: FDSelect<'a>,
+}
+
+// A Font DICT's Private DICT data with resolved local subroutines.
+#[derive(Clone, Copy, Default, Debug)]
+struct CIDPrivateDict<'a> {
+    data: &'a [u8],
+    local_subrs: Option<DataIndex<'a>>,
 }
 
 pub(crate) fn parse_metadata(data: &[u8]) -> Option<Metadata> {
@@ -116,46 +203,146 @@ pub(crate) fn parse_metadata(data: &[u8]) -> Option<Metadata> {
     // Skip Name INDEX.
     skip_index(&mut s)?;
 
-    let (char_strings_offset, private_dict_range) = parse_top_dict(&mut s)?;
+    let top_dict = parse_top_dict(&mut s)?;
 
     // Must be set, otherwise there are nothing to parse.
-    if char_strings_offset == 0 {
+    if top_dict.char_strings_offset == 0 {
         return None;
     }
 
-    let subroutines_offset = if let Some(range) = private_dict_range.clone() {
-        parse_private_dict(data.get(range)?)
-    } else {
-        None
+    // Parse String INDEX.
+    let strings = parse_index(&mut s)?;
+
+    // Parse Global Subroutines INDEX.
+    let global_subrs = parse_index(&mut s)?;
+
+    let char_strings = {
+        let mut s = Stream::new_at(data, top_dict.char_strings_offset)?;
+        parse_index(&mut s)?
     };
 
-    // Skip String INDEX.
-    skip_index(&mut s)?;
+    if char_strings.len() == 0 {
+        return None;
+    }
 
-    // Parse Global Subroutines INDEX.
-    let mut metadata = Metadata::default();
-    metadata.global_subrs = parse_index(&mut s)?;
-
-    match (private_dict_range, subroutines_offset) {
-        (Some(private_dict_range), Some(subroutines_offset)) => {
-            // 'The local subroutines offset is relative to the beginning
-            // of the Private DICT data.'
-            if let Some(start) = private_dict_range.start.checked_add(subroutines_offset) {
-                let data = data.get(start..data.len())?;
-                let mut s = Stream::new(data);
-                metadata.local_subrs = parse_index(&mut s)?;
-            }
+    let charset = match top_dict.charset_offset {
+        Some(charset_id::ISO_ADOBE) | None => Some(Charset::ISOAdobe),
+        Some(charset_id::EXPERT) => Some(Charset::Expert),
+        Some(charset_id::EXPERT_SUBSET) => Some(Charset::ExpertSubset),
+        Some(offset) => {
+            // A charset is only required for glyph names and `seac`,
+            // so a malformed one should not affect outlining.
+            Stream::new_at(data, offset).and_then(|mut s| parse_charset(char_strings.len(), &mut s))
+        }
+    };
+
+    // Encoding is not used by CID fonts.
+    let encoding = match top_dict.encoding_offset {
+        _ if top_dict.has_ros => Some(Encoding::default()),
+        Some(encoding_id::STANDARD) | None => Some(Encoding::default()),
+        Some(encoding_id::EXPERT) => Some(Encoding::new(EncodingKind::Expert)),
+        Some(offset) => {
+            // An encoding is only required for `glyph_index_by_code` and `seac`.
+            Stream::new_at(data, offset).and_then(|mut s| parse_encoding(&mut s))
+        }
+    };
+
+    let kind = if top_dict.has_ros {
+        parse_cid_metadata(data, &top_dict, char_strings.len())?
+    } else {
+        FontKind::Sid(SIDMetadata {
+            private_dict_data: top_dict.private_dict_range.clone()
+                .and_then(|range| data.get(range))
+                .unwrap_or_default(),
+            local_subrs: top_dict.private_dict_range
+                .and_then(|range| parse_local_subrs(data, range))
+                .unwrap_or_default(),
+        })
+    };
+
+    let mut cid_private_dicts = [CIDPrivateDict::default(); MAX_CACHED_PRIVATE_DICTS];
+    if let FontKind::Cid(ref cid) = kind {
+        let cached_len = cid.fd_array.len().min(MAX_CACHED_PRIVATE_DICTS as u16);
+        for font_dict_index in 0..cached_len {
+            // A malformed Private DICT is treated as an empty one, like during outlining.
+            cid_private_dicts[usize::from(font_dict_index)] =
+                parse_cid_private_dict(data, &cid.fd_array, font_dict_index).unwrap_or_default();
         }
-        _ => {}
     }
 
-    // TODO: check that index is not default
-    metadata.char_strings = {
-        let mut s = Stream::new_at(data, char_strings_offset)?;
+    Some(Metadata {
+        table_data: data,
+        top_dict_data: top_dict.data,
+        strings,
+        global_subrs,
+        char_strings,
+        charset,
+        encoding,
+        kind,
+        cid_private_dicts,
+    })
+}
+
+fn parse_cid_metadata<'a>(data: &'a [u8], top_dict: &TopDict, number_of_glyphs: u16) -> Option<FontKind<'a>> {
+    let (fd_array_offset, fd_select_offset) = match (top_dict.fd_array_offset, top_dict.fd_select_offset) {
+        (Some(a), Some(b)) => (a, b),
+        _ => return None,
+    };
+
+    let fd_array = {
+        let mut s = Stream::new_at(data, fd_array_offset)?;
         parse_index(&mut s)?
     };
 
-    Some(metadata)
+    let fd_select = {
+        let mut s = Stream::new_at(data, fd_select_offset)?;
+        parse_fd_select(number_of_glyphs, &mut s)?
+    };
+
+    Some(FontKind::Cid(CIDMetadata {
+        fd_array,
+        fd_select,
+    }))
+}
+
+fn parse_local_subrs<'a>(data: &'a [u8], private_dict_range: Range<usize>) -> Option<DataIndex<'a>> {
+    let subroutines_offset = parse_private_dict(data.get(private_dict_range.clone())?)?;
+
+    // 'The local subroutines offset is relative to the beginning
+    // of the Private DICT data.'
+    let start = private_dict_range.start.checked_add(subroutines_offset)?;
+    let mut s = Stream::new_at(data, start)?;
+    parse_index(&mut s)
+}
+
+// Each glyph in a CID font references a Font DICT via FDSelect,
+// which in turn references a Private DICT with its own local subroutines.
+fn cid_private_dict<'a>(
+    metadata: &Metadata<'a>,
+    glyph_id: GlyphId,
+    cid: &CIDMetadata<'a>,
+) -> Option<CIDPrivateDict<'a>> {
+    let font_dict_index = cid.fd_select.font_dict_index(glyph_id)?;
+    if font_dict_index < cid.fd_array.len() {
+        if let Some(private_dict) = metadata.cid_private_dicts.get(usize::from(font_dict_index)) {
+            return Some(*private_dict);
+        }
+    }
+
+    parse_cid_private_dict(metadata.table_data, &cid.fd_array, font_dict_index)
+}
+
+fn parse_cid_private_dict<'a>(
+    data: &'a [u8],
+    fd_array: &DataIndex<'a>,
+    font_dict_index: u16,
+) -> Option<CIDPrivateDict<'a>> {
+    let font_dict_data = fd_array.get(font_dict_index)?;
+    let private_dict_range = parse_font_dict(font_dict_data)?;
+    Some(CIDPrivateDict {
+        data: data.get(private_dict_range.clone())?,
+        local_subrs: parse_local_subrs(data, private_dict_range),
+    })
 }
 
 
@@ -164,18 +351,454 @@ pub fn outline(
     glyph_id: GlyphId,
     builder: &mut dyn OutlineBuilder,
 ) -> Option<Rect> {
+    outline_with_hints(metadata, glyph_id, &mut UnhintedOutlineBuilder(builder))
+}
+
+pub fn outline_with_hints(
+    metadata: &Metadata,
+    glyph_id: GlyphId,
+    builder: &mut dyn HintedOutlineBuilder,
+) -> Option<Rect> {
+    let data = metadata.char_strings.get(glyph_id.0)?;
+    parse_char_string(data, metadata, glyph_id, builder).ok()
+}
+
+// 'The first stack-clearing operator, which must be one of hstem, hstemhm, vstem, vstemhm,
+// cntrmask, hintmask, hmoveto, vmoveto, rmoveto, or endchar, takes an additional argument
+// — the width, which may be expressed as zero or one numeric argument.'
+pub fn glyph_width(metadata: &Metadata, glyph_id: GlyphId) -> Option<f32> {
     let data = metadata.char_strings.get(glyph_id.0)?;
-    parse_char_string(data, metadata, builder).ok()
+    let mut builder = UnhintedOutlineBuilder(&mut DummyOutline);
+    let (_, width) = parse_char_string_impl(data, metadata, glyph_id, &mut builder).ok()?;
+    let private_dict = private_dict(metadata, glyph_id).unwrap_or_default();
+    match width {
+        Some(width) => Some(private_dict.nominal_width_x + width),
+        None => Some(private_dict.default_width_x),
+    }
+}
+
+// Returns a rounded `glyph_width`.
+pub fn glyph_advance(metadata: &Metadata, glyph_id: GlyphId) -> Option<u16> {
+    round_width(glyph_width(metadata, glyph_id)?)
+}
+
+// Rounds a charstring width half away from zero. Also used by Type 1 fonts.
+pub fn round_width(width: f32) -> Option<u16> {
+    // We can't use `round()` in `no_std`, so this is the next best thing.
+    let width = if width < 0.0 { width - 0.5 } else { width + 0.5 };
+    u16::try_num_from(width)
+}
+
+#[inline]
+pub fn number_of_glyphs(metadata: &Metadata) -> u16 {
+    metadata.char_strings.len()
+}
+
+pub fn glyph_name<'a>(metadata: &Metadata<'a>, glyph_id: GlyphId) -> Option<&'a str> {
+    // CID fonts map glyphs to CIDs and not to names.
+    if let FontKind::Cid(_) = metadata.kind {
+        return None;
+    }
+
+    let charset = metadata.charset.as_ref()?;
+    let sid = charset.gid_to_sid(glyph_id, metadata.char_strings.len())?;
+    resolve_string(metadata, sid)
+}
+
+// SIDs below 391 are Standard Strings and the rest are stored in the String INDEX.
+fn resolve_string<'a>(metadata: &Metadata<'a>, sid: StringId) -> Option<&'a str> {
+    let sid = usize::from(sid.0);
+    match STANDARD_NAMES.get(sid) {
+        Some(name) => Some(name),
+        None => {
+            let index = u16::try_from(sid - STANDARD_NAMES.len()).ok()?;
+            let name = metadata.strings.get(index)?;
+            core::str::from_utf8(name).ok()
+        }
+    }
+}
+
+pub fn glyph_index(metadata: &Metadata, code: u8) -> Option<GlyphId> {
+    if let FontKind::Cid(_) = metadata.kind {
+        return None;
+    }
+
+    let charset = metadata.charset.as_ref()?;
+    let encoding = metadata.encoding.as_ref()?;
+    encoding.code_to_gid(charset, metadata.char_strings.len(), code)
+}
+
+/// A [font matrix](https://wwwimages.adobe.com/content/dam/acom/en/devnet/font/pdfs/5176.CFF.pdf#page=16).
+///
+/// Maps glyph space to text space, where 1 unit is equal to an em.
+/// Usually `[0.001 0 0 0.001 0 0]`, i.e. 1000 units per em.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[allow(missing_docs)]
+pub struct FontMatrix {
+    pub sx: f32,
+    pub ky: f32,
+    pub kx: f32,
+    pub sy: f32,
+    pub tx: f32,
+    pub ty: f32,
+}
+
+impl Default for FontMatrix {
+    #[inline]
+    fn default() -> Self {
+        FontMatrix {
+            sx: 0.001,
+            ky: 0.0,
+            kx: 0.0,
+            sy: 0.001,
+            tx: 0.0,
+            ty: 0.0,
+        }
+    }
+}
+
+/// Values of a `CFF` Top DICT.
+///
+/// Offsets to other `CFF` structures are not included.
+/// Unset values are set to defaults according to the
+/// Adobe Technical Note #5176, Table 9.
+#[derive(Clone, Copy, Debug)]
+pub struct CFFTopDict<'a> {
+    /// Font program version.
+    pub version: Option<&'a str>,
+    /// A trademark notice.
+    pub notice: Option<&'a str>,
+    /// A copyright notice.
+    pub copyright: Option<&'a str>,
+    /// A full font name.
+    pub full_name: Option<&'a str>,
+    /// A family name.
+    pub family_name: Option<&'a str>,
+    /// A weight name.
+    pub weight: Option<&'a str>,
+    /// Indicates that all glyphs have the same width.
+    pub is_fixed_pitch: bool,
+    /// Italic angle in degrees counterclockwise from the vertical.
+    pub italic_angle: f32,
+    /// Underline position.
+    pub underline_position: f32,
+    /// Underline thickness.
+    pub underline_thickness: f32,
+    /// Paint type. 0 - filled, 2 - stroked.
+    pub paint_type: i32,
+    /// Charstring type. Only 2 is supported.
+    pub char_string_type: i32,
+    /// Font matrix.
+    pub font_matrix: FontMatrix,
+    /// A unique ID.
+    pub unique_id: Option<i32>,
+    /// Font bounding box.
+    pub font_bbox: Rect,
+    /// Stroke width for stroked fonts.
+    pub stroke_width: f32,
+}
+
+impl Default for CFFTopDict<'_> {
+    #[inline]
+    fn default() -> Self {
+        CFFTopDict {
+            version: None,
+            notice: None,
+            copyright: None,
+            full_name: None,
+            family_name: None,
+            weight: None,
+            is_fixed_pitch: false,
+            italic_angle: 0.0,
+            underline_position: -100.0,
+            underline_thickness: 50.0,
+            paint_type: 0,
+            char_string_type: 2,
+            font_matrix: FontMatrix::default(),
+            unique_id: None,
+            font_bbox: Rect { x_min: 0, y_min: 0, x_max: 0, y_max: 0 },
+            stroke_width: 0.0,
+        }
+    }
+}
+
+/// An alignment zone.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+#[allow(missing_docs)]
+pub struct BlueZone {
+    pub bottom: f32,
+    pub top: f32,
+}
+
+/// A list of alignment zones.
+///
+/// Up to 7 zones can be stored.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct BlueZones {
+    zones: [BlueZone; 7],
+    len: u8,
+}
+
+impl BlueZones {
+    /// Returns zones as a slice.
+    #[inline]
+    pub fn as_slice(&self) -> &[BlueZone] {
+        &self.zones[..usize::from(self.len)]
+    }
+
+    // Zones are stored as a delta-encoded array of bottom/top pairs.
+    fn parse(operands: &[f64]) -> Self {
+        let mut zones = BlueZones::default();
+        let mut prev = 0.0;
+        for pair in operands.chunks(2) {
+            if pair.len() != 2 || usize::from(zones.len) == zones.zones.len() {
+                break;
+            }
+
+            let bottom = prev + pair[0];
+            let top = bottom + pair[1];
+            prev = top;
+
+            zones.zones[usize::from(zones.len)] = BlueZone {
+                bottom: bottom as f32,
+                top: top as f32,
+            };
+            zones.len += 1;
+        }
+
+        zones
+    }
+}
+
+/// A list of dominant stem widths.
+///
+/// Up to 12 widths can be stored.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct StemSnaps {
+    values: [f32; 12],
+    len: u8,
+}
+
+impl StemSnaps {
+    /// Returns widths as a slice.
+    #[inline]
+    pub fn as_slice(&self) -> &[f32] {
+        &self.values[..usize::from(self.len)]
+    }
+
+    // Widths are stored as a delta-encoded array.
+    fn parse(operands: &[f64]) -> Self {
+        let mut snaps = StemSnaps::default();
+        let mut prev = 0.0;
+        for n in operands.iter().take(snaps.values.len()) {
+            prev += *n;
+            snaps.values[usize::from(snaps.len)] = prev as f32;
+            snaps.len += 1;
+        }
+
+        snaps
+    }
+}
+
+/// Values of a `CFF` Private DICT.
+///
+/// Offsets to other `CFF` structures are not included.
+/// Unset values are set to defaults according to the
+/// Adobe Technical Note #5176, Table 23.
+#[derive(Clone, Copy, Debug)]
+pub struct CFFPrivateDict {
+    /// Baseline and top alignment zones.
+    pub blue_values: BlueZones,
+    /// Bottom alignment zones.
+    pub other_blues: BlueZones,
+    /// Family-wide baseline and top alignment zones.
+    pub family_blues: BlueZones,
+    /// Family-wide bottom alignment zones.
+    pub family_other_blues: BlueZones,
+    /// Controls the point size at which overshoot suppression turns off.
+    pub blue_scale: f32,
+    /// Controls the overshoot enforcement.
+    pub blue_shift: f32,
+    /// Extends the range of alignment zones.
+    pub blue_fuzz: f32,
+    /// The dominant width of horizontal stems.
+    pub std_hw: Option<f32>,
+    /// The dominant width of vertical stems.
+    pub std_vw: Option<f32>,
+    /// Dominant widths of horizontal stems.
+    pub stem_snap_h: StemSnaps,
+    /// Dominant widths of vertical stems.
+    pub stem_snap_v: StemSnaps,
+    /// Indicates that the font should be emboldened at small sizes.
+    pub force_bold: bool,
+    /// Language group. 1 for CJK fonts.
+    pub language_group: i32,
+    /// Limits the counter expansion for the language group 1.
+    pub expansion_factor: f32,
+    /// Width of glyphs without an explicit width.
+    pub default_width_x: f32,
+    /// A value added to explicit glyph widths.
+    pub nominal_width_x: f32,
+}
+
+impl Default for CFFPrivateDict {
+    #[inline]
+    fn default() -> Self {
+        CFFPrivateDict {
+            blue_values: BlueZones::default(),
+            other_blues: BlueZones::default(),
+            family_blues: BlueZones::default(),
+            family_other_blues: BlueZones::default(),
+            blue_scale: 0.039625,
+            blue_shift: 7.0,
+            blue_fuzz: 1.0,
+            std_hw: None,
+            std_vw: None,
+            stem_snap_h: StemSnaps::default(),
+            stem_snap_v: StemSnaps::default(),
+            force_bold: false,
+            language_group: 0,
+            expansion_factor: 0.06,
+            default_width_x: 0.0,
+            nominal_width_x: 0.0,
+        }
+    }
+}
+
+pub fn top_dict<'a>(metadata: &Metadata<'a>) -> CFFTopDict<'a> {
+    let mut dict = CFFTopDict::default();
+
+    let mut dict_parser = DictionaryParser::new(metadata.top_dict_data);
+    while let Some(operator) = dict_parser.parse_next() {
+        if dict_parser.parse_operands().is_none() {
+            break;
+        }
+
+        let operands = dict_parser.operands();
+        let first = match operands.first() {
+            Some(n) => *n,
+            None => continue,
+        };
+
+        match operator.get() {
+            top_dict_operator::VERSION => dict.version = parse_sid(metadata, first),
+            top_dict_operator::NOTICE => dict.notice = parse_sid(metadata, first),
+            top_dict_operator::COPYRIGHT => dict.copyright = parse_sid(metadata, first),
+            top_dict_operator::FULL_NAME => dict.full_name = parse_sid(metadata, first),
+            top_dict_operator::FAMILY_NAME => dict.family_name = parse_sid(metadata, first),
+            top_dict_operator::WEIGHT => dict.weight = parse_sid(metadata, first),
+            top_dict_operator::IS_FIXED_PITCH => dict.is_fixed_pitch = first != 0.0,
+            top_dict_operator::ITALIC_ANGLE => dict.italic_angle = first as f32,
+            top_dict_operator::UNDERLINE_POSITION => dict.underline_position = first as f32,
+            top_dict_operator::UNDERLINE_THICKNESS => dict.underline_thickness = first as f32,
+            top_dict_operator::PAINT_TYPE => dict.paint_type = first as i32,
+            top_dict_operator::CHAR_STRING_TYPE => dict.char_string_type = first as i32,
+            top_dict_operator::FONT_MATRIX if operands.len() == 6 => {
+                dict.font_matrix = FontMatrix {
+                    sx: operands[0] as f32,
+                    ky: operands[1] as f32,
+                    kx: operands[2] as f32,
+                    sy: operands[3] as f32,
+                    tx: operands[4] as f32,
+                    ty: operands[5] as f32,
+                };
+            }
+            top_dict_operator::UNIQUE_ID => dict.unique_id = Some(first as i32),
+            top_dict_operator::FONT_BBOX if operands.len() == 4 => {
+                let bbox = (
+                    i16::try_num_from(operands[0] as f32),
+                    i16::try_num_from(operands[1] as f32),
+                    i16::try_num_from(operands[2] as f32),
+                    i16::try_num_from(operands[3] as f32),
+                );
+
+                if let (Some(x_min), Some(y_min), Some(x_max), Some(y_max)) = bbox {
+                    dict.font_bbox = Rect { x_min, y_min, x_max, y_max };
+                }
+            }
+            top_dict_operator::STROKE_WIDTH => dict.stroke_width = first as f32,
+            _ => {}
+        }
+    }
+
+    dict
+}
+
+fn parse_sid<'a>(metadata: &Metadata<'a>, n: f64) -> Option<&'a str> {
+    let sid = u16::try_num_from(n as f32)?;
+    resolve_string(metadata, StringId(sid))
+}
+
+pub fn private_dict(metadata: &Metadata, glyph_id: GlyphId) -> Option<CFFPrivateDict> {
+    let data = match metadata.kind {
+        FontKind::Sid(ref sid) => sid.private_dict_data,
+        FontKind::Cid(ref cid) => cid_private_dict(metadata, glyph_id, cid)?.data,
+    };
+
+    if data.is_empty() {
+        return None;
+    }
+
+    let mut dict = CFFPrivateDict::default();
+
+    let mut dict_parser = DictionaryParser::new(data);
+    while let Some(operator) = dict_parser.parse_next() {
+        if dict_parser.parse_operands().is_none() {
+            break;
+        }
+
+        let operands = dict_parser.operands();
+        let first = match operands.first() {
+            Some(n) => *n,
+            None => continue,
+        };
+
+        match operator.get() {
+            private_dict_operator::BLUE_VALUES => dict.blue_values = BlueZones::parse(operands),
+            private_dict_operator::OTHER_BLUES => dict.other_blues = BlueZones::parse(operands),
+            private_dict_operator::FAMILY_BLUES => dict.family_blues = BlueZones::parse(operands),
+            private_dict_operator::FAMILY_OTHER_BLUES => {
+                dict.family_other_blues = BlueZones::parse(operands);
+            }
+            private_dict_operator::STD_HW => dict.std_hw = Some(first as f32),
+            private_dict_operator::STD_VW => dict.std_vw = Some(first as f32),
+            private_dict_operator::DEFAULT_WIDTH_X => dict.default_width_x = first as f32,
+            private_dict_operator::NOMINAL_WIDTH_X => dict.nominal_width_x = first as f32,
+            private_dict_operator::BLUE_SCALE => dict.blue_scale = first as f32,
+            private_dict_operator::BLUE_SHIFT => dict.blue_shift = first as f32,
+            private_dict_operator::BLUE_FUZZ => dict.blue_fuzz = first as f32,
+            private_dict_operator::STEM_SNAP_H => dict.stem_snap_h = StemSnaps::parse(operands),
+            private_dict_operator::STEM_SNAP_V => dict.stem_snap_v = StemSnaps::parse(operands),
+            private_dict_operator::FORCE_BOLD => dict.force_bold = first != 0.0,
+            private_dict_operator::LANGUAGE_GROUP => dict.language_group = first as i32,
+            private_dict_operator::EXPANSION_FACTOR => dict.expansion_factor = first as f32,
+            _ => {}
+        }
+    }
+
+    Some(dict)
+}
+
+#[derive(Clone, Default, Debug)]
+struct TopDict<'a> {
+    data: &'a [u8],
+    char_strings_offset: usize,
+    private_dict_range: Option<Range<usize>>,
+    charset_offset: Option<usize>,
+    encoding_offset: Option<usize>,
+    has_ros: bool,
+    fd_array_offset: Option<usize>,
+    fd_select_offset: Option<usize>,
 }
 
-fn parse_top_dict(s: &mut Stream) -> Option<(usize, Option<Range<usize>>)> {
-    let mut char_strings_offset = 0;
-    let mut private_dict_range = None;
+fn parse_top_dict<'a>(s: &mut Stream<'a>) -> Option<TopDict<'a>> {
+    let mut top_dict = TopDict::default();
 
     let index = parse_index(s)?;
 
     // The Top DICT INDEX should have only one dictionary.
     let data = index.get(0)?;
+    top_dict.data = data;
 
     let mut dict_parser = DictionaryParser::new(data);
     while let Some(operator) = dict_parser.parse_next() {
@@ -185,29 +808,70 @@ fn parse_top_dict(s: &mut Stream) -> Option<(usize, Option<Range<usize>>)> {
                 let operands = dict_parser.operands();
 
                 if operands.len() == 1 {
-                    char_strings_offset = usize::try_from(operands[0]).ok()?;
+                    top_dict.char_strings_offset = f64_to_usize(operands[0])?;
                 }
             }
             top_dict_operator::PRIVATE_DICT_SIZE_AND_OFFSET => {
-                dict_parser.parse_operands()?;
-                let operands = dict_parser.operands();
-
-                if operands.len() == 2 {
-                    let len = usize::try_from(operands[0]).ok()?;
-                    let start = usize::try_from(operands[1]).ok()?;
-                    let end = start.checked_add(len)?;
-                    private_dict_range = Some(start..end);
-                }
+                top_dict.private_dict_range = Some(parse_private_dict_range(&mut dict_parser)?);
+            }
+            top_dict_operator::CHARSET_OFFSET => {
+                top_dict.charset_offset = parse_single_offset(&mut dict_parser);
+            }
+            top_dict_operator::ENCODING_OFFSET => {
+                top_dict.encoding_offset = parse_single_offset(&mut dict_parser);
+            }
+            top_dict_operator::ROS => {
+                top_dict.has_ros = true;
+            }
+            top_dict_operator::FD_ARRAY => {
+                top_dict.fd_array_offset = parse_single_offset(&mut dict_parser);
+            }
+            top_dict_operator::FD_SELECT => {
+                top_dict.fd_select_offset = parse_single_offset(&mut dict_parser);
             }
             _ => {}
         }
+    }
 
-        if char_strings_offset != 0 && private_dict_range.is_some() {
-            break;
+    Some(top_dict)
+}
+
+// Font DICT has the same structure as Top DICT,
+// but we care only about the Private DICT location.
+fn parse_font_dict(data: &[u8]) -> Option<Range<usize>> {
+    let mut dict_parser = DictionaryParser::new(data);
+    while let Some(operator) = dict_parser.parse_next() {
+        if operator.get() == top_dict_operator::PRIVATE_DICT_SIZE_AND_OFFSET {
+            return parse_private_dict_range(&mut dict_parser);
         }
     }
 
-    Some((char_strings_offset, private_dict_range))
+    None
+}
+
+fn parse_private_dict_range(dict_parser: &mut DictionaryParser) -> Option<Range<usize>> {
+    dict_parser.parse_operands()?;
+    let operands = dict_parser.operands();
+
+    if operands.len() == 2 {
+        let len = f64_to_usize(operands[0])?;
+        let start = f64_to_usize(operands[1])?;
+        let end = start.checked_add(len)?;
+        Some(start..end)
+    } else {
+        None
+    }
+}
+
+fn parse_single_offset(dict_parser: &mut DictionaryParser) -> Option<usize> {
+    dict_parser.parse_operands()?;
+    let operands = dict_parser.operands();
+
+    if operands.len() == 1 {
+        f64_to_usize(operands[0])
+    } else {
+        None
+    }
 }
 
 fn parse_private_dict(data: &[u8]) -> Option<usize> {
@@ -219,7 +883,7 @@ fn parse_private_dict(data: &[u8]) -> Option<usize> {
             let operands = dict_parser.operands();
 
             if operands.len() == 1 {
-                subroutines_offset = usize::try_from(operands[0]).ok();
+                subroutines_offset = f64_to_usize(operands[0]);
             }
 
             break;
@@ -229,46 +893,818 @@ fn parse_private_dict(data: &[u8]) -> Option<usize> {
     subroutines_offset
 }
 
-struct CharStringParserContext<'a> {
-    metadata: &'a Metadata<'a>,
-    is_first_move_to: bool,
-    has_move_to: bool,
-    width_parsed: bool,
-    stems_len: u32,
-    has_endchar: bool,
+
+#[derive(Clone, Copy, Debug)]
+pub enum FDSelect<'a> {
+    Format0(LazyArray16<'a, u8>),
+    Format3(&'a [u8]), // It's easier to parse it in-place.
+    Format4(&'a [u8]), // CFF2 only.
 }
 
-fn parse_char_string(
-    data: &[u8],
-    metadata: &Metadata,
-    builder: &mut dyn OutlineBuilder,
-) -> Result<Rect, CFFError> {
-    let mut ctx = CharStringParserContext {
-        metadata,
-        is_first_move_to: true,
-        has_move_to: false,
-        width_parsed: false,
-        stems_len: 0,
-        has_endchar: false,
-    };
+impl Default for FDSelect<'_> {
+    #[inline]
+    fn default() -> Self {
+        FDSelect::Format0(LazyArray16::default())
+    }
+}
 
-    let mut inner_builder = Builder {
-        builder,
-        bbox: BBox::new(),
-    };
+impl FDSelect<'_> {
+    pub fn font_dict_index(&self, glyph_id: GlyphId) -> Option<u16> {
+        match *self {
+            FDSelect::Format0(ref array) => array.get(glyph_id.0).map(u16::from),
+            FDSelect::Format3(data) => {
+                let mut s = Stream::new(data);
+                let number_of_ranges: u16 = s.read()?;
+                if number_of_ranges == 0 {
+                    return None;
+                }
 
-    let mut stack = ArgumentsStack {
-        data: &mut [0.0; MAX_ARGUMENTS_STACK_LEN], // 192B
-        len: 0,
-        max_len: MAX_ARGUMENTS_STACK_LEN,
-    };
-    let _ = _parse_char_string(&mut ctx, data, 0.0, 0.0, &mut stack, 0, &mut inner_builder)?;
+                // 'A sentinel GID follows the last range element and serves
+                // to delimit the last range in the array.'
+                // So we can simply increase the number of ranges by one.
+                let number_of_ranges = number_of_ranges.checked_add(1)?;
+
+                // Range is: GlyphId + u8
+                let mut prev_first_glyph: GlyphId = s.read()?;
+                let mut prev_index: u8 = s.read()?;
+                for _ in 1..number_of_ranges {
+                    let curr_first_glyph: GlyphId = s.read()?;
+                    if (prev_first_glyph..curr_first_glyph).contains(&glyph_id) {
+                        return Some(u16::from(prev_index));
+                    } else {
+                        prev_index = s.read()?;
+                    }
 
-    if !ctx.has_endchar {
-        return Err(CFFError::MissingEndChar);
+                    prev_first_glyph = curr_first_glyph;
+                }
+
+                None
+            }
+            FDSelect::Format4(data) => {
+                let mut s = Stream::new(data);
+                let number_of_ranges: u32 = s.read()?;
+                if number_of_ranges == 0 {
+                    return None;
+                }
+
+                // Same as format 3, but with 32-bit glyph IDs and 16-bit indices.
+                let number_of_ranges = number_of_ranges.checked_add(1)?;
+
+                let glyph_id = u32::from(glyph_id.0);
+                let mut prev_first_glyph: u32 = s.read()?;
+                let mut prev_index: u16 = s.read()?;
+                for _ in 1..number_of_ranges {
+                    let curr_first_glyph: u32 = s.read()?;
+                    if (prev_first_glyph..curr_first_glyph).contains(&glyph_id) {
+                        return Some(prev_index);
+                    } else {
+                        prev_index = s.read()?;
+                    }
+
+                    prev_first_glyph = curr_first_glyph;
+                }
+
+                None
+            }
+        }
+    }
+}
+
+/// Standard Strings defined in the Adobe Technical Note #5176, Appendix A.
+pub const STANDARD_NAMES: &[&str] = &[
+    ".notdef",
+    "space",
+    "exclam",
+    "quotedbl",
+    "numbersign",
+    "dollar",
+    "percent",
+    "ampersand",
+    "quoteright",
+    "parenleft",
+    "parenright",
+    "asterisk",
+    "plus",
+    "comma",
+    "hyphen",
+    "period",
+    "slash",
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "colon",
+    "semicolon",
+    "less",
+    "equal",
+    "greater",
+    "question",
+    "at",
+    "A",
+    "B",
+    "C",
+    "D",
+    "E",
+    "F",
+    "G",
+    "H",
+    "I",
+    "J",
+    "K",
+    "L",
+    "M",
+    "N",
+    "O",
+    "P",
+    "Q",
+    "R",
+    "S",
+    "T",
+    "U",
+    "V",
+    "W",
+    "X",
+    "Y",
+    "Z",
+    "bracketleft",
+    "backslash",
+    "bracketright",
+    "asciicircum",
+    "underscore",
+    "quoteleft",
+    "a",
+    "b",
+    "c",
+    "d",
+    "e",
+    "f",
+    "g",
+    "h",
+    "i",
+    "j",
+    "k",
+    "l",
+    "m",
+    "n",
+    "o",
+    "p",
+    "q",
+    "r",
+    "s",
+    "t",
+    "u",
+    "v",
+    "w",
+    "x",
+    "y",
+    "z",
+    "braceleft",
+    "bar",
+    "braceright",
+    "asciitilde",
+    "exclamdown",
+    "cent",
+    "sterling",
+    "fraction",
+    "yen",
+    "florin",
+    "section",
+    "currency",
+    "quotesingle",
+    "quotedblleft",
+    "guillemotleft",
+    "guilsinglleft",
+    "guilsinglright",
+    "fi",
+    "fl",
+    "endash",
+    "dagger",
+    "daggerdbl",
+    "periodcentered",
+    "paragraph",
+    "bullet",
+    "quotesinglbase",
+    "quotedblbase",
+    "quotedblright",
+    "guillemotright",
+    "ellipsis",
+    "perthousand",
+    "questiondown",
+    "grave",
+    "acute",
+    "circumflex",
+    "tilde",
+    "macron",
+    "breve",
+    "dotaccent",
+    "dieresis",
+    "ring",
+    "cedilla",
+    "hungarumlaut",
+    "ogonek",
+    "caron",
+    "emdash",
+    "AE",
+    "ordfeminine",
+    "Lslash",
+    "Oslash",
+    "OE",
+    "ordmasculine",
+    "ae",
+    "dotlessi",
+    "lslash",
+    "oslash",
+    "oe",
+    "germandbls",
+    "onesuperior",
+    "logicalnot",
+    "mu",
+    "trademark",
+    "Eth",
+    "onehalf",
+    "plusminus",
+    "Thorn",
+    "onequarter",
+    "divide",
+    "brokenbar",
+    "degree",
+    "thorn",
+    "threequarters",
+    "twosuperior",
+    "registered",
+    "minus",
+    "eth",
+    "multiply",
+    "threesuperior",
+    "copyright",
+    "Aacute",
+    "Acircumflex",
+    "Adieresis",
+    "Agrave",
+    "Aring",
+    "Atilde",
+    "Ccedilla",
+    "Eacute",
+    "Ecircumflex",
+    "Edieresis",
+    "Egrave",
+    "Iacute",
+    "Icircumflex",
+    "Idieresis",
+    "Igrave",
+    "Ntilde",
+    "Oacute",
+    "Ocircumflex",
+    "Odieresis",
+    "Ograve",
+    "Otilde",
+    "Scaron",
+    "Uacute",
+    "Ucircumflex",
+    "Udieresis",
+    "Ugrave",
+    "Yacute",
+    "Ydieresis",
+    "Zcaron",
+    "aacute",
+    "acircumflex",
+    "adieresis",
+    "agrave",
+    "aring",
+    "atilde",
+    "ccedilla",
+    "eacute",
+    "ecircumflex",
+    "edieresis",
+    "egrave",
+    "iacute",
+    "icircumflex",
+    "idieresis",
+    "igrave",
+    "ntilde",
+    "oacute",
+    "ocircumflex",
+    "odieresis",
+    "ograve",
+    "otilde",
+    "scaron",
+    "uacute",
+    "ucircumflex",
+    "udieresis",
+    "ugrave",
+    "yacute",
+    "ydieresis",
+    "zcaron",
+    "exclamsmall",
+    "Hungarumlautsmall",
+    "dollaroldstyle",
+    "dollarsuperior",
+    "ampersandsmall",
+    "Acutesmall",
+    "parenleftsuperior",
+    "parenrightsuperior",
+    "twodotenleader",
+    "onedotenleader",
+    "zerooldstyle",
+    "oneoldstyle",
+    "twooldstyle",
+    "threeoldstyle",
+    "fouroldstyle",
+    "fiveoldstyle",
+    "sixoldstyle",
+    "sevenoldstyle",
+    "eightoldstyle",
+    "nineoldstyle",
+    "commasuperior",
+    "threequartersemdash",
+    "periodsuperior",
+    "questionsmall",
+    "asuperior",
+    "bsuperior",
+    "centsuperior",
+    "dsuperior",
+    "esuperior",
+    "isuperior",
+    "lsuperior",
+    "msuperior",
+    "nsuperior",
+    "osuperior",
+    "rsuperior",
+    "ssuperior",
+    "tsuperior",
+    "ff",
+    "ffi",
+    "ffl",
+    "parenleftinferior",
+    "parenrightinferior",
+    "Circumflexsmall",
+    "hyphensuperior",
+    "Gravesmall",
+    "Asmall",
+    "Bsmall",
+    "Csmall",
+    "Dsmall",
+    "Esmall",
+    "Fsmall",
+    "Gsmall",
+    "Hsmall",
+    "Ismall",
+    "Jsmall",
+    "Ksmall",
+    "Lsmall",
+    "Msmall",
+    "Nsmall",
+    "Osmall",
+    "Psmall",
+    "Qsmall",
+    "Rsmall",
+    "Ssmall",
+    "Tsmall",
+    "Usmall",
+    "Vsmall",
+    "Wsmall",
+    "Xsmall",
+    "Ysmall",
+    "Zsmall",
+    "colonmonetary",
+    "onefitted",
+    "rupiah",
+    "Tildesmall",
+    "exclamdownsmall",
+    "centoldstyle",
+    "Lslashsmall",
+    "Scaronsmall",
+    "Zcaronsmall",
+    "Dieresissmall",
+    "Brevesmall",
+    "Caronsmall",
+    "Dotaccentsmall",
+    "Macronsmall",
+    "figuredash",
+    "hypheninferior",
+    "Ogoneksmall",
+    "Ringsmall",
+    "Cedillasmall",
+    "questiondownsmall",
+    "oneeighth",
+    "threeeighths",
+    "fiveeighths",
+    "seveneighths",
+    "onethird",
+    "twothirds",
+    "zerosuperior",
+    "foursuperior",
+    "fivesuperior",
+    "sixsuperior",
+    "sevensuperior",
+    "eightsuperior",
+    "ninesuperior",
+    "zeroinferior",
+    "oneinferior",
+    "twoinferior",
+    "threeinferior",
+    "fourinferior",
+    "fiveinferior",
+    "sixinferior",
+    "seveninferior",
+    "eightinferior",
+    "nineinferior",
+    "centinferior",
+    "dollarinferior",
+    "periodinferior",
+    "commainferior",
+    "Agravesmall",
+    "Aacutesmall",
+    "Acircumflexsmall",
+    "Atildesmall",
+    "Adieresissmall",
+    "Aringsmall",
+    "AEsmall",
+    "Ccedillasmall",
+    "Egravesmall",
+    "Eacutesmall",
+    "Ecircumflexsmall",
+    "Edieresissmall",
+    "Igravesmall",
+    "Iacutesmall",
+    "Icircumflexsmall",
+    "Idieresissmall",
+    "Ethsmall",
+    "Ntildesmall",
+    "Ogravesmall",
+    "Oacutesmall",
+    "Ocircumflexsmall",
+    "Otildesmall",
+    "Odieresissmall",
+    "OEsmall",
+    "Oslashsmall",
+    "Ugravesmall",
+    "Uacutesmall",
+    "Ucircumflexsmall",
+    "Udieresissmall",
+    "Yacutesmall",
+    "Thornsmall",
+    "Ydieresissmall",
+    "001.000",
+    "001.001",
+    "001.002",
+    "001.003",
+    "Black",
+    "Bold",
+    "Book",
+    "Light",
+    "Medium",
+    "Regular",
+    "Roman",
+    "Semibold",
+];
+
+/// Maps a Standard Encoding character code to a SID.
+///
+/// Defined in the Adobe Technical Note #5176, Appendix B.
+pub const STANDARD_ENCODING: [u8; 256] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
+    17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32,
+    33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48,
+    49, 50, 51, 52, 53, 54, 55, 56, 57, 58, 59, 60, 61, 62, 63, 64,
+    65, 66, 67, 68, 69, 70, 71, 72, 73, 74, 75, 76, 77, 78, 79, 80,
+    81, 82, 83, 84, 85, 86, 87, 88, 89, 90, 91, 92, 93, 94, 95, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 96, 97, 98, 99, 100, 101, 102, 103, 104, 105, 106, 107, 108, 109, 110,
+    0, 111, 112, 113, 114, 0, 115, 116, 117, 118, 119, 120, 121, 122, 0, 123,
+    0, 124, 125, 126, 127, 128, 129, 130, 131, 0, 132, 133, 0, 134, 135, 136,
+    137, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 138, 0, 139, 0, 0, 0, 0, 140, 141, 142, 143, 0, 0, 0, 0,
+    0, 144, 0, 0, 0, 145, 0, 0, 146, 147, 148, 149, 0, 0, 0, 0,
+];
+
+/// Enumerates Charset IDs defined in the Adobe Technical Note #5176, Table 22
+mod charset_id {
+    pub const ISO_ADOBE: usize = 0;
+    pub const EXPERT: usize = 1;
+    pub const EXPERT_SUBSET: usize = 2;
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct StringId(u16);
+
+impl FromData for StringId {
+    const SIZE: usize = 2;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        u16::parse(data).map(StringId)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Charset<'a> {
+    ISOAdobe,
+    Expert,
+    ExpertSubset,
+    Format0(LazyArray16<'a, StringId>),
+    Format1(&'a [u8]), // It's easier to parse it in-place.
+    Format2(&'a [u8]), // It's easier to parse it in-place.
+}
+
+impl Default for Charset<'_> {
+    #[inline]
+    fn default() -> Self {
+        Charset::ISOAdobe
+    }
+}
+
+impl Charset<'_> {
+    fn gid_to_sid(&self, glyph_id: GlyphId, number_of_glyphs: u16) -> Option<StringId> {
+        if glyph_id.0 == 0 {
+            return Some(StringId(0));
+        }
+
+        if glyph_id.0 >= number_of_glyphs {
+            return None;
+        }
+
+        match *self {
+            Charset::ISOAdobe => {
+                if glyph_id.0 <= 228 { Some(StringId(glyph_id.0)) } else { None }
+            }
+            Charset::Expert | Charset::ExpertSubset => None,
+            Charset::Format0(ref array) => array.get(glyph_id.0 - 1),
+            Charset::Format1(data) => {
+                charset_ranges_gid_to_sid::<u8>(data, glyph_id, number_of_glyphs)
+            }
+            Charset::Format2(data) => {
+                charset_ranges_gid_to_sid::<u16>(data, glyph_id, number_of_glyphs)
+            }
+        }
+    }
+
+    fn sid_to_gid(&self, sid: StringId, number_of_glyphs: u16) -> Option<GlyphId> {
+        if sid.0 == 0 {
+            return Some(GlyphId(0));
+        }
+
+        match *self {
+            Charset::ISOAdobe => {
+                // ISO Adobe charset maps SIDs 0..=228 to the same glyph IDs.
+                if sid.0 <= 228 && sid.0 < number_of_glyphs {
+                    Some(GlyphId(sid.0))
+                } else {
+                    None
+                }
+            }
+            // TODO: Expert charsets are used only by a couple of ancient fonts.
+            Charset::Expert | Charset::ExpertSubset => None,
+            Charset::Format0(ref array) => {
+                // '.notdef' is not stored, so the first item is glyph 1.
+                let (index, _) = array.into_iter().enumerate().find(|(_, n)| *n == sid)?;
+                u16::try_from(index).ok()?.checked_add(1).map(GlyphId)
+            }
+            Charset::Format1(data) => {
+                charset_ranges_sid_to_gid::<u8>(data, sid, number_of_glyphs)
+            }
+            Charset::Format2(data) => {
+                charset_ranges_sid_to_gid::<u16>(data, sid, number_of_glyphs)
+            }
+        }
+    }
+}
+
+// Charset formats 1 and 2 differ only by the `nLeft` field type.
+// Ranges are not counted, so we have to stop when all glyphs are covered.
+fn charset_ranges_sid_to_gid<T>(data: &[u8], sid: StringId, number_of_glyphs: u16) -> Option<GlyphId>
+    where T: FromData + Into<u16>
+{
+    let mut s = Stream::new(data);
+    // '.notdef' is not stored, so the first range starts at glyph 1.
+    let mut glyph_id = 1u16;
+    while glyph_id < number_of_glyphs {
+        let first: u16 = s.read()?;
+        let left: u16 = s.read::<T>()?.into();
+        let last = first.checked_add(left)?;
+        if sid.0 >= first && sid.0 <= last {
+            return glyph_id.checked_add(sid.0 - first).map(GlyphId);
+        }
+
+        glyph_id = glyph_id.checked_add(left)?.checked_add(1)?;
+    }
+
+    None
+}
+
+fn charset_ranges_gid_to_sid<T>(data: &[u8], glyph_id: GlyphId, number_of_glyphs: u16) -> Option<StringId>
+    where T: FromData + Into<u16>
+{
+    let mut s = Stream::new(data);
+    let mut first_glyph = 1u16;
+    while first_glyph < number_of_glyphs {
+        let first: u16 = s.read()?;
+        let left: u16 = s.read::<T>()?.into();
+        let last_glyph = first_glyph.checked_add(left)?;
+        if glyph_id.0 >= first_glyph && glyph_id.0 <= last_glyph {
+            return first.checked_add(glyph_id.0 - first_glyph).map(StringId);
+        }
+
+        first_glyph = last_glyph.checked_add(1)?;
+    }
+
+    None
+}
+
+fn parse_charset<'a>(number_of_glyphs: u16, s: &mut Stream<'a>) -> Option<Charset<'a>> {
+    if number_of_glyphs < 2 {
+        return None;
+    }
+
+    // -1 everywhere, since `.notdef` is omitted.
+    let format: u8 = s.read()?;
+    match format {
+        0 => Some(Charset::Format0(s.read_array16(number_of_glyphs - 1)?)),
+        1 => Some(Charset::Format1(s.tail()?)),
+        2 => Some(Charset::Format2(s.tail()?)),
+        _ => None,
+    }
+}
+
+/// Enumerates Encoding IDs defined in the Adobe Technical Note #5176, Table 16
+mod encoding_id {
+    pub const STANDARD: usize = 0;
+    pub const EXPERT: usize = 1;
+}
+
+#[derive(Clone, Copy, Debug)]
+struct EncodingRange {
+    first: u8,
+    left: u8,
+}
+
+impl FromData for EncodingRange {
+    const SIZE: usize = 2;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        Some(EncodingRange {
+            first: s.read()?,
+            left: s.read()?,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Supplement {
+    code: u8,
+    name: StringId,
+}
+
+impl FromData for Supplement {
+    const SIZE: usize = 3;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        Some(Supplement {
+            code: s.read()?,
+            name: s.read()?,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum EncodingKind<'a> {
+    Standard,
+    Expert,
+    Format0(LazyArray16<'a, u8>),
+    Format1(LazyArray16<'a, EncodingRange>),
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Encoding<'a> {
+    kind: EncodingKind<'a>,
+    supplemental: LazyArray16<'a, Supplement>,
+}
+
+impl Default for Encoding<'_> {
+    #[inline]
+    fn default() -> Self {
+        Encoding::new(EncodingKind::Standard)
+    }
+}
+
+impl<'a> Encoding<'a> {
+    #[inline]
+    fn new(kind: EncodingKind<'a>) -> Self {
+        Encoding {
+            kind,
+            supplemental: LazyArray16::default(),
+        }
+    }
+
+    fn code_to_gid(&self, charset: &Charset, number_of_glyphs: u16, code: u8) -> Option<GlyphId> {
+        if let Some(supplement) = self.supplemental.into_iter().find(|s| s.code == code) {
+            return charset.sid_to_gid(supplement.name, number_of_glyphs);
+        }
+
+        match self.kind {
+            EncodingKind::Standard => {
+                let sid = StringId(u16::from(STANDARD_ENCODING[usize::from(code)]));
+                match sid.0 {
+                    0 => None,
+                    _ => charset.sid_to_gid(sid, number_of_glyphs),
+                }
+            }
+            // TODO: Expert encoding is used only by a couple of ancient fonts.
+            EncodingKind::Expert => None,
+            EncodingKind::Format0(ref codes) => {
+                // '.notdef' is not encoded, so the first code is glyph 1.
+                let (index, _) = codes.into_iter().enumerate().find(|(_, c)| *c == code)?;
+                u16::try_from(index).ok()?.checked_add(1).map(GlyphId)
+            }
+            EncodingKind::Format1(ref ranges) => {
+                let mut glyph_id = 1u16;
+                for range in ranges.into_iter() {
+                    let offset = code.checked_sub(range.first);
+                    match offset {
+                        Some(offset) if offset <= range.left => {
+                            return glyph_id.checked_add(u16::from(offset)).map(GlyphId);
+                        }
+                        _ => {
+                            glyph_id = glyph_id.checked_add(u16::from(range.left) + 1)?;
+                        }
+                    }
+                }
+
+                None
+            }
+        }
+    }
+}
+
+fn parse_encoding<'a>(s: &mut Stream<'a>) -> Option<Encoding<'a>> {
+    let format: u8 = s.read()?;
+    // 'A high-order bit set in the format byte indicates
+    // that supplemental encoding data is present.'
+    let kind = match format & 0x7f {
+        0 => {
+            let count: u8 = s.read()?;
+            EncodingKind::Format0(s.read_array16(u16::from(count))?)
+        }
+        1 => {
+            let count: u8 = s.read()?;
+            EncodingKind::Format1(s.read_array16(u16::from(count))?)
+        }
+        _ => return None,
+    };
+
+    let supplemental = if format & 0x80 != 0 {
+        let count: u8 = s.read()?;
+        s.read_array16(u16::from(count))?
+    } else {
+        LazyArray16::default()
+    };
+
+    Some(Encoding {
+        kind,
+        supplemental,
+    })
+}
+
+fn parse_fd_select<'a>(number_of_glyphs: u16, s: &mut Stream<'a>) -> Option<FDSelect<'a>> {
+    let format: u8 = s.read()?;
+    match format {
+        0 => Some(FDSelect::Format0(s.read_array16(number_of_glyphs)?)),
+        3 => Some(FDSelect::Format3(s.tail()?)),
+        _ => None,
     }
+}
+
+struct CharStringParserContext<'a> {
+    metadata: &'a Metadata<'a>,
+    local_subrs: Option<DataIndex<'a>>,
+    is_first_move_to: bool,
+    has_move_to: bool,
+    width_parsed: bool,
+    width: Option<f32>,
+    stems_len: u32,
+    has_endchar: bool,
+}
 
-    let bbox = inner_builder.bbox;
+fn parse_char_string(
+    data: &[u8],
+    metadata: &Metadata,
+    glyph_id: GlyphId,
+    builder: &mut dyn HintedOutlineBuilder,
+) -> Result<Rect, CFFError> {
+    let (bbox, _) = parse_char_string_impl(data, metadata, glyph_id, builder)?;
 
     // Check that bbox was changed.
     if bbox.is_default() {
@@ -278,9 +1714,53 @@ fn parse_char_string(
     bbox.to_rect().ok_or(CFFError::BboxOverflow)
 }
 
+// Returns a bounding box and a width stored in the charstring, if any.
+fn parse_char_string_impl(
+    data: &[u8],
+    metadata: &Metadata,
+    glyph_id: GlyphId,
+    builder: &mut dyn HintedOutlineBuilder,
+) -> Result<(BBox, Option<f32>), CFFError> {
+    let local_subrs = match metadata.kind {
+        FontKind::Sid(ref sid) => Some(sid.local_subrs),
+        FontKind::Cid(ref cid) => {
+            cid_private_dict(metadata, glyph_id, cid).and_then(|dict| dict.local_subrs)
+        }
+    };
+
+    let mut ctx = CharStringParserContext {
+        metadata,
+        local_subrs,
+        is_first_move_to: true,
+        has_move_to: false,
+        width_parsed: false,
+        width: None,
+        stems_len: 0,
+        has_endchar: false,
+    };
+
+    let mut inner_builder = Builder {
+        builder,
+        bbox: BBox::new(),
+    };
+
+    let mut stack = ArgumentsStack {
+        data: &mut [0.0; MAX_ARGUMENTS_STACK_LEN], // 192B
+        len: 0,
+        max_len: MAX_ARGUMENTS_STACK_LEN,
+    };
+    let _ = _parse_char_string(&mut ctx, data, 0.0, 0.0, &mut stack, 0, &mut inner_builder)?;
+
+    if !ctx.has_endchar {
+        return Err(CFFError::MissingEndChar);
+    }
+
+    Ok((inner_builder.bbox, ctx.width))
+}
+
 
 pub(crate) struct Builder<'a> {
-    pub builder: &'a mut dyn OutlineBuilder,
+    pub builder: &'a mut dyn HintedOutlineBuilder,
     pub bbox: BBox,
 }
 
@@ -309,6 +1789,33 @@ impl<'a> Builder<'a> {
     pub fn close(&mut self) {
         self.builder.close();
     }
+
+    // Stems are stored as a delta-encoded array of edge/size pairs.
+    pub fn stems(&mut self, stack: &ArgumentsStack, start: usize, is_horizontal: bool) {
+        let mut pos = 0.0;
+        let mut i = start;
+        while i + 1 < stack.len() {
+            let edge = pos + stack.at(i);
+            let size = stack.at(i + 1);
+            if is_horizontal {
+                self.builder.hstem(edge, size);
+            } else {
+                self.builder.vstem(edge, size);
+            }
+
+            pos = edge + size;
+            i += 2;
+        }
+    }
+
+    #[inline]
+    pub fn mask(&mut self, mask: &[u8], is_counter: bool) {
+        if is_counter {
+            self.builder.counter_mask(mask);
+        } else {
+            self.builder.hint_mask(mask);
+        }
+    }
 }
 
 fn _parse_char_string(
@@ -338,16 +1845,20 @@ fn _parse_char_string(
                 // x dx {dxa dxb}* vstemhm
 
                 // If the stack length is uneven, than the first value is a `width`.
-                let len = if stack.len().is_odd() && !ctx.width_parsed {
+                let start = if stack.len().is_odd() && !ctx.width_parsed {
                     ctx.width_parsed = true;
-                    stack.len() - 1
+                    ctx.width = Some(stack.at(0));
+                    1
                 } else {
-                    stack.len()
+                    0
                 };
 
-                ctx.stems_len += len as u32 >> 1;
+                ctx.stems_len += (stack.len() - start) as u32 >> 1;
+
+                let is_horizontal = op == operator::HORIZONTAL_STEM
+                    || op == operator::HORIZONTAL_STEM_HINT_MASK;
+                builder.stems(stack, start, is_horizontal);
 
-                // We are ignoring the hint operators.
                 stack.clear();
             }
             operator::VERTICAL_MOVE_TO => {
@@ -357,6 +1868,7 @@ fn _parse_char_string(
                 if stack.len() == 2 && !ctx.width_parsed {
                     i += 1;
                     ctx.width_parsed = true;
+                    ctx.width = Some(stack.at(0));
                 } else if stack.len() != 1 {
                     return Err(CFFError::InvalidArgumentsStackLength);
                 }
@@ -488,9 +2000,14 @@ fn _parse_char_string(
                     return Err(CFFError::NestingLimitReached);
                 }
 
-                let subroutine_bias = calc_subroutine_bias(ctx.metadata.local_subrs.len());
+                let local_subrs = match ctx.local_subrs {
+                    Some(v) => v,
+                    None => return Err(CFFError::NoLocalSubroutines),
+                };
+
+                let subroutine_bias = calc_subroutine_bias(local_subrs.len());
                 let index = conv_subroutine_index(stack.pop(), subroutine_bias)?;
-                let char_string = ctx.metadata.local_subrs.get(index)
+                let char_string = local_subrs.get(index)
                     .ok_or(CFFError::InvalidSubroutineIndex)?;
                 let pos = _parse_char_string(ctx, char_string, x, y, stack, depth + 1, builder)?;
                 x = pos.0;
@@ -632,7 +2149,50 @@ fn _parse_char_string(
                 }
             }
             operator::ENDCHAR => {
+                if stack.len() == 4 || (!ctx.width_parsed && stack.len() == 5) {
+                    // Process the deprecated 'seac' form of 'endchar':
+                    // adx ady bchar achar endchar
+                    let accent_char = seac_code_to_glyph_id(ctx.metadata, stack.pop())
+                        .ok_or(CFFError::InvalidSeacCode)?;
+                    let base_char = seac_code_to_glyph_id(ctx.metadata, stack.pop())
+                        .ok_or(CFFError::InvalidSeacCode)?;
+                    let dy = stack.pop();
+                    let dx = stack.pop();
+
+                    if !ctx.width_parsed {
+                        ctx.width = Some(stack.pop());
+                        ctx.width_parsed = true;
+                    }
+
+                    if depth == STACK_LIMIT {
+                        return Err(CFFError::NestingLimitReached);
+                    }
+
+                    if !ctx.is_first_move_to {
+                        builder.close();
+                    }
+
+                    // Components have their own widths, which must be ignored.
+                    let width = ctx.width;
+
+                    let base_char_string = ctx.metadata.char_strings.get(base_char.0)
+                        .ok_or(CFFError::InvalidSeacCode)?;
+                    parse_seac_component(ctx, base_char_string, 0.0, 0.0, stack, depth + 1, builder)?;
+
+                    let accent_char_string = ctx.metadata.char_strings.get(accent_char.0)
+                        .ok_or(CFFError::InvalidSeacCode)?;
+                    parse_seac_component(ctx, accent_char_string, dx, dy, stack, depth + 1, builder)?;
+                    ctx.width = width;
+
+                    if !s.at_end() {
+                        return Err(CFFError::DataAfterEndChar);
+                    }
+
+                    break;
+                }
+
                 if !stack.is_empty() && !ctx.width_parsed {
+                    ctx.width = Some(stack.at(0));
                     stack.clear();
                     ctx.width_parsed = true;
                 }
@@ -651,20 +2211,23 @@ fn _parse_char_string(
                 break;
             }
             operator::HINT_MASK | operator::COUNTER_MASK => {
-                let mut len = stack.len();
-
-                // We are ignoring the hint operators.
-                stack.clear();
-
                 // If the stack length is uneven, than the first value is a `width`.
-                if len.is_odd() && !ctx.width_parsed {
-                    len -= 1;
+                let start = if stack.len().is_odd() && !ctx.width_parsed {
                     ctx.width_parsed = true;
-                }
+                    ctx.width = Some(stack.at(0));
+                    1
+                } else {
+                    0
+                };
 
-                ctx.stems_len += len as u32 >> 1;
+                // Values on the stack are an implicit `vstem`.
+                ctx.stems_len += (stack.len() - start) as u32 >> 1;
+                builder.stems(stack, start, false);
+                stack.clear();
 
-                s.advance(usize::num_from((ctx.stems_len + 7) >> 3));
+                let mask = s.read_bytes(usize::num_from((ctx.stems_len + 7) >> 3))
+                    .ok_or(CFFError::ReadOutOfBounds)?;
+                builder.mask(mask, op == operator::COUNTER_MASK);
             }
             operator::MOVE_TO => {
                 // dx1 dy1
@@ -673,6 +2236,7 @@ fn _parse_char_string(
                 if stack.len() == 3 && !ctx.width_parsed {
                     i += 1;
                     ctx.width_parsed = true;
+                    ctx.width = Some(stack.at(0));
                 } else if stack.len() != 2 {
                     return Err(CFFError::InvalidArgumentsStackLength);
                 }
@@ -698,6 +2262,7 @@ fn _parse_char_string(
                 if stack.len() == 2 && !ctx.width_parsed {
                     i += 1;
                     ctx.width_parsed = true;
+                    ctx.width = Some(stack.at(0));
                 } else if stack.len() != 1 {
                     return Err(CFFError::InvalidArgumentsStackLength);
                 }
@@ -1013,6 +2578,49 @@ pub fn calc_subroutine_bias(len: u16) -> u16 {
     }
 }
 
+// Components of an accented character are separate glyphs,
+// so the parser state must be reset for each of them.
+fn parse_seac_component(
+    ctx: &mut CharStringParserContext,
+    char_string: &[u8],
+    x: f32,
+    y: f32,
+    stack: &mut ArgumentsStack,
+    depth: u8,
+    builder: &mut Builder,
+) -> Result<(), CFFError> {
+    ctx.is_first_move_to = true;
+    ctx.has_move_to = false;
+    ctx.width_parsed = false;
+    ctx.stems_len = 0;
+    ctx.has_endchar = false;
+    stack.clear();
+
+    _parse_char_string(ctx, char_string, x, y, stack, depth, builder)?;
+
+    if !ctx.has_endchar {
+        return Err(CFFError::MissingEndChar);
+    }
+
+    Ok(())
+}
+
+// 'bchar' and 'achar' are Standard Encoding codes,
+// which are mapped to glyphs via a SID and a charset.
+fn seac_code_to_glyph_id(metadata: &Metadata, n: f32) -> Option<GlyphId> {
+    // CID-keyed fonts do not use SIDs for glyph names.
+    if let FontKind::Cid(_) = metadata.kind {
+        return None;
+    }
+
+    // A font with a malformed encoding cannot be trusted to resolve codes either.
+    metadata.encoding.as_ref()?;
+
+    let code = u8::try_from(i32::try_num_from(n)?).ok()?;
+    let sid = StringId(u16::from(STANDARD_ENCODING[usize::from(code)]));
+    metadata.charset.as_ref()?.sid_to_gid(sid, metadata.char_strings.len())
+}
+
 fn parse_index<'a>(s: &mut Stream<'a>) -> Option<DataIndex<'a>> {
     let count: u16 = s.read()?;
     if count != 0 && count != core::u16::MAX {
@@ -1234,7 +2842,7 @@ struct DictionaryParser<'a> {
     // Offset to the last operands start.
     operands_offset: usize,
     // Actual operands.
-    operands: [i32; MAX_OPERANDS_LEN as usize], // 192B
+    operands: [f64; MAX_OPERANDS_LEN as usize], // 384B
     // An amount of operands in the `operands` array.
     operands_len: u8,
 }
@@ -1246,7 +2854,7 @@ impl<'a> DictionaryParser<'a> {
             data,
             offset: 0,
             operands_offset: 0,
-            operands: [0; MAX_OPERANDS_LEN as usize],
+            operands: [0.0; MAX_OPERANDS_LEN as usize],
             operands_len: 0,
         }
     }
@@ -1297,7 +2905,7 @@ impl<'a> DictionaryParser<'a> {
             if is_dict_one_byte_op(b) {
                 break;
             } else {
-                let op = parse_number(b, &mut s)?;
+                let op = parse_dict_number(b, &mut s)?;
                 self.operands[usize::from(self.operands_len)] = op;
                 self.operands_len += 1;
 
@@ -1311,7 +2919,7 @@ impl<'a> DictionaryParser<'a> {
     }
 
     #[inline]
-    fn operands(&self) -> &[i32] {
+    fn operands(&self) -> &[f64] {
         &self.operands[..usize::from(self.operands_len)]
     }
 }
@@ -1328,16 +2936,98 @@ pub fn is_dict_one_byte_op(b: u8) -> bool {
     }
 }
 
-// Adobe Technical Note #5177, Table 3 Operand Encoding
-pub fn parse_number(b0: u8, s: &mut Stream) -> Option<i32> {
+// Unlike `parse_number`, also parses real numbers.
+fn parse_dict_number(b0: u8, s: &mut Stream) -> Option<f64> {
     match b0 {
-        28 => {
-            let n = i32::from(s.read::<i16>()?);
-            Some(n)
+        30 => parse_float(s),
+        _ => parse_number(b0, s).map(f64::from),
+    }
+}
+
+// Adobe Technical Note #5176, Table 5 Nibble Definitions
+//
+// We are not using `str::parse`, because it will pull the core::num::dec2flt dependency.
+fn parse_float(s: &mut Stream) -> Option<f64> {
+    let mut mantissa = 0.0f64;
+    let mut fraction_digits = 0i32;
+    let mut exponent = 0i32;
+    let mut is_negative = false;
+    let mut is_negative_exponent = false;
+    let mut has_point = false;
+    let mut has_exponent = false;
+
+    'outer: loop {
+        let b1: u8 = s.read()?;
+        for nibble in [b1 >> 4, b1 & 15].iter().cloned() {
+            match nibble {
+                0..=9 => {
+                    if has_exponent {
+                        // Large exponents will overflow `f64` anyway.
+                        exponent = exponent.saturating_mul(10).saturating_add(i32::from(nibble));
+                    } else {
+                        mantissa = mantissa * 10.0 + f64::from(nibble);
+                        if has_point {
+                            fraction_digits += 1;
+                        }
+                    }
+                }
+                0xa => has_point = true,
+                0xb => has_exponent = true,
+                0xc => {
+                    has_exponent = true;
+                    is_negative_exponent = true;
+                }
+                0xd => return None, // Reserved.
+                0xe => is_negative = true,
+                END_OF_FLOAT_FLAG => break 'outer,
+                _ => return None,
+            }
         }
-        29 => {
-            let n = s.read::<i32>()?;
-            Some(n)
+    }
+
+    if is_negative_exponent {
+        exponent = -exponent;
+    }
+
+    let power = exponent.saturating_sub(fraction_digits);
+    // 10^309 is already outside the `f64` range.
+    if !(-308..=308).contains(&power) {
+        return None;
+    }
+
+    let mut scale = 1.0f64;
+    for _ in 0..power.abs() {
+        scale *= 10.0;
+    }
+
+    let mut n = if power < 0 { mantissa / scale } else { mantissa * scale };
+    if is_negative {
+        n = -n;
+    }
+
+    Some(n)
+}
+
+// Offsets and lengths in DICT are stored as numbers.
+fn f64_to_usize(n: f64) -> Option<usize> {
+    let v = n as u32;
+    if n >= 0.0 && f64::from(v) == n {
+        usize::try_from(v).ok()
+    } else {
+        None
+    }
+}
+
+// Adobe Technical Note #5177, Table 3 Operand Encoding
+pub fn parse_number(b0: u8, s: &mut Stream) -> Option<i32> {
+    match b0 {
+        28 => {
+            let n = i32::from(s.read::<i16>()?);
+            Some(n)
+        }
+        29 => {
+            let n = s.read::<i32>()?;
+            Some(n)
         }
         30 => {
             // We do not parse float, because we don't use it.
@@ -1517,6 +3207,49 @@ mod tests {
         }
     }
 
+    impl HintedOutlineBuilder for Builder {}
+
+    struct HintsBuilder(String);
+    impl OutlineBuilder for HintsBuilder {
+        fn move_to(&mut self, x: f32, y: f32) {
+            write!(&mut self.0, "M {} {} ", x, y).unwrap();
+        }
+
+        fn line_to(&mut self, x: f32, y: f32) {
+            write!(&mut self.0, "L {} {} ", x, y).unwrap();
+        }
+
+        fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+            write!(&mut self.0, "Q {} {} {} {} ", x1, y1, x, y).unwrap();
+        }
+
+        fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+            write!(&mut self.0, "C {} {} {} {} {} {} ", x1, y1, x2, y2, x, y).unwrap();
+        }
+
+        fn close(&mut self) {
+            write!(&mut self.0, "Z ").unwrap();
+        }
+    }
+
+    impl HintedOutlineBuilder for HintsBuilder {
+        fn hstem(&mut self, y: f32, dy: f32) {
+            write!(&mut self.0, "HS {} {} ", y, dy).unwrap();
+        }
+
+        fn vstem(&mut self, x: f32, dx: f32) {
+            write!(&mut self.0, "VS {} {} ", x, dx).unwrap();
+        }
+
+        fn hint_mask(&mut self, mask: &[u8]) {
+            write!(&mut self.0, "HM {:?} ", mask).unwrap();
+        }
+
+        fn counter_mask(&mut self, mask: &[u8]) {
+            write!(&mut self.0, "CM {:?} ", mask).unwrap();
+        }
+    }
+
     impl core::fmt::Display for CFFError {
         fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
             match *self {
@@ -1556,6 +3289,12 @@ mod tests {
                 CFFError::InvalidSubroutineIndex => {
                     write!(f, "an invalid subroutine index")
                 }
+                CFFError::NoLocalSubroutines => {
+                    write!(f, "no local subroutines")
+                }
+                CFFError::InvalidSeacCode => {
+                    write!(f, "invalid seac code")
+                }
                 CFFError::InvalidItemVariationDataIndex => {
                     write!(f, "no ItemVariationData with required index")
                 }
@@ -1751,7 +3490,7 @@ mod tests {
         let metadata = parse_metadata(&data).unwrap();
         let mut builder = Builder(String::new());
         let char_str = metadata.char_strings.get(0).unwrap();
-        let rect = parse_char_string(char_str, &metadata, &mut builder).unwrap();
+        let rect = parse_char_string(char_str, &metadata, GlyphId(0), &mut builder).unwrap();
 
         assert_eq!(builder.0, "M 10 0 Z ");
         assert_eq!(rect, Rect { x_min: 10, y_min: 0, x_max: 10, y_max: 0 });
@@ -1769,7 +3508,7 @@ mod tests {
                 let metadata = parse_metadata(&data).unwrap();
                 let mut builder = Builder(String::new());
                 let char_str = metadata.char_strings.get(0).unwrap();
-                let rect = parse_char_string(char_str, &metadata, &mut builder).unwrap();
+                let rect = parse_char_string(char_str, &metadata, GlyphId(0), &mut builder).unwrap();
 
                 assert_eq!(builder.0, $path);
                 assert_eq!(rect, $rect_res);
@@ -1791,7 +3530,7 @@ mod tests {
                 let metadata = parse_metadata(&data).unwrap();
                 let mut builder = Builder(String::new());
                 let char_str = metadata.char_strings.get(0).unwrap();
-                let res = parse_char_string(char_str, &metadata, &mut builder);
+                let res = parse_char_string(char_str, &metadata, GlyphId(0), &mut builder);
 
                 assert_eq!(res.unwrap_err().to_string(), $err);
             }
@@ -1961,7 +3700,7 @@ mod tests {
         let metadata = parse_metadata(&data).unwrap();
         let mut builder = Builder(String::new());
         let char_str = metadata.char_strings.get(0).unwrap();
-        assert!(parse_char_string(char_str, &metadata, &mut builder).is_err());
+        assert!(parse_char_string(char_str, &metadata, GlyphId(0), &mut builder).is_err());
     }
 
     test_cs_with_subrs!(local_subr,
@@ -2186,7 +3925,7 @@ mod tests {
         let metadata = parse_metadata(&data).unwrap();
         let mut builder = Builder(String::new());
         let char_str = metadata.char_strings.get(0).unwrap();
-        let res = parse_char_string(char_str, &metadata, &mut builder);
+        let res = parse_char_string(char_str, &metadata, GlyphId(0), &mut builder);
         assert_eq!(res.unwrap_err().to_string(),
                    "unused data left after 'endchar' operator");
     }
@@ -2215,7 +3954,7 @@ mod tests {
         let metadata = parse_metadata(&data).unwrap();
         let mut builder = Builder(String::new());
         let char_str = metadata.char_strings.get(0).unwrap();
-        let res = parse_char_string(char_str, &metadata, &mut builder);
+        let res = parse_char_string(char_str, &metadata, GlyphId(0), &mut builder);
         assert_eq!(res.unwrap_err().to_string(),
                    "unused data left after 'endchar' operator");
     }
@@ -2244,7 +3983,7 @@ mod tests {
         let metadata = parse_metadata(&data).unwrap();
         let mut builder = Builder(String::new());
         let char_str = metadata.char_strings.get(0).unwrap();
-        let res = parse_char_string(char_str, &metadata, &mut builder);
+        let res = parse_char_string(char_str, &metadata, GlyphId(0), &mut builder);
         assert_eq!(res.unwrap_err().to_string(),
                    "unused data left after 'endchar' operator");
     }
@@ -2268,7 +4007,7 @@ mod tests {
         let metadata = parse_metadata(&data).unwrap();
         let mut builder = Builder(String::new());
         let char_str = metadata.char_strings.get(0).unwrap();
-        let res = parse_char_string(char_str, &metadata, &mut builder);
+        let res = parse_char_string(char_str, &metadata, GlyphId(0), &mut builder);
         assert_eq!(res.unwrap_err().to_string(),
                    "subroutines nesting limit reached");
     }
@@ -2292,7 +4031,7 @@ mod tests {
         let metadata = parse_metadata(&data).unwrap();
         let mut builder = Builder(String::new());
         let char_str = metadata.char_strings.get(0).unwrap();
-        let res = parse_char_string(char_str, &metadata, &mut builder);
+        let res = parse_char_string(char_str, &metadata, GlyphId(0), &mut builder);
         assert_eq!(res.unwrap_err().to_string(),
                    "subroutines nesting limit reached");
     }
@@ -2319,7 +4058,7 @@ mod tests {
         let metadata = parse_metadata(&data).unwrap();
         let mut builder = Builder(String::new());
         let char_str = metadata.char_strings.get(0).unwrap();
-        let res = parse_char_string(char_str, &metadata, &mut builder);
+        let res = parse_char_string(char_str, &metadata, GlyphId(0), &mut builder);
         assert_eq!(res.unwrap_err().to_string(),
                    "subroutines nesting limit reached");
     }
@@ -2425,8 +4164,9 @@ mod tests {
             UInt8(top_dict_operator::PRIVATE_DICT_SIZE_AND_OFFSET as u8),
         ]);
 
-        assert_eq!(parse_top_dict(&mut Stream::new(&data)).unwrap(),
-                   (5, Some(2147483647..4294967294)));
+        let top_dict = parse_top_dict(&mut Stream::new(&data)).unwrap();
+        assert_eq!(top_dict.char_strings_offset, 5);
+        assert_eq!(top_dict.private_dict_range, Some(2147483647..4294967294));
     }
 
     #[test]
@@ -2475,6 +4215,718 @@ mod tests {
         assert!(parse_top_dict(&mut Stream::new(&data)).is_none());
     }
 
+    #[test]
+    fn fd_select_format_0() {
+        let data = writer::convert(&[
+            UInt8(0), // format
+            UInt8(1), // glyph 0
+            UInt8(0), // glyph 1
+            UInt8(2), // glyph 2
+        ]);
+
+        let fd_select = parse_fd_select(3, &mut Stream::new(&data)).unwrap();
+        assert_eq!(fd_select.font_dict_index(GlyphId(0)), Some(1));
+        assert_eq!(fd_select.font_dict_index(GlyphId(1)), Some(0));
+        assert_eq!(fd_select.font_dict_index(GlyphId(2)), Some(2));
+        assert_eq!(fd_select.font_dict_index(GlyphId(3)), None);
+    }
+
+    #[test]
+    fn fd_select_format_3() {
+        let data = writer::convert(&[
+            UInt8(3), // format
+            UInt16(2), // number of ranges
+            // Range 0
+            UInt16(0), // first glyph
+            UInt8(1), // font dict index
+            // Range 1
+            UInt16(5), // first glyph
+            UInt8(0), // font dict index
+            UInt16(10), // sentinel
+        ]);
+
+        let fd_select = parse_fd_select(10, &mut Stream::new(&data)).unwrap();
+        assert_eq!(fd_select.font_dict_index(GlyphId(0)), Some(1));
+        assert_eq!(fd_select.font_dict_index(GlyphId(4)), Some(1));
+        assert_eq!(fd_select.font_dict_index(GlyphId(5)), Some(0));
+        assert_eq!(fd_select.font_dict_index(GlyphId(9)), Some(0));
+        assert_eq!(fd_select.font_dict_index(GlyphId(10)), None);
+    }
+
+    #[test]
+    fn fd_select_unsupported_format() {
+        let data = writer::convert(&[
+            UInt8(1), // format
+        ]);
+
+        assert!(parse_fd_select(1, &mut Stream::new(&data)).is_none());
+    }
+
+    #[test]
+    fn cid_font_local_subrs() {
+        let data = writer::convert(&[
+            // Header
+            UInt8(1), // major version
+            UInt8(0), // minor version
+            UInt8(4), // header size
+            UInt8(0), // absolute offset
+            // Name INDEX
+            UInt16(0), // count
+            // Top DICT INDEX
+            UInt16(1), // count
+            UInt8(1), // offset size
+            UInt8(1), // index[0]
+            UInt8(14), // index[1]
+            // Item 0
+            CFFInt(0), CFFInt(0), CFFInt(0),
+            UInt8(12), UInt8((top_dict_operator::ROS - 1200) as u8),
+            CFFInt(28),
+            UInt8(top_dict_operator::CHAR_STRINGS_OFFSET as u8),
+            CFFInt(51),
+            UInt8(12), UInt8((top_dict_operator::FD_ARRAY - 1200) as u8),
+            CFFInt(40),
+            UInt8(12), UInt8((top_dict_operator::FD_SELECT - 1200) as u8),
+            // String INDEX
+            UInt16(0), // count
+            // Global Subroutines INDEX
+            UInt16(0), // count
+            // CharString INDEX (offset 28)
+            UInt16(2), // count
+            UInt8(1), // offset size
+            UInt8(1), // index[0]
+            UInt8(4), // index[1]
+            UInt8(7), // index[2]
+            // Glyph 0
+            CFFInt(-107), UInt8(operator::CALL_LOCAL_SUBROUTINE), UInt8(operator::ENDCHAR),
+            // Glyph 1
+            CFFInt(-107), UInt8(operator::CALL_LOCAL_SUBROUTINE), UInt8(operator::ENDCHAR),
+            // FDSelect (offset 40)
+            UInt8(3), // format
+            UInt16(2), // number of ranges
+            UInt16(0), // first glyph
+            UInt8(0), // font dict index
+            UInt16(1), // first glyph
+            UInt8(1), // font dict index
+            UInt16(2), // sentinel
+            // FDArray INDEX (offset 51)
+            UInt16(2), // count
+            UInt8(1), // offset size
+            UInt8(1), // index[0]
+            UInt8(4), // index[1]
+            UInt8(7), // index[2]
+            // Font DICT 0
+            CFFInt(2), // length
+            CFFInt(63), // offset
+            UInt8(top_dict_operator::PRIVATE_DICT_SIZE_AND_OFFSET as u8),
+            // Font DICT 1
+            CFFInt(2), // length
+            CFFInt(77), // offset
+            UInt8(top_dict_operator::PRIVATE_DICT_SIZE_AND_OFFSET as u8),
+            // Private DICT 0 (offset 63)
+            CFFInt(2),
+            UInt8(private_dict_operator::LOCAL_SUBROUTINES_OFFSET as u8),
+            // Local Subroutines INDEX 0
+            UInt16(1), // count
+            UInt8(1), // offset size
+            UInt8(1), // index[0]
+            UInt8(8), // index[1]
+            CFFInt(10), CFFInt(20), UInt8(operator::MOVE_TO),
+            CFFInt(30), CFFInt(40), UInt8(operator::LINE_TO),
+            UInt8(operator::RETURN),
+            // Private DICT 1 (offset 77)
+            CFFInt(2),
+            UInt8(private_dict_operator::LOCAL_SUBROUTINES_OFFSET as u8),
+            // Local Subroutines INDEX 1
+            UInt16(1), // count
+            UInt8(1), // offset size
+            UInt8(1), // index[0]
+            UInt8(8), // index[1]
+            CFFInt(1), CFFInt(2), UInt8(operator::MOVE_TO),
+            CFFInt(3), CFFInt(4), UInt8(operator::LINE_TO),
+            UInt8(operator::RETURN),
+        ]);
+
+        let metadata = parse_metadata(&data).unwrap();
+
+        let mut builder = Builder(String::new());
+        let bbox = outline(&metadata, GlyphId(0), &mut builder).unwrap();
+        assert_eq!(builder.0, "M 10 20 L 40 60 Z ");
+        assert_eq!(bbox, rect(10, 20, 40, 60));
+
+        let mut builder = Builder(String::new());
+        let bbox = outline(&metadata, GlyphId(1), &mut builder).unwrap();
+        assert_eq!(builder.0, "M 1 2 L 4 6 Z ");
+        assert_eq!(bbox, rect(1, 2, 4, 6));
+
+        assert!(outline(&metadata, GlyphId(2), &mut Builder(String::new())).is_none());
+    }
+
+    #[test]
+    fn uncached_cid_private_dict() {
+        // Glyph N uses Font DICT N. Font DICTs past the cached ones have to be parsed on demand.
+        let number_of_glyphs = MAX_CACHED_PRIVATE_DICTS as u8 + 2;
+        let char_strings_offset = 40;
+        let fd_select_offset = char_strings_offset + 4 + 8 * u32::from(number_of_glyphs);
+        let fd_array_offset = fd_select_offset + 1 + u32::from(number_of_glyphs);
+        let font_dicts_data_offset = fd_array_offset + 3 + u32::from(number_of_glyphs) + 1;
+        // Private DICT plus its Local Subroutines INDEX.
+        let private_dict_len = 20;
+        let private_dicts_offset = font_dicts_data_offset + 11 * u32::from(number_of_glyphs);
+
+        let mut w = writer::Writer::new();
+        // Header
+        w.write(UInt8(1)); // major version
+        w.write(UInt8(0)); // minor version
+        w.write(UInt8(4)); // header size
+        w.write(UInt8(0)); // absolute offset
+
+        // Name INDEX
+        w.write(UInt16(0)); // count
+
+        // Top DICT INDEX
+        w.write(UInt16(1)); // count
+        w.write(UInt8(1)); // offset size
+        w.write(UInt8(1)); // index[0]
+        w.write(UInt8(26)); // index[1]
+        // Item 0
+        w.write(CFFInt(0)); w.write(CFFInt(0)); w.write(CFFInt(0));
+        w.write(UInt8(12)); w.write(UInt8((top_dict_operator::ROS - 1200) as u8));
+        w.write(UInt8(29)); w.write(Int32(char_strings_offset as i32)); // CharStrings offset
+        w.write(UInt8(top_dict_operator::CHAR_STRINGS_OFFSET as u8));
+        w.write(UInt8(29)); w.write(Int32(fd_array_offset as i32)); // FDArray offset
+        w.write(UInt8(12)); w.write(UInt8((top_dict_operator::FD_ARRAY - 1200) as u8));
+        w.write(UInt8(29)); w.write(Int32(fd_select_offset as i32)); // FDSelect offset
+        w.write(UInt8(12)); w.write(UInt8((top_dict_operator::FD_SELECT - 1200) as u8));
+
+        // String INDEX
+        w.write(UInt16(0)); // count
+
+        // Global Subroutines INDEX
+        w.write(UInt16(0)); // count
+
+        // CharStrings INDEX
+        assert_eq!(w.offset(), char_strings_offset as usize);
+        w.write(UInt16(u16::from(number_of_glyphs))); // count
+        w.write(UInt8(1)); // offset size
+        for i in 0..=number_of_glyphs {
+            w.write(UInt8(1 + i * 7)); // index[i]
+        }
+        for _ in 0..number_of_glyphs {
+            w.write(CFFInt(0 - 107)); // subroutine 0
+            w.write(UInt8(operator::CALL_LOCAL_SUBROUTINE));
+            w.write(UInt8(operator::MOVE_TO));
+            w.write(CFFInt(10)); w.write(CFFInt(10));
+            w.write(UInt8(operator::LINE_TO));
+            w.write(UInt8(operator::ENDCHAR));
+        }
+
+        // FDSelect
+        assert_eq!(w.offset(), fd_select_offset as usize);
+        w.write(UInt8(0)); // format
+        for i in 0..number_of_glyphs {
+            w.write(UInt8(i)); // fd
+        }
+
+        // FDArray INDEX
+        assert_eq!(w.offset(), fd_array_offset as usize);
+        w.write(UInt16(u16::from(number_of_glyphs))); // count
+        w.write(UInt8(1)); // offset size
+        for i in 0..=number_of_glyphs {
+            w.write(UInt8(1 + i * 11)); // index[i]
+        }
+        for i in 0..u32::from(number_of_glyphs) {
+            w.write(UInt8(29)); w.write(Int32(12)); // Private DICT size
+            w.write(UInt8(29)); w.write(Int32((private_dicts_offset + i * private_dict_len) as i32)); // Private DICT offset
+            w.write(UInt8(top_dict_operator::PRIVATE_DICT_SIZE_AND_OFFSET as u8));
+        }
+
+        // Private DICTs, each with its own default width
+        // and a single local subroutine that pushes the glyph index.
+        assert_eq!(w.offset(), private_dicts_offset as usize);
+        for i in 0..i32::from(number_of_glyphs) {
+            w.write(UInt8(29)); w.write(Int32(i * 100)); // Default width
+            w.write(UInt8(private_dict_operator::DEFAULT_WIDTH_X as u8));
+            w.write(UInt8(29)); w.write(Int32(12)); // Local Subroutines offset
+            w.write(UInt8(private_dict_operator::LOCAL_SUBROUTINES_OFFSET as u8));
+            // Local Subroutines INDEX
+            w.write(UInt16(1)); // count
+            w.write(UInt8(1)); // offset size
+            w.write(UInt8(1)); // index[0]
+            w.write(UInt8(4)); // index[1]
+            w.write(CFFInt(i)); w.write(CFFInt(10));
+            w.write(UInt8(operator::RETURN));
+        }
+
+        let metadata = parse_metadata(&w.data).unwrap();
+        for i in 0..u16::from(number_of_glyphs) {
+            let mut builder = Builder(String::new());
+            outline(&metadata, GlyphId(i), &mut builder).unwrap();
+            assert_eq!(builder.0, format!("M {} 10 L {} 20 Z ", i, i + 10));
+            assert_eq!(glyph_width(&metadata, GlyphId(i)), Some(f32::from(i) * 100.0));
+            assert_eq!(private_dict(&metadata, GlyphId(i)).unwrap().default_width_x, f32::from(i) * 100.0);
+        }
+    }
+
+    #[test]
+    fn seac() {
+        let data = writer::convert(&[
+            // Header
+            UInt8(1), // major version
+            UInt8(0), // minor version
+            UInt8(4), // header size
+            UInt8(0), // absolute offset
+            // Name INDEX
+            UInt16(0), // count
+            // Top DICT INDEX
+            UInt16(1), // count
+            UInt8(1), // offset size
+            UInt8(1), // index[0]
+            UInt8(5), // index[1]
+            // Item 0
+            CFFInt(19),
+            UInt8(top_dict_operator::CHAR_STRINGS_OFFSET as u8),
+            CFFInt(49),
+            UInt8(top_dict_operator::CHARSET_OFFSET as u8),
+            // String INDEX
+            UInt16(0), // count
+            // Global Subroutines INDEX
+            UInt16(0), // count
+            // CharString INDEX (offset 19)
+            UInt16(4), // count
+            UInt8(1), // offset size
+            UInt8(1), // index[0]
+            UInt8(2), // index[1]
+            UInt8(9), // index[2]
+            UInt8(16), // index[3]
+            UInt8(23), // index[4]
+            // Glyph 0 - .notdef
+            UInt8(operator::ENDCHAR),
+            // Glyph 1 - a
+            CFFInt(10), CFFInt(20), UInt8(operator::MOVE_TO),
+            CFFInt(30), CFFInt(40), UInt8(operator::LINE_TO),
+            UInt8(operator::ENDCHAR),
+            // Glyph 2 - acute
+            CFFInt(1), CFFInt(2), UInt8(operator::MOVE_TO),
+            CFFInt(3), CFFInt(4), UInt8(operator::LINE_TO),
+            UInt8(operator::ENDCHAR),
+            // Glyph 3 - aacute
+            CFFInt(50), // width
+            CFFInt(5), // adx
+            CFFInt(6), // ady
+            CFFInt(97), // bchar - a
+            CFFInt(194), // achar - acute
+            UInt8(operator::ENDCHAR),
+            // Charset (offset 49)
+            UInt8(0), // format
+            UInt16(66), // SID of glyph 1 - a
+            UInt16(125), // SID of glyph 2 - acute
+            UInt16(200), // SID of glyph 3 - aacute
+        ]);
+
+        let metadata = parse_metadata(&data).unwrap();
+        let mut builder = Builder(String::new());
+        let bbox = outline(&metadata, GlyphId(3), &mut builder).unwrap();
+        assert_eq!(builder.0, "M 10 20 L 40 60 Z M 6 8 L 9 12 Z ");
+        assert_eq!(bbox, rect(6, 8, 40, 60));
+    }
+
+    test_cs_err!(seac_missing_glyph, &[
+        CFFInt(5), CFFInt(6), CFFInt(97), CFFInt(194), UInt8(operator::ENDCHAR),
+    ], "invalid seac code");
+
+    #[test]
+    fn charset_format_1() {
+        let data = writer::convert(&[
+            UInt8(1), // format
+            // Range 0
+            UInt16(10), // first SID
+            UInt8(2), // left
+            // Range 1
+            UInt16(100), // first SID
+            UInt8(0), // left
+        ]);
+
+        let charset = parse_charset(5, &mut Stream::new(&data)).unwrap();
+        assert_eq!(charset.sid_to_gid(StringId(0), 5), Some(GlyphId(0)));
+        assert_eq!(charset.sid_to_gid(StringId(10), 5), Some(GlyphId(1)));
+        assert_eq!(charset.sid_to_gid(StringId(12), 5), Some(GlyphId(3)));
+        assert_eq!(charset.sid_to_gid(StringId(100), 5), Some(GlyphId(4)));
+        assert_eq!(charset.sid_to_gid(StringId(13), 5), None);
+
+        assert_eq!(charset.gid_to_sid(GlyphId(0), 5), Some(StringId(0)));
+        assert_eq!(charset.gid_to_sid(GlyphId(3), 5), Some(StringId(12)));
+        assert_eq!(charset.gid_to_sid(GlyphId(4), 5), Some(StringId(100)));
+        assert_eq!(charset.gid_to_sid(GlyphId(5), 5), None);
+    }
+
+    #[test]
+    fn charset_format_2() {
+        let data = writer::convert(&[
+            UInt8(2), // format
+            // Range 0
+            UInt16(400), // first SID
+            UInt16(299), // left
+        ]);
+
+        let charset = parse_charset(301, &mut Stream::new(&data)).unwrap();
+        assert_eq!(charset.sid_to_gid(StringId(400), 301), Some(GlyphId(1)));
+        assert_eq!(charset.sid_to_gid(StringId(699), 301), Some(GlyphId(300)));
+        assert_eq!(charset.sid_to_gid(StringId(700), 301), None);
+    }
+
+    #[test]
+    fn glyph_names_and_encoding() {
+        let data = writer::convert(&[
+            // Header
+            UInt8(1), // major version
+            UInt8(0), // minor version
+            UInt8(4), // header size
+            UInt8(0), // absolute offset
+            // Name INDEX
+            UInt16(0), // count
+            // Top DICT INDEX
+            UInt16(1), // count
+            UInt8(1), // offset size
+            UInt8(1), // index[0]
+            UInt8(7), // index[1]
+            // Item 0
+            CFFInt(30),
+            UInt8(top_dict_operator::CHAR_STRINGS_OFFSET as u8),
+            CFFInt(40),
+            UInt8(top_dict_operator::CHARSET_OFFSET as u8),
+            CFFInt(45),
+            UInt8(top_dict_operator::ENCODING_OFFSET as u8),
+            // String INDEX
+            UInt16(1), // count
+            UInt8(1), // offset size
+            UInt8(1), // index[0]
+            UInt8(7), // index[1]
+            Raw(b"custom"),
+            // Global Subroutines INDEX
+            UInt16(0), // count
+            // CharString INDEX (offset 30)
+            UInt16(3), // count
+            UInt8(1), // offset size
+            UInt8(1), // index[0]
+            UInt8(2), // index[1]
+            UInt8(3), // index[2]
+            UInt8(4), // index[3]
+            UInt8(operator::ENDCHAR),
+            UInt8(operator::ENDCHAR),
+            UInt8(operator::ENDCHAR),
+            // Charset (offset 40)
+            UInt8(0), // format
+            UInt16(66), // SID of glyph 1 - a
+            UInt16(391), // SID of glyph 2 - custom
+            // Encoding (offset 45)
+            UInt8(0x80), // format 0 with supplements
+            UInt8(2), // number of codes
+            UInt8(b'a'), // glyph 1
+            UInt8(b'A'), // glyph 2
+            UInt8(1), // number of supplements
+            UInt8(b'b'), // code
+            UInt16(391), // SID
+        ]);
+
+        let metadata = parse_metadata(&data).unwrap();
+        assert_eq!(glyph_name(&metadata, GlyphId(0)), Some(".notdef"));
+        assert_eq!(glyph_name(&metadata, GlyphId(1)), Some("a"));
+        assert_eq!(glyph_name(&metadata, GlyphId(2)), Some("custom"));
+        assert_eq!(glyph_name(&metadata, GlyphId(3)), None);
+
+        assert_eq!(glyph_index(&metadata, b'a'), Some(GlyphId(1)));
+        assert_eq!(glyph_index(&metadata, b'A'), Some(GlyphId(2)));
+        assert_eq!(glyph_index(&metadata, b'b'), Some(GlyphId(2)));
+        assert_eq!(glyph_index(&metadata, b'c'), None);
+    }
+
+    #[test]
+    fn malformed_charset() {
+        let data = writer::convert(&[
+            // Header
+            UInt8(1), // major version
+            UInt8(0), // minor version
+            UInt8(4), // header size
+            UInt8(0), // absolute offset
+            // Name INDEX
+            UInt16(0), // count
+            // Top DICT INDEX
+            UInt16(1), // count
+            UInt8(1), // offset size
+            UInt8(1), // index[0]
+            UInt8(5), // index[1]
+            // Item 0
+            CFFInt(19),
+            UInt8(top_dict_operator::CHAR_STRINGS_OFFSET as u8),
+            CFFInt(35),
+            UInt8(top_dict_operator::CHARSET_OFFSET as u8),
+            // String INDEX
+            UInt16(0), // count
+            // Global Subroutines INDEX
+            UInt16(0), // count
+            // CharString INDEX (offset 19)
+            UInt16(3), // count
+            UInt8(1), // offset size
+            UInt8(1), // index[0]
+            UInt8(8), // index[1]
+            UInt8(9), // index[2]
+            UInt8(10), // index[3]
+            CFFInt(10),
+            CFFInt(20),
+            UInt8(operator::MOVE_TO),
+            CFFInt(30),
+            CFFInt(40),
+            UInt8(operator::LINE_TO),
+            UInt8(operator::ENDCHAR),
+            UInt8(operator::ENDCHAR),
+            UInt8(operator::ENDCHAR),
+            // Charset (offset 35)
+            UInt8(3), // format
+        ]);
+
+        // An unsupported charset format should not affect outlining,
+        // but glyph names are no longer available.
+        let metadata = parse_metadata(&data).unwrap();
+        assert_eq!(glyph_name(&metadata, GlyphId(1)), None);
+        assert_eq!(glyph_index(&metadata, b' '), None);
+
+        let mut builder = Builder(String::new());
+        outline(&metadata, GlyphId(0), &mut builder).unwrap();
+        assert_eq!(builder.0, "M 10 20 L 40 60 Z ");
+    }
+
+    #[test]
+    fn standard_encoding() {
+        let data = gen_cff(&[], &[], &[UInt8(operator::ENDCHAR)]);
+        let metadata = parse_metadata(&data).unwrap();
+        // Only .notdef is present.
+        assert_eq!(glyph_index(&metadata, b'a'), None);
+        assert_eq!(glyph_name(&metadata, GlyphId(0)), Some(".notdef"));
+    }
+
+    #[test]
+    fn malformed_encoding() {
+        let data = writer::convert(&[
+            // Header
+            UInt8(1), // major version
+            UInt8(0), // minor version
+            UInt8(4), // header size
+            UInt8(0), // absolute offset
+            // Name INDEX
+            UInt16(0), // count
+            // Top DICT INDEX
+            UInt16(1), // count
+            UInt8(1), // offset size
+            UInt8(1), // index[0]
+            UInt8(5), // index[1]
+            // Item 0
+            CFFInt(19),
+            UInt8(top_dict_operator::CHAR_STRINGS_OFFSET as u8),
+            CFFInt(35),
+            UInt8(top_dict_operator::ENCODING_OFFSET as u8),
+            // String INDEX
+            UInt16(0), // count
+            // Global Subroutines INDEX
+            UInt16(0), // count
+            // CharString INDEX (offset 19)
+            UInt16(3), // count
+            UInt8(1), // offset size
+            UInt8(1), // index[0]
+            UInt8(8), // index[1]
+            UInt8(9), // index[2]
+            UInt8(10), // index[3]
+            CFFInt(10),
+            CFFInt(20),
+            UInt8(operator::MOVE_TO),
+            CFFInt(30),
+            CFFInt(40),
+            UInt8(operator::LINE_TO),
+            UInt8(operator::ENDCHAR),
+            UInt8(operator::ENDCHAR),
+            UInt8(operator::ENDCHAR),
+            // Encoding (offset 35)
+            UInt8(5), // format
+        ]);
+
+        // An unsupported encoding format should not affect outlining,
+        // but codes can no longer be resolved.
+        let metadata = parse_metadata(&data).unwrap();
+        assert_eq!(glyph_index(&metadata, b' '), None);
+        assert_eq!(glyph_name(&metadata, GlyphId(1)), Some("space"));
+
+        let mut builder = Builder(String::new());
+        outline(&metadata, GlyphId(0), &mut builder).unwrap();
+        assert_eq!(builder.0, "M 10 20 L 40 60 Z ");
+
+        // Truncated.
+        let mut data = data;
+        data.truncate(35);
+        data.push(0x80);
+        assert!(parse_metadata(&data).is_some());
+    }
+
+    #[test]
+    fn encoding_format_1() {
+        let data = writer::convert(&[
+            UInt8(1), // format
+            UInt8(2), // number of ranges
+            // Range 0
+            UInt8(b'a'), // first code
+            UInt8(2), // left
+            // Range 1
+            UInt8(b'x'), // first code
+            UInt8(0), // left
+        ]);
+
+        let encoding = parse_encoding(&mut Stream::new(&data)).unwrap();
+        let charset = Charset::ISOAdobe;
+        assert_eq!(encoding.code_to_gid(&charset, 5, b'a'), Some(GlyphId(1)));
+        assert_eq!(encoding.code_to_gid(&charset, 5, b'c'), Some(GlyphId(3)));
+        assert_eq!(encoding.code_to_gid(&charset, 5, b'x'), Some(GlyphId(4)));
+        assert_eq!(encoding.code_to_gid(&charset, 5, b'd'), None);
+    }
+
+    #[test]
+    fn parse_dict_real_number() {
+        fn parse(data: &[u8]) -> Option<f64> {
+            let mut s = Stream::new(data);
+            let b0: u8 = s.read().unwrap();
+            super::parse_dict_number(b0, &mut s)
+        }
+
+        assert_eq!(parse(&[30, 0xe2, 0xa2, 0x5f]), Some(-2.25));
+        assert_eq!(parse(&[30, 0x0a, 0x00, 0x1f]), Some(0.001));
+        assert_eq!(parse(&[30, 0x1a, 0x14, 0x05, 0x41, 0xc3, 0xff]), Some(0.001140541));
+        assert_eq!(parse(&[30, 0x2b, 0x3f]), Some(2000.0));
+        assert_eq!(parse(&[30, 0x1d, 0xff]), None);
+        assert_eq!(parse(&[30, 0x11]), None);
+    }
+
+    #[test]
+    fn top_and_private_dicts() {
+        let data = writer::convert(&[
+            // Header
+            UInt8(1), // major version
+            UInt8(0), // minor version
+            UInt8(4), // header size
+            UInt8(0), // absolute offset
+            // Name INDEX
+            UInt16(0), // count
+            // Top DICT INDEX
+            UInt16(1), // count
+            UInt8(1), // offset size
+            UInt8(1), // index[0]
+            UInt8(42), // index[1]
+            // Item 0
+            CFFInt(384), // Bold
+            UInt8(top_dict_operator::WEIGHT as u8),
+            UInt8(30), Raw(&[0x0a, 0x00, 0x05, 0xff]), // 0.0005
+            CFFInt(0),
+            CFFInt(0),
+            UInt8(30), Raw(&[0x0a, 0x00, 0x05, 0xff]), // 0.0005
+            CFFInt(0),
+            CFFInt(0),
+            UInt8(12), UInt8((top_dict_operator::FONT_MATRIX - 1200) as u8),
+            UInt8(30), Raw(&[0xe1, 0x2a, 0x5f]), // -12.5
+            UInt8(12), UInt8((top_dict_operator::ITALIC_ANGLE - 1200) as u8),
+            CFFInt(1),
+            UInt8(12), UInt8((top_dict_operator::IS_FIXED_PITCH - 1200) as u8),
+            CFFInt(-50), CFFInt(-200), CFFInt(1000), CFFInt(900),
+            UInt8(top_dict_operator::FONT_BBOX as u8),
+            CFFInt(56),
+            UInt8(top_dict_operator::CHAR_STRINGS_OFFSET as u8),
+            CFFInt(17), // length
+            CFFInt(62), // offset
+            UInt8(top_dict_operator::PRIVATE_DICT_SIZE_AND_OFFSET as u8),
+            // String INDEX
+            UInt16(0), // count
+            // Global Subroutines INDEX
+            UInt16(0), // count
+            // CharString INDEX (offset 56)
+            UInt16(1), // count
+            UInt8(1), // offset size
+            UInt8(1), // index[0]
+            UInt8(2), // index[1]
+            UInt8(operator::ENDCHAR),
+            // Private DICT (offset 62)
+            CFFInt(-15), CFFInt(15), CFFInt(485), CFFInt(15),
+            UInt8(private_dict_operator::BLUE_VALUES as u8),
+            CFFInt(80),
+            UInt8(private_dict_operator::STD_VW as u8),
+            CFFInt(500),
+            UInt8(private_dict_operator::NOMINAL_WIDTH_X as u8),
+            UInt8(30), Raw(&[0x0a, 0x05, 0xff]), // 0.05
+            UInt8(12), UInt8((private_dict_operator::BLUE_SCALE - 1200) as u8),
+        ]);
+
+        let metadata = parse_metadata(&data).unwrap();
+
+        let dict = top_dict(&metadata);
+        assert_eq!(dict.weight, Some("Bold"));
+        assert_eq!(dict.full_name, None);
+        assert_eq!(dict.font_matrix.sx, 0.0005);
+        assert_eq!(dict.font_matrix.sy, 0.0005);
+        assert_eq!(dict.font_matrix.kx, 0.0);
+        assert_eq!(dict.italic_angle, -12.5);
+        assert!(dict.is_fixed_pitch);
+        assert_eq!(dict.font_bbox, rect(-50, -200, 1000, 900));
+        assert_eq!(dict.underline_position, -100.0);
+
+        let dict = private_dict(&metadata, GlyphId(0)).unwrap();
+        assert_eq!(dict.blue_values.as_slice(), &[
+            BlueZone { bottom: -15.0, top: 0.0 },
+            BlueZone { bottom: 485.0, top: 500.0 },
+        ]);
+        assert!(dict.other_blues.as_slice().is_empty());
+        assert_eq!(dict.std_hw, None);
+        assert_eq!(dict.std_vw, Some(80.0));
+        assert_eq!(dict.nominal_width_x, 500.0);
+        assert_eq!(dict.default_width_x, 0.0);
+        assert_eq!(dict.blue_scale, 0.05);
+        assert_eq!(dict.blue_shift, 7.0);
+    }
+
+    macro_rules! test_cs_hints {
+        ($name:ident, $values:expr, $path:expr) => {
+            #[test]
+            fn $name() {
+                let data = gen_cff(&[], &[], $values);
+                let metadata = parse_metadata(&data).unwrap();
+                let mut builder = HintsBuilder(String::new());
+                outline_with_hints(&metadata, GlyphId(0), &mut builder).unwrap();
+                assert_eq!(builder.0, $path);
+            }
+        };
+    }
+
+    test_cs_hints!(hints_stems, &[
+        CFFInt(5), // width
+        CFFInt(10), CFFInt(20), CFFInt(30), CFFInt(40),
+        UInt8(operator::HORIZONTAL_STEM),
+        CFFInt(15), CFFInt(25),
+        UInt8(operator::VERTICAL_STEM),
+        CFFInt(10), CFFInt(20), UInt8(operator::MOVE_TO),
+        CFFInt(30), CFFInt(40), UInt8(operator::LINE_TO),
+        UInt8(operator::ENDCHAR),
+    ], "HS 10 20 HS 60 40 VS 15 25 M 10 20 L 40 60 Z ");
+
+    test_cs_hints!(hints_masks, &[
+        CFFInt(10), CFFInt(20), CFFInt(30), CFFInt(40),
+        UInt8(operator::HORIZONTAL_STEM_HINT_MASK),
+        CFFInt(15), CFFInt(25),
+        UInt8(operator::HINT_MASK), UInt8(0b1010_0000),
+        CFFInt(10), CFFInt(20), UInt8(operator::MOVE_TO),
+        UInt8(operator::COUNTER_MASK), UInt8(0b1110_0000),
+        CFFInt(30), CFFInt(40), UInt8(operator::LINE_TO),
+        UInt8(operator::ENDCHAR),
+    ], "HS 10 20 HS 60 40 VS 15 25 HM [160] M 10 20 CM [224] L 40 60 Z ");
+
+    test_cs_err!(hints_mask_out_of_bounds, &[
+        CFFInt(10), CFFInt(20),
+        UInt8(operator::HORIZONTAL_STEM_HINT_MASK),
+        UInt8(operator::HINT_MASK),
+    ], "read out of bounds");
+
     // TODO: return from main
     // TODO: return without endchar
     // TODO: data after return
@@ -2502,4 +4954,72 @@ mod tests {
         assert_eq!(parse_number(0x1D, &mut Stream::new(&[0x00, 0x01, 0x86, 0xA0])).unwrap(), 100000);
         assert_eq!(parse_number(0x1D, &mut Stream::new(&[0xFF, 0xFE, 0x79, 0x60])).unwrap(), -100000);
     }
+
+    #[test]
+    fn glyph_width_from_char_string() {
+        let data = gen_cff(&[], &[], &[
+            CFFInt(500),
+            CFFInt(10),
+            UInt8(operator::HORIZONTAL_MOVE_TO),
+            UInt8(operator::ENDCHAR),
+        ]);
+
+        let metadata = parse_metadata(&data).unwrap();
+        // nominalWidthX is 0 by default.
+        assert_eq!(glyph_width(&metadata, GlyphId(0)), Some(500.0));
+    }
+
+    #[test]
+    fn width_rounding() {
+        assert_eq!(round_width(0.0), Some(0));
+        assert_eq!(round_width(0.4), Some(0));
+        assert_eq!(round_width(0.5), Some(1));
+        assert_eq!(round_width(499.6), Some(500));
+        assert_eq!(round_width(-0.4), Some(0));
+        assert_eq!(round_width(-0.6), None);
+        assert_eq!(round_width(65535.4), Some(65535));
+        assert_eq!(round_width(65535.5), None);
+    }
+
+    #[test]
+    fn glyph_width_default() {
+        let data = gen_cff(&[], &[], &[
+            CFFInt(10),
+            UInt8(operator::HORIZONTAL_MOVE_TO),
+            UInt8(operator::ENDCHAR),
+        ]);
+
+        let metadata = parse_metadata(&data).unwrap();
+        // defaultWidthX is 0 by default.
+        assert_eq!(glyph_width(&metadata, GlyphId(0)), Some(0.0));
+    }
+
+    #[test]
+    fn standalone_font() {
+        let data = gen_cff(&[], &[], &[
+            CFFInt(500),
+            CFFInt(10),
+            CFFInt(20),
+            UInt8(operator::MOVE_TO),
+            CFFInt(30),
+            CFFInt(40),
+            UInt8(operator::LINE_TO),
+            UInt8(operator::ENDCHAR),
+        ]);
+
+        let font = crate::CFFFont::from_data(&data).unwrap();
+        assert!(!font.is_cff2());
+        assert_eq!(font.number_of_glyphs(), 1);
+        assert_eq!(font.glyph_hor_advance(GlyphId(0)), Some(500));
+        assert_eq!(font.font_matrix(), FontMatrix::default());
+
+        let mut builder = Builder(String::new());
+        let bbox = font.outline_glyph(GlyphId(0), &mut builder).unwrap();
+        assert_eq!(builder.0, "M 10 20 L 40 60 Z ");
+        assert_eq!(bbox, rect(10, 20, 40, 60));
+
+        assert!(font.outline_glyph(GlyphId(1), &mut builder).is_none());
+        assert!(crate::CFFFont::from_data(&[]).is_none());
+        assert!(crate::CFFFont::from_data(&[3, 0, 4, 1]).is_none());
+    }
 }
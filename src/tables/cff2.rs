@@ -4,11 +4,11 @@
 use core::convert::TryFrom;
 use core::ops::Range;
 
-use crate::{GlyphId, OutlineBuilder, Rect, BBox, NormalizedCoord};
+use crate::{GlyphId, OutlineBuilder, HintedOutlineBuilder, UnhintedOutlineBuilder, Rect, BBox, NormalizedCoord};
 use crate::parser::{Stream, Fixed, NumFrom, TryNumFrom};
 use crate::var_store::*;
 use crate::cff::{
-    Builder, DataIndex, IsEven, Operator, ArgumentsStack, CFFError,
+    Builder, DataIndex, FDSelect, IsEven, Operator, ArgumentsStack, CFFError,
     calc_subroutine_bias, f32_abs, parse_number, skip_number, parse_index_impl,
     is_dict_one_byte_op, conv_subroutine_index
 };
@@ -23,6 +23,10 @@ const MAX_ARGUMENTS_STACK_LEN: usize = 513;
 
 const TWO_BYTE_OPERATOR_MARK: u8 = 12;
 
+// The number of Font DICTs whose Private DICTs are parsed in advance.
+// Most fonts have just a few of them. The rest are parsed on demand.
+const MAX_CACHED_PRIVATE_DICTS: usize = 4;
+
 // https://docs.microsoft.com/en-us/typography/opentype/spec/cff2charstr#4-charstring-operators
 mod operator {
     pub const HORIZONTAL_STEM: u8           = 1;
@@ -61,6 +65,7 @@ mod top_dict_operator {
     pub const CHAR_STRINGS_OFFSET: u16      = 17;
     pub const VARIATION_STORE_OFFSET: u16   = 24;
     pub const FONT_DICT_INDEX_OFFSET: u16   = 1236;
+    pub const FONT_DICT_SELECT_OFFSET: u16  = 1237;
 }
 
 // https://docs.microsoft.com/en-us/typography/opentype/spec/cff2#table-10-font-dict-operator-entries
@@ -71,14 +76,22 @@ mod font_dict_operator {
 // https://docs.microsoft.com/en-us/typography/opentype/spec/cff2#table-16-private-dict-operators
 mod private_dict_operator {
     pub const LOCAL_SUBROUTINES_OFFSET: u16 = 19;
+    pub const VARIATION_STORE_INDEX: u16    = 22;
 }
 
 
 #[derive(Clone, Copy, Default)]
 pub struct Metadata<'a> {
+    // The whole CFF2 table.
+    // Used to resolve Private DICTs and local subroutines.
+    table_data: &'a [u8],
     global_subrs: DataIndex<'a>,
-    local_subrs: DataIndex<'a>,
     char_strings: DataIndex<'a>,
+    font_dicts: DataIndex<'a>,
+    // 'FDSelect is only present if FDArray contains more than one Font DICT.'
+    fd_select: Option<FDSelect<'a>>,
+    // Private DICTs of the first `MAX_CACHED_PRIVATE_DICTS` Font DICTs.
+    private_dicts: [PrivateDict<'a>; MAX_CACHED_PRIVATE_DICTS],
     item_variation_store: ItemVariationStore<'a>,
 }
 
@@ -104,6 +117,7 @@ pub(crate) fn parse_metadata(data: &[u8]) -> Option<Metadata> {
     let top_dict = parse_top_dict(top_dict_data)?;
 
     let mut metadata = Metadata::default();
+    metadata.table_data = data;
 
     // Parse Global Subroutines INDEX.
     metadata.global_subrs = parse_index(&mut s)?;
@@ -119,45 +133,107 @@ pub(crate) fn parse_metadata(data: &[u8]) -> Option<Metadata> {
         metadata.item_variation_store = ItemVariationStore::parse(s)?;
     }
 
-    // TODO: simplify
     if let Some(offset) = top_dict.font_dict_index_offset {
         let mut s = Stream::new_at(data, offset)?;
-        'outer: for font_dict_data in parse_index(&mut s)? {
-            if let Some(private_dict_range) = parse_font_dict(font_dict_data) {
-                // 'Private DICT size and offset, from start of the CFF2 table.'
-                let private_dict_data = data.get(private_dict_range.clone())?;
-                if let Some(subroutines_offset) = parse_private_dict(private_dict_data) {
-                    // 'The local subroutines offset is relative to the beginning
-                    // of the Private DICT data.'
-                    if let Some(start) = private_dict_range.start.checked_add(subroutines_offset) {
-                        let data = data.get(start..data.len())?;
-                        let mut s = Stream::new(data);
-                        metadata.local_subrs = parse_index(&mut s)?;
-                        break 'outer;
-                    }
-                }
-            }
-        }
+        metadata.font_dicts = parse_index(&mut s)?;
+    }
+
+    let cached_len = metadata.font_dicts.len().min(MAX_CACHED_PRIVATE_DICTS as u16);
+    for font_dict_index in 0..cached_len {
+        // A malformed Private DICT is treated as an empty one, like during outlining.
+        let private_dict = parse_font_private_dict(&metadata, font_dict_index).unwrap_or_default();
+        metadata.private_dicts[usize::from(font_dict_index)] = private_dict;
+    }
+
+    if let Some(offset) = top_dict.font_dict_select_offset {
+        let mut s = Stream::new_at(data, offset)?;
+        metadata.fd_select = Some(parse_fd_select(metadata.char_strings.len(), &mut s)?);
     }
 
     Some(metadata)
 }
 
+fn parse_fd_select<'a>(number_of_glyphs: u16, s: &mut Stream<'a>) -> Option<FDSelect<'a>> {
+    let format: u8 = s.read()?;
+    match format {
+        0 => Some(FDSelect::Format0(s.read_array16(number_of_glyphs)?)),
+        3 => Some(FDSelect::Format3(s.tail()?)),
+        4 => Some(FDSelect::Format4(s.tail()?)),
+        _ => None,
+    }
+}
+
+// Each glyph references a Font DICT via FDSelect,
+// which in turn references a Private DICT with its own local subroutines
+// and a default variation store index.
+fn glyph_private_dict<'a>(metadata: &Metadata<'a>, glyph_id: GlyphId) -> Option<PrivateDict<'a>> {
+    let font_dict_index = match metadata.fd_select {
+        Some(ref fd_select) => fd_select.font_dict_index(glyph_id)?,
+        None => 0,
+    };
+
+    if font_dict_index < metadata.font_dicts.len() {
+        if let Some(private_dict) = metadata.private_dicts.get(usize::from(font_dict_index)) {
+            return Some(*private_dict);
+        }
+    }
+
+    parse_font_private_dict(metadata, font_dict_index)
+}
+
+fn parse_font_private_dict<'a>(metadata: &Metadata<'a>, font_dict_index: u16) -> Option<PrivateDict<'a>> {
+    let font_dict_data = metadata.font_dicts.get(font_dict_index)?;
+    let private_dict_range = parse_font_dict(font_dict_data)?;
+    // 'Private DICT size and offset, from start of the CFF2 table.'
+    let private_dict_data = metadata.table_data.get(private_dict_range.clone())?;
+    let private_dict_data = parse_private_dict(private_dict_data)?;
+
+    let mut private_dict = PrivateDict {
+        local_subrs: None,
+        variation_store_index: private_dict_data.variation_store_index,
+    };
+
+    if let Some(subroutines_offset) = private_dict_data.local_subrs_offset {
+        // 'The local subroutines offset is relative to the beginning
+        // of the Private DICT data.'
+        let start = private_dict_range.start.checked_add(subroutines_offset)?;
+        let mut s = Stream::new_at(metadata.table_data, start)?;
+        private_dict.local_subrs = Some(parse_index(&mut s)?);
+    }
+
+    Some(private_dict)
+}
+
 
 pub(crate) fn outline(
     metadata: &Metadata,
     coordinates: &[NormalizedCoord],
     glyph_id: GlyphId,
     builder: &mut dyn OutlineBuilder,
+) -> Option<Rect> {
+    outline_with_hints(metadata, coordinates, glyph_id, &mut UnhintedOutlineBuilder(builder))
+}
+
+#[inline]
+pub(crate) fn number_of_glyphs(metadata: &Metadata) -> u16 {
+    metadata.char_strings.len()
+}
+
+pub(crate) fn outline_with_hints(
+    metadata: &Metadata,
+    coordinates: &[NormalizedCoord],
+    glyph_id: GlyphId,
+    builder: &mut dyn HintedOutlineBuilder,
 ) -> Option<Rect> {
     let data = metadata.char_strings.get(glyph_id.0)?;
-    parse_char_string(data, metadata, coordinates, builder).ok()
+    parse_char_string(data, metadata, coordinates, glyph_id, builder).ok()
 }
 
 #[derive(Clone, Copy, Default)]
 struct TopDictData {
     char_strings_offset: usize,
     font_dict_index_offset: Option<usize>,
+    font_dict_select_offset: Option<usize>,
     variation_store_offset: Option<usize>,
 }
 
@@ -181,6 +257,13 @@ fn parse_top_dict(data: &[u8]) -> Option<TopDictData> {
             if operands.len() == 1 {
                 dict_data.font_dict_index_offset = usize::try_from(operands[0]).ok();
             }
+        } else if operator.get() == top_dict_operator::FONT_DICT_SELECT_OFFSET {
+            dict_parser.parse_operands()?;
+            let operands = dict_parser.operands();
+
+            if operands.len() == 1 {
+                dict_data.font_dict_select_offset = usize::try_from(operands[0]).ok();
+            }
         } else if operator.get() == top_dict_operator::VARIATION_STORE_OFFSET {
             dict_parser.parse_operands()?;
             let operands = dict_parser.operands();
@@ -222,8 +305,21 @@ fn parse_font_dict(data: &[u8]) -> Option<Range<usize>> {
     private_dict_range
 }
 
-fn parse_private_dict(data: &[u8]) -> Option<usize> {
-    let mut subroutines_offset = None;
+#[derive(Clone, Copy, Default)]
+struct PrivateDictData {
+    local_subrs_offset: Option<usize>,
+    variation_store_index: u16,
+}
+
+// A Private DICT with resolved local subroutines.
+#[derive(Clone, Copy, Default)]
+struct PrivateDict<'a> {
+    local_subrs: Option<DataIndex<'a>>,
+    variation_store_index: u16,
+}
+
+fn parse_private_dict(data: &[u8]) -> Option<PrivateDictData> {
+    let mut dict = PrivateDictData::default();
     let mut dict_parser = DictionaryParser::new(data);
     while let Some(operator) = dict_parser.parse_next() {
         if operator.get() == private_dict_operator::LOCAL_SUBROUTINES_OFFSET {
@@ -231,14 +327,19 @@ fn parse_private_dict(data: &[u8]) -> Option<usize> {
             let operands = dict_parser.operands();
 
             if operands.len() == 1 {
-                subroutines_offset = usize::try_from(operands[0]).ok();
+                dict.local_subrs_offset = usize::try_from(operands[0]).ok();
             }
+        } else if operator.get() == private_dict_operator::VARIATION_STORE_INDEX {
+            dict_parser.parse_operands()?;
+            let operands = dict_parser.operands();
 
-            break;
+            if operands.len() == 1 {
+                dict.variation_store_index = u16::try_from(operands[0]).ok()?;
+            }
         }
     }
 
-    subroutines_offset
+    Some(dict)
 }
 
 /// CFF2 allows up to 65535 scalars, but an average font will have 3-5.
@@ -291,6 +392,7 @@ impl Scalars {
 struct CharStringParserContext<'a> {
     metadata: &'a Metadata<'a>,
     coordinates: &'a [NormalizedCoord],
+    local_subrs: Option<DataIndex<'a>>,
     is_first_move_to: bool,
     has_move_to: bool,
     scalars: Scalars,
@@ -320,11 +422,15 @@ fn parse_char_string(
     data: &[u8],
     metadata: &Metadata,
     coordinates: &[NormalizedCoord],
-    builder: &mut dyn OutlineBuilder,
+    glyph_id: GlyphId,
+    builder: &mut dyn HintedOutlineBuilder,
 ) -> Result<Rect, CFFError> {
+    let private_dict = glyph_private_dict(metadata, glyph_id).unwrap_or_default();
+
     let mut ctx = CharStringParserContext {
         metadata,
         coordinates,
+        local_subrs: private_dict.local_subrs,
         is_first_move_to: true,
         has_move_to: false,
         scalars: Scalars::default(),
@@ -333,8 +439,11 @@ fn parse_char_string(
         stems_len: 0,
     };
 
-    // Load scalars at default index.
-    ctx.update_scalars(0)?;
+    // Load scalars at default index, which can be overridden by the Private DICT.
+    // Non-variable fonts may not have a variation store at all.
+    if !metadata.item_variation_store.is_empty() {
+        ctx.update_scalars(private_dict.variation_store_index)?;
+    }
 
     let mut inner_builder = Builder {
         builder,
@@ -391,7 +500,10 @@ fn _parse_char_string(
 
                 ctx.stems_len += stack.len() as u32 >> 1;
 
-                // We are ignoring the hint operators.
+                let is_horizontal = op == operator::HORIZONTAL_STEM
+                    || op == operator::HORIZONTAL_STEM_HINT_MASK;
+                builder.stems(stack, 0, is_horizontal);
+
                 stack.clear();
             }
             operator::VERTICAL_MOVE_TO => {
@@ -528,9 +640,14 @@ fn _parse_char_string(
                     return Err(CFFError::NestingLimitReached);
                 }
 
-                let subroutine_bias = calc_subroutine_bias(ctx.metadata.local_subrs.len());
+                let local_subrs = match ctx.local_subrs {
+                    Some(v) => v,
+                    None => return Err(CFFError::NoLocalSubroutines),
+                };
+
+                let subroutine_bias = calc_subroutine_bias(local_subrs.len());
                 let index = conv_subroutine_index(stack.pop(), subroutine_bias)?;
-                let char_string = ctx.metadata.local_subrs.get(index)
+                let char_string = local_subrs.get(index)
                     .ok_or(CFFError::InvalidSubroutineIndex)?;
                 let pos = _parse_char_string(ctx, char_string, x, y, stack, depth + 1, builder)?;
                 x = pos.0;
@@ -706,11 +823,14 @@ fn _parse_char_string(
                 }
             }
             operator::HINT_MASK | operator::COUNTER_MASK => {
+                // Values on the stack are an implicit `vstem`.
                 ctx.stems_len += stack.len() as u32 >> 1;
-                s.advance(usize::num_from((ctx.stems_len + 7) >> 3));
-
-                // We are ignoring the hint operators.
+                builder.stems(stack, 0, false);
                 stack.clear();
+
+                let mask = s.read_bytes(usize::num_from((ctx.stems_len + 7) >> 3))
+                    .ok_or(CFFError::ReadOutOfBounds)?;
+                builder.mask(mask, op == operator::COUNTER_MASK);
             }
             operator::MOVE_TO => {
                 // dx1 dy1
@@ -1137,4 +1257,233 @@ mod tests {
 
         assert!(parse_index_impl(std::u32::MAX / 2, &mut Stream::new(&data)).is_none());
     }
+
+    struct Builder(std::string::String);
+    impl OutlineBuilder for Builder {
+        fn move_to(&mut self, x: f32, y: f32) {
+            self.0 += &format!("M {} {} ", x, y);
+        }
+
+        fn line_to(&mut self, x: f32, y: f32) {
+            self.0 += &format!("L {} {} ", x, y);
+        }
+
+        fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+            self.0 += &format!("Q {} {} {} {} ", x1, y1, x, y);
+        }
+
+        fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+            self.0 += &format!("C {} {} {} {} {} {} ", x1, y1, x2, y2, x, y);
+        }
+
+        fn close(&mut self) {
+            self.0 += "Z ";
+        }
+    }
+
+    #[test]
+    fn fd_select_format_4() {
+        let data = writer::convert(&[
+            UInt32(2), // number of ranges
+            UInt32(0), // range[0].first
+            UInt16(300), // range[0].fd
+            UInt32(5), // range[1].first
+            UInt16(1), // range[1].fd
+            UInt32(10), // sentinel
+        ]);
+
+        let fd_select = FDSelect::Format4(&data);
+        assert_eq!(fd_select.font_dict_index(GlyphId(0)), Some(300));
+        assert_eq!(fd_select.font_dict_index(GlyphId(4)), Some(300));
+        assert_eq!(fd_select.font_dict_index(GlyphId(5)), Some(1));
+        assert_eq!(fd_select.font_dict_index(GlyphId(9)), Some(1));
+        assert_eq!(fd_select.font_dict_index(GlyphId(10)), None);
+    }
+
+    #[test]
+    fn per_glyph_local_subrs() {
+        let data = writer::convert(&[
+            // Header
+            UInt8(2), // major version
+            UInt8(0), // minor version
+            UInt8(5), // header size
+            UInt16(20), // Top DICT length
+
+            // Top DICT
+            UInt8(29), Int32(29), // CharStrings offset
+            UInt8(top_dict_operator::CHAR_STRINGS_OFFSET as u8),
+            UInt8(29), Int32(55), // FDArray offset
+            UInt8(12), UInt8(36),
+            UInt8(29), Int32(44), // FDSelect offset
+            UInt8(12), UInt8(37),
+
+            // Global Subroutines INDEX
+            UInt32(0), // count
+
+            // CharStrings INDEX (offset 29)
+            UInt32(3), // count
+            UInt8(1), // offset size
+            UInt8(1), // index[0]
+            UInt8(3), // index[1]
+            UInt8(5), // index[2]
+            UInt8(7), // index[3]
+            CFFInt(0 - 107), // subroutine 0
+            UInt8(operator::CALL_LOCAL_SUBROUTINE),
+            CFFInt(0 - 107), // subroutine 0
+            UInt8(operator::CALL_LOCAL_SUBROUTINE),
+            CFFInt(0 - 107), // subroutine 0
+            UInt8(operator::CALL_LOCAL_SUBROUTINE),
+
+            // FDSelect (offset 44)
+            UInt8(3), // format
+            UInt16(2), // number of ranges
+            UInt16(0), // range[0].first glyph
+            UInt8(1), // range[0].fd
+            UInt16(1), // range[1].first glyph
+            UInt8(0), // range[1].fd
+            UInt16(2), // sentinel, so glyph 2 is not covered
+
+            // FDArray INDEX (offset 55)
+            UInt32(2), // count
+            UInt8(1), // offset size
+            UInt8(1), // index[0]
+            UInt8(12), // index[1]
+            UInt8(23), // index[2]
+            // Font DICT 0
+            UInt8(29), Int32(6), // Private DICT size
+            UInt8(29), Int32(85), // Private DICT offset
+            UInt8(font_dict_operator::PRIVATE_DICT_SIZE_AND_OFFSET as u8),
+            // Font DICT 1
+            UInt8(29), Int32(6), // Private DICT size
+            UInt8(29), Int32(104), // Private DICT offset
+            UInt8(font_dict_operator::PRIVATE_DICT_SIZE_AND_OFFSET as u8),
+
+            // Private DICT 0 (offset 85)
+            UInt8(29), Int32(6), // Local Subroutines offset
+            UInt8(private_dict_operator::LOCAL_SUBROUTINES_OFFSET as u8),
+            // Local Subroutines INDEX
+            UInt32(1), // count
+            UInt8(1), // offset size
+            UInt8(1), // index[0]
+            UInt8(7), // index[1]
+            CFFInt(10),
+            CFFInt(20),
+            UInt8(operator::MOVE_TO),
+            CFFInt(30),
+            CFFInt(40),
+            UInt8(operator::LINE_TO),
+
+            // Private DICT 1 (offset 104)
+            UInt8(29), Int32(6), // Local Subroutines offset
+            UInt8(private_dict_operator::LOCAL_SUBROUTINES_OFFSET as u8),
+            // Local Subroutines INDEX
+            UInt32(1), // count
+            UInt8(1), // offset size
+            UInt8(1), // index[0]
+            UInt8(6), // index[1]
+            CFFInt(50),
+            CFFInt(60),
+            UInt8(operator::MOVE_TO),
+            CFFInt(5),
+            UInt8(operator::HORIZONTAL_LINE_TO),
+        ]);
+
+        let metadata = parse_metadata(&data).unwrap();
+        assert_eq!(number_of_glyphs(&metadata), 3);
+
+        let mut builder = Builder(std::string::String::new());
+        outline(&metadata, &[], GlyphId(0), &mut builder).unwrap();
+        assert_eq!(builder.0, "M 50 60 L 55 60 ");
+
+        let mut builder = Builder(std::string::String::new());
+        outline(&metadata, &[], GlyphId(1), &mut builder).unwrap();
+        assert_eq!(builder.0, "M 10 20 L 40 60 ");
+
+        // Not covered by FDSelect, so there are no local subroutines to call.
+        assert!(outline(&metadata, &[], GlyphId(2), &mut Builder(std::string::String::new())).is_none());
+    }
+
+    #[test]
+    fn uncached_private_dict() {
+        // Glyph N uses Font DICT N. Font DICTs past the cached ones have to be parsed on demand.
+        let number_of_glyphs = MAX_CACHED_PRIVATE_DICTS as u8 + 2;
+        let char_strings_offset = 29;
+        let fd_select_offset = char_strings_offset + 5 + 4 * u32::from(number_of_glyphs) + 1;
+        let fd_array_offset = fd_select_offset + 1 + u32::from(number_of_glyphs);
+        let font_dicts_data_offset = fd_array_offset + 5 + u32::from(number_of_glyphs) + 1;
+        // Private DICT plus its Local Subroutines INDEX.
+        let private_dict_len = 14;
+        let private_dicts_offset = font_dicts_data_offset + 11 * u32::from(number_of_glyphs);
+
+        let mut w = writer::Writer::new();
+        // Header
+        w.write(UInt8(2)); // major version
+        w.write(UInt8(0)); // minor version
+        w.write(UInt8(5)); // header size
+        w.write(UInt16(20)); // Top DICT length
+
+        // Top DICT
+        w.write(UInt8(29)); w.write(Int32(char_strings_offset as i32)); // CharStrings offset
+        w.write(UInt8(top_dict_operator::CHAR_STRINGS_OFFSET as u8));
+        w.write(UInt8(29)); w.write(Int32(fd_array_offset as i32)); // FDArray offset
+        w.write(UInt8(12)); w.write(UInt8(36));
+        w.write(UInt8(29)); w.write(Int32(fd_select_offset as i32)); // FDSelect offset
+        w.write(UInt8(12)); w.write(UInt8(37));
+
+        // Global Subroutines INDEX
+        w.write(UInt32(0)); // count
+
+        // CharStrings INDEX
+        assert_eq!(w.offset(), char_strings_offset as usize);
+        w.write(UInt32(u32::from(number_of_glyphs))); // count
+        w.write(UInt8(1)); // offset size
+        for i in 0..=number_of_glyphs {
+            w.write(UInt8(1 + i * 3)); // index[i]
+        }
+        for _ in 0..number_of_glyphs {
+            w.write(CFFInt(0 - 107)); // subroutine 0
+            w.write(UInt8(operator::CALL_LOCAL_SUBROUTINE));
+            w.write(UInt8(operator::HORIZONTAL_MOVE_TO));
+        }
+
+        // FDSelect
+        assert_eq!(w.offset(), fd_select_offset as usize);
+        w.write(UInt8(0)); // format
+        for i in 0..number_of_glyphs {
+            w.write(UInt8(i)); // fd
+        }
+
+        // FDArray INDEX
+        assert_eq!(w.offset(), fd_array_offset as usize);
+        w.write(UInt32(u32::from(number_of_glyphs))); // count
+        w.write(UInt8(1)); // offset size
+        for i in 0..=number_of_glyphs {
+            w.write(UInt8(1 + i * 11)); // index[i]
+        }
+        for i in 0..u32::from(number_of_glyphs) {
+            w.write(UInt8(29)); w.write(Int32(6)); // Private DICT size
+            w.write(UInt8(29)); w.write(Int32((private_dicts_offset + i * private_dict_len) as i32)); // Private DICT offset
+            w.write(UInt8(font_dict_operator::PRIVATE_DICT_SIZE_AND_OFFSET as u8));
+        }
+
+        // Private DICTs, each with a single local subroutine that pushes the glyph index.
+        assert_eq!(w.offset(), private_dicts_offset as usize);
+        for i in 0..i32::from(number_of_glyphs) {
+            w.write(UInt8(29)); w.write(Int32(6)); // Local Subroutines offset
+            w.write(UInt8(private_dict_operator::LOCAL_SUBROUTINES_OFFSET as u8));
+            // Local Subroutines INDEX
+            w.write(UInt32(1)); // count
+            w.write(UInt8(1)); // offset size
+            w.write(UInt8(1)); // index[0]
+            w.write(UInt8(2)); // index[1]
+            w.write(CFFInt(i));
+        }
+
+        let metadata = parse_metadata(&w.data).unwrap();
+        for i in 0..u16::from(number_of_glyphs) {
+            let mut builder = Builder(std::string::String::new());
+            outline(&metadata, &[], GlyphId(i), &mut builder).unwrap();
+            assert_eq!(builder.0, format!("M {} 0 ", i));
+        }
+    }
 }
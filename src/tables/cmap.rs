@@ -3,7 +3,7 @@
 use core::convert::TryFrom;
 
 use crate::{GlyphId, PlatformId};
-use crate::parser::{Stream, FromData, Offset, Offset32, U24, LazyArray16, NumFrom};
+use crate::parser::{Stream, FromData, Offset, Offset32, U24, LazyArray16, LazyArray32, LazyArrayIter32, NumFrom};
 
 
 #[derive(Clone, Copy)]
@@ -104,26 +104,227 @@ pub fn glyph_index(table: &Table, c: char) -> Option<GlyphId> {
     None
 }
 
-pub fn glyph_variation_index(table: &Table, c: char, variation: char) -> Option<GlyphId> {
+pub fn glyph_variation_index(table: &Table, c: char, variation: char) -> Option<GlyphVariationResult> {
+    let (data, records) = parse_variation_selector_records(table)?;
+    // Records are sorted by the variation selector.
+    let variation = u32::from(variation);
+    let (_, record) = records.binary_search_by(|v| v.var_selector.cmp(&variation))?;
+    VariationSelector::parse(data, record)?.glyph_index(c)
+}
+
+pub fn variation_selectors<'a>(table: &Table<'a>) -> VariationSelectors<'a> {
+    match parse_variation_selector_records(table) {
+        Some((data, records)) => VariationSelectors {
+            data,
+            records: records.into_iter(),
+        },
+        None => VariationSelectors::default(),
+    }
+}
+
+// Returns the first Unicode Variation Sequences subtable and its Variation Selector Records.
+fn parse_variation_selector_records<'a>(
+    table: &Table<'a>,
+) -> Option<(&'a [u8], LazyArray32<'a, VariationSelectorRecord>)> {
     for record in table.records {
-        let subtable_data = table.data.get(usize::num_from(record.offset)..)?;
-        let mut s = Stream::new(subtable_data);
-        let format = match parse_format(s.read()?) {
-            Some(format) => format,
+        let subtable_data = match table.data.get(usize::num_from(record.offset)..) {
+            Some(data) => data,
             None => continue,
         };
 
-        if format != Format::UnicodeVariationSequences {
+        let mut s = Stream::new(subtable_data);
+        if s.read::<u16>().and_then(parse_format) != Some(Format::UnicodeVariationSequences) {
             continue;
         }
 
-        return parse_unicode_variation_sequences(table, subtable_data, c, u32::from(variation));
+        s.skip::<u32>(); // length
+        let count: u32 = s.read()?;
+        let records = s.read_array32(count)?;
+        return Some((subtable_data, records));
     }
 
     None
 }
 
 
+/// A result of a variation glyph mapping.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GlyphVariationResult {
+    /// Glyph was found in the variation encoding table.
+    Found(GlyphId),
+    /// Glyph should be looked up in the default, non-variation encoding tables.
+    ///
+    /// Basically, you should use `Font::glyph_index` in this case.
+    UseDefault,
+}
+
+
+/// An iterator over
+/// [Variation Selector Records](
+/// https://docs.microsoft.com/en-us/typography/opentype/spec/cmap#format-14-unicode-variation-sequences).
+#[allow(missing_debug_implementations)]
+#[derive(Clone, Copy, Default)]
+pub struct VariationSelectors<'a> {
+    data: &'a [u8],
+    records: LazyArrayIter32<'a, VariationSelectorRecord>,
+}
+
+impl<'a> Iterator for VariationSelectors<'a> {
+    type Item = VariationSelector<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let record = self.records.next()?;
+
+            // Skip records with an invalid code point.
+            if let Some(selector) = VariationSelector::parse(self.data, record) {
+                return Some(selector);
+            }
+        }
+    }
+}
+
+fn parse_array32<T: FromData>(data: &[u8], offset: Offset32) -> Option<LazyArray32<'_, T>> {
+    let mut s = Stream::new_at(data, offset.to_usize())?;
+    let count: u32 = s.read()?;
+    s.read_array32(count)
+}
+
+
+/// A variation selector with all the sequences it supports.
+#[allow(missing_debug_implementations)]
+#[derive(Clone, Copy)]
+pub struct VariationSelector<'a> {
+    variation: char,
+    default_ranges: LazyArray32<'a, UnicodeRangeRecord>,
+    non_default_mappings: LazyArray32<'a, UVSMappingRecord>,
+}
+
+impl<'a> VariationSelector<'a> {
+    // Returns `None` when the record has an invalid code point.
+    fn parse(data: &'a [u8], record: VariationSelectorRecord) -> Option<Self> {
+        let variation = char::from_u32(record.var_selector)?;
+
+        let default_ranges = record.default_uvs_offset
+            .and_then(|offset| parse_array32(data, offset))
+            .unwrap_or_default();
+
+        let non_default_mappings = record.non_default_uvs_offset
+            .and_then(|offset| parse_array32(data, offset))
+            .unwrap_or_default();
+
+        Some(VariationSelector {
+            variation,
+            default_ranges,
+            non_default_mappings,
+        })
+    }
+
+    /// Returns the variation selector code point.
+    #[inline]
+    pub fn variation(&self) -> char {
+        self.variation
+    }
+
+    /// Resolves a glyph for a base character followed by this variation selector.
+    ///
+    /// Returns `None` when the sequence is not supported.
+    pub fn glyph_index(&self, c: char) -> Option<GlyphVariationResult> {
+        let cp = u32::from(c);
+
+        let is_default = self.default_ranges.binary_search_by(|range| {
+            use core::cmp::Ordering;
+
+            if range.contains(cp) {
+                Ordering::Equal
+            } else if range.start_unicode_value < cp {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            }
+        }).is_some();
+
+        if is_default {
+            return Some(GlyphVariationResult::UseDefault);
+        }
+
+        self.non_default_mappings.binary_search_by(|v| v.unicode_value.cmp(&cp))
+            .map(|(_, mapping)| GlyphVariationResult::Found(mapping.glyph_id))
+    }
+
+    /// Returns an iterator over all the sequences supported by this variation selector.
+    ///
+    /// Sequences are returned in the code point order.
+    #[inline]
+    pub fn sequences(&self) -> VariationSequences<'a> {
+        VariationSequences {
+            ranges: self.default_ranges.into_iter(),
+            range: None,
+            mappings: self.non_default_mappings.into_iter(),
+            mapping: None,
+        }
+    }
+}
+
+
+/// An iterator over base characters supported by a variation selector.
+///
+/// Each item is a base character and a mapping result for it.
+#[allow(missing_debug_implementations)]
+#[derive(Clone, Copy)]
+pub struct VariationSequences<'a> {
+    ranges: LazyArrayIter32<'a, UnicodeRangeRecord>,
+    // The next code point and the last code point of the current default range.
+    range: Option<(u32, u32)>,
+    mappings: LazyArrayIter32<'a, UVSMappingRecord>,
+    mapping: Option<UVSMappingRecord>,
+}
+
+impl<'a> Iterator for VariationSequences<'a> {
+    type Item = (char, GlyphVariationResult);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.range.is_none() {
+                self.range = self.ranges.next().map(|r| (r.start_unicode_value, r.end()));
+            }
+
+            if self.mapping.is_none() {
+                self.mapping = self.mappings.next();
+            }
+
+            let use_range = match (self.range, self.mapping) {
+                (Some((cp, _)), Some(mapping)) => cp <= mapping.unicode_value,
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (None, None) => return None,
+            };
+
+            let (cp, result) = if use_range {
+                let (cp, end) = self.range?;
+                self.range = if cp < end { Some((cp + 1, end)) } else { None };
+
+                // A code point can be present in both tables.
+                // In this case, the default mapping has a higher priority.
+                if self.mapping.map(|m| m.unicode_value) == Some(cp) {
+                    self.mapping = None;
+                }
+
+                (cp, GlyphVariationResult::UseDefault)
+            } else {
+                let mapping = self.mapping.take()?;
+                (mapping.unicode_value, GlyphVariationResult::Found(mapping.glyph_id))
+            };
+
+            // Skip invalid code points.
+            if let Some(c) = char::from_u32(cp) {
+                return Some((c, result));
+            }
+        }
+    }
+}
+
+
 #[derive(Clone, Copy)]
 struct VariationSelectorRecord {
     var_selector: u32,
@@ -173,9 +374,15 @@ struct UnicodeRangeRecord {
 }
 
 impl UnicodeRangeRecord {
-    fn contains(&self, c: char) -> bool {
-        let end = self.start_unicode_value + u32::from(self.additional_count);
-        self.start_unicode_value >= u32::from(c) && u32::from(c) < end
+    #[inline]
+    fn end(&self) -> u32 {
+        self.start_unicode_value + u32::from(self.additional_count)
+    }
+
+    #[inline]
+    fn contains(&self, cp: u32) -> bool {
+        // `additionalCount` is inclusive.
+        self.start_unicode_value <= cp && cp <= self.end()
     }
 }
 
@@ -192,52 +399,6 @@ impl FromData for UnicodeRangeRecord {
     }
 }
 
-
-fn parse_unicode_variation_sequences(
-    table: &Table,
-    data: &[u8],
-    c: char,
-    variation: u32,
-) -> Option<GlyphId> {
-    let cp = u32::from(c);
-
-    let mut s = Stream::new(data);
-    s.skip::<u16>(); // format
-    s.skip::<u32>(); // length
-    let count: u32 = s.read()?;
-    let records = s.read_array32::<VariationSelectorRecord>(count)?;
-
-    let (_, record) = match records.binary_search_by(|v| v.var_selector.cmp(&variation)) {
-        Some(v) => v,
-        None => return None,
-    };
-
-    if let Some(offset) = record.default_uvs_offset {
-        let data = data.get(offset.to_usize()..)?;
-        let mut s = Stream::new(data);
-        let count: u32 = s.read()?;
-        let ranges = s.read_array32::<UnicodeRangeRecord>(count)?;
-        for range in ranges {
-            if range.contains(c) {
-                // This is a default glyph.
-                return glyph_index(table, c);
-            }
-        }
-    }
-
-    if let Some(offset) = record.non_default_uvs_offset {
-        let data = data.get(offset.to_usize()..)?;
-        let mut s = Stream::new(data);
-        let count: u32 = s.read()?;
-        let uvs_mappings = s.read_array32::<UVSMappingRecord>(count)?;
-        if let Some((_, mapping)) = uvs_mappings.binary_search_by(|v| v.unicode_value.cmp(&cp)) {
-            return Some(mapping.glyph_id);
-        }
-    }
-
-    None
-}
-
 // https://docs.microsoft.com/en-us/typography/opentype/spec/cmap#format-0-byte-encoding-table
 fn parse_byte_encoding_table(mut s: Stream, code_point: u32) -> Option<u16> {
     let length: u16 = s.read()?;
@@ -523,3 +684,118 @@ fn is_unicode_encoding(format: Format, platform_id: PlatformId, encoding_id: u16
         _ => false,
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    fn variation_sequences_table() -> std::vec::Vec<u8> {
+        writer::convert(&[
+            UInt16(0), // version
+            UInt16(1), // numTables
+            UInt16(0), // platformID
+            UInt16(5), // encodingID
+            UInt32(12), // offset
+            // Subtable
+            UInt16(14), // format
+            UInt32(43), // length
+            UInt32(1), // numVarSelectorRecords
+            // Variation Selector Record [0]
+            UInt8(0), UInt16(0xFE00), // varSelector
+            UInt32(21), // defaultUVSOffset
+            UInt32(29), // nonDefaultUVSOffset
+            // Default UVS
+            UInt32(1), // numUnicodeValueRanges
+            UInt8(0), UInt16(0x41), // startUnicodeValue
+            UInt8(2), // additionalCount
+            // Non-Default UVS
+            UInt32(2), // numUVSMappings
+            UInt8(0), UInt16(0x42), // unicodeValue
+            UInt16(6), // glyphID
+            UInt8(0), UInt16(0x50), // unicodeValue
+            UInt16(7), // glyphID
+        ])
+    }
+
+    #[test]
+    fn variation_index() {
+        let data = variation_sequences_table();
+        let table = Table::parse(&data).unwrap();
+
+        let v = '\u{FE00}';
+        assert_eq!(glyph_variation_index(&table, 'A', v), Some(GlyphVariationResult::UseDefault));
+        assert_eq!(glyph_variation_index(&table, 'C', v), Some(GlyphVariationResult::UseDefault));
+        assert_eq!(glyph_variation_index(&table, 'D', v), None);
+        assert_eq!(glyph_variation_index(&table, 'P', v), Some(GlyphVariationResult::Found(GlyphId(7))));
+        assert_eq!(glyph_variation_index(&table, 'P', '\u{FE01}'), None);
+    }
+
+    #[test]
+    fn variation_index_multiple_selectors() {
+        let data = writer::convert(&[
+            UInt16(0), // version
+            UInt16(1), // numTables
+            UInt16(0), // platformID
+            UInt16(5), // encodingID
+            UInt32(12), // offset
+            // Subtable
+            UInt16(14), // format
+            UInt32(70), // length
+            UInt32(3), // numVarSelectorRecords
+            // Variation Selector Record [0]
+            UInt8(0), UInt16(0xFE00), // varSelector
+            UInt32(0), // defaultUVSOffset
+            UInt32(43), // nonDefaultUVSOffset
+            // Variation Selector Record [1]
+            UInt8(0), UInt16(0xFE01), // varSelector
+            UInt32(0), // defaultUVSOffset
+            UInt32(52), // nonDefaultUVSOffset
+            // Variation Selector Record [2]
+            UInt8(0x0E), UInt16(0x0100), // varSelector
+            UInt32(0), // defaultUVSOffset
+            UInt32(61), // nonDefaultUVSOffset
+            // Non-Default UVS [0]
+            UInt32(1), // numUVSMappings
+            UInt8(0), UInt16(0x41), // unicodeValue
+            UInt16(1), // glyphID
+            // Non-Default UVS [1]
+            UInt32(1), // numUVSMappings
+            UInt8(0), UInt16(0x41), // unicodeValue
+            UInt16(2), // glyphID
+            // Non-Default UVS [2]
+            UInt32(1), // numUVSMappings
+            UInt8(0), UInt16(0x41), // unicodeValue
+            UInt16(3), // glyphID
+        ]);
+        let table = Table::parse(&data).unwrap();
+
+        let found = |id| Some(GlyphVariationResult::Found(GlyphId(id)));
+        assert_eq!(glyph_variation_index(&table, 'A', '\u{FE00}'), found(1));
+        assert_eq!(glyph_variation_index(&table, 'A', '\u{FE01}'), found(2));
+        assert_eq!(glyph_variation_index(&table, 'A', '\u{E0100}'), found(3));
+        assert_eq!(glyph_variation_index(&table, 'A', '\u{FE02}'), None);
+        assert_eq!(glyph_variation_index(&table, 'B', '\u{FE01}'), None);
+    }
+
+    #[test]
+    fn variation_sequences() {
+        let data = variation_sequences_table();
+        let table = Table::parse(&data).unwrap();
+
+        let mut selectors = variation_selectors(&table);
+        let selector = selectors.next().unwrap();
+        assert_eq!(selector.variation(), '\u{FE00}');
+        assert!(selectors.next().is_none());
+
+        let mut sequences = selector.sequences();
+        assert_eq!(sequences.next(), Some(('A', GlyphVariationResult::UseDefault)));
+        // 'B' is present in both tables, but the default one has a higher priority.
+        assert_eq!(sequences.next(), Some(('B', GlyphVariationResult::UseDefault)));
+        assert_eq!(sequences.next(), Some(('C', GlyphVariationResult::UseDefault)));
+        assert_eq!(sequences.next(), Some(('P', GlyphVariationResult::Found(GlyphId(7)))));
+        assert_eq!(sequences.next(), None);
+    }
+}
@@ -0,0 +1,55 @@
+// https://docs.microsoft.com/en-us/typography/opentype/spec/cvt
+
+use crate::parser::LazyArray16;
+
+/// A list of control values.
+#[derive(Clone, Copy, Default)]
+pub struct ControlValues<'a> {
+    values: LazyArray16<'a, i16>,
+    index: u16,
+}
+
+impl<'a> ControlValues<'a> {
+    #[inline]
+    pub(crate) fn parse(data: &'a [u8]) -> Self {
+        ControlValues {
+            values: LazyArray16::new(data),
+            index: 0,
+        }
+    }
+
+    /// Returns the number of control values.
+    #[inline]
+    pub fn len(&self) -> u16 {
+        self.values.len()
+    }
+
+    /// Checks that there are no control values.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns a control value at `index`.
+    #[inline]
+    pub fn get(&self, index: u16) -> Option<i16> {
+        self.values.get(index)
+    }
+}
+
+impl<'a> Iterator for ControlValues<'a> {
+    type Item = i16;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.values.get(self.index)?;
+        self.index += 1;
+        Some(value)
+    }
+}
+
+impl core::fmt::Debug for ControlValues<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "ControlValues()")
+    }
+}
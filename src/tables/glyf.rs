@@ -1,9 +1,10 @@
 // https://docs.microsoft.com/en-us/typography/opentype/spec/glyf
 
+use core::convert::TryFrom;
 use core::num::NonZeroU16;
 
 use crate::parser::{Stream, F2DOT14, LazyArray16, NumFrom};
-use crate::{loca, GlyphId, OutlineBuilder, Rect, BBox};
+use crate::{loca, gvar, GlyphId, NormalizedCoord, OutlineBuilder, Rect, BBox};
 
 pub(crate) struct Builder<'a> {
     pub builder: &'a mut dyn OutlineBuilder,
@@ -145,7 +146,11 @@ impl<'a> Builder<'a> {
 }
 
 
-#[derive(Clone, Copy)]
+/// An affine transform.
+///
+/// `a`, `b`, `c` and `d` define a 2x2 matrix and `e`, `f` define an offset.
+#[derive(Clone, Copy, PartialEq)]
+#[allow(missing_docs)]
 pub struct Transform {
     pub a: f32, pub b: f32, pub c: f32,
     pub d: f32, pub e: f32, pub f: f32,
@@ -153,12 +158,12 @@ pub struct Transform {
 
 impl Transform {
     #[inline]
-    pub fn new_translate(tx: f32, ty: f32) -> Self {
+    pub(crate) fn new_translate(tx: f32, ty: f32) -> Self {
         Transform { a: 1.0, b: 0.0, c: 0.0, d: 1.0, e: tx, f: ty }
     }
 
     #[inline]
-    pub fn combine(ts1: Self, ts2: Self) -> Self {
+    pub(crate) fn combine(ts1: Self, ts2: Self) -> Self {
         Transform {
             a: ts1.a * ts2.a + ts1.c * ts2.b,
             b: ts1.b * ts2.a + ts1.d * ts2.b,
@@ -204,35 +209,53 @@ impl core::fmt::Debug for Transform {
 }
 
 
+/// A composite glyph component.
 #[derive(Clone, Copy, Debug)]
-pub(crate) struct CompositeGlyphInfo {
+pub struct GlyphComponent {
+    /// Component's glyph ID.
     pub glyph_id: GlyphId,
+    /// Component's transform.
+    ///
+    /// The offset is set only when `flags.args_are_xy_values()` is set.
     pub transform: Transform,
+    /// Parent's and component's point numbers that should be matched
+    /// to position the component.
+    ///
+    /// Set only when `flags.args_are_xy_values()` is not set.
+    pub matched_points: Option<(u16, u16)>,
+    /// Component's flags.
     pub flags: CompositeGlyphFlags,
 }
 
 
+/// An iterator over composite glyph components.
 #[derive(Clone)]
-pub(crate) struct CompositeGlyphIter<'a> {
+pub struct GlyphComponents<'a> {
     stream: Stream<'a>,
+    finished: bool,
 }
 
-impl<'a> CompositeGlyphIter<'a> {
+impl<'a> GlyphComponents<'a> {
     #[inline]
-    pub fn new(data: &'a [u8]) -> Self {
-        CompositeGlyphIter { stream: Stream::new(data) }
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        GlyphComponents { stream: Stream::new(data), finished: false }
     }
 }
 
-impl<'a> Iterator for CompositeGlyphIter<'a> {
-    type Item = CompositeGlyphInfo;
+impl<'a> Iterator for GlyphComponents<'a> {
+    type Item = GlyphComponent;
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
         let flags = CompositeGlyphFlags(self.stream.read()?);
         let glyph_id: GlyphId = self.stream.read()?;
 
         let mut ts = Transform::default();
+        let mut matched_points = None;
 
         if flags.args_are_xy_values() {
             if flags.arg_1_and_2_are_words() {
@@ -242,6 +265,17 @@ impl<'a> Iterator for CompositeGlyphIter<'a> {
                 ts.e = f32::from(self.stream.read::<i8>()?);
                 ts.f = f32::from(self.stream.read::<i8>()?);
             }
+        } else {
+            // Point numbers are unsigned.
+            if flags.arg_1_and_2_are_words() {
+                let parent: u16 = self.stream.read()?;
+                let child: u16 = self.stream.read()?;
+                matched_points = Some((parent, child));
+            } else {
+                let parent: u8 = self.stream.read()?;
+                let child: u8 = self.stream.read()?;
+                matched_points = Some((u16::from(parent), u16::from(child)));
+            }
         }
 
         if flags.we_have_a_two_by_two() {
@@ -259,24 +293,32 @@ impl<'a> Iterator for CompositeGlyphIter<'a> {
 
         if !flags.more_components() {
             // Finish the iterator even if stream still has some data.
-            self.stream.jump_to_end();
+            // The stream position is kept, since instructions may follow.
+            self.finished = true;
         }
 
-        Some(CompositeGlyphInfo {
+        Some(GlyphComponent {
             glyph_id,
             transform: ts,
+            matched_points,
             flags,
         })
     }
 }
 
+impl core::fmt::Debug for GlyphComponents<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "GlyphComponents()")
+    }
+}
+
 
 // Due to some optimization magic, using f32 instead of i16
 // makes the code ~10% slower. At least on my machine.
 // I guess it's due to the fact that with i16 the struct
 // fits into the machine word.
 #[derive(Clone, Copy, Debug)]
-pub struct GlyphPoint {
+pub struct ParsedPoint {
     pub x: i16,
     pub y: i16,
     /// Indicates that a point is a point on curve
@@ -303,7 +345,7 @@ impl GlyphPointsIter<'_> {
 }
 
 impl<'a> Iterator for GlyphPointsIter<'a> {
-    type Item = GlyphPoint;
+    type Item = ParsedPoint;
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
@@ -313,7 +355,7 @@ impl<'a> Iterator for GlyphPointsIter<'a> {
 
         let last_point = self.endpoints.next();
         let flags = self.flags.next()?;
-        Some(GlyphPoint {
+        Some(ParsedPoint {
             x: self.x_coords.next(flags.x_short(), flags.x_is_same_or_positive_short()),
             y: self.y_coords.next(flags.y_short(), flags.y_is_same_or_positive_short()),
             on_curve_point: flags.on_curve_point(),
@@ -323,6 +365,93 @@ impl<'a> Iterator for GlyphPointsIter<'a> {
 }
 
 
+/// A simple glyph point.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct GlyphPoint {
+    /// Point's X coordinate.
+    pub x: f32,
+    /// Point's Y coordinate.
+    pub y: f32,
+    /// Indicates that a point is a point on curve
+    /// and not a control point.
+    pub on_curve_point: bool,
+    /// Indicates that a point is the last point of a contour.
+    pub last_point: bool,
+}
+
+
+/// An iterator over simple glyph points.
+///
+/// Points are returned as stored in the `glyf` table, so implied on-curve points
+/// are not generated and the point index is the same as the one used by
+/// TrueType instructions and `gvar`.
+#[derive(Clone)]
+pub struct GlyphPoints<'a> {
+    endpoints: LazyArray16<'a, u16>,
+    all_points: GlyphPointsIter<'a>,
+    points: GlyphPointsIter<'a>,
+    pub(crate) variation: Option<gvar::VariationTuples<'a>>,
+}
+
+impl<'a> GlyphPoints<'a> {
+    #[inline]
+    fn new(points: GlyphPointsIter<'a>) -> Self {
+        GlyphPoints {
+            endpoints: points.endpoints.endpoints,
+            all_points: points.clone(),
+            points,
+            variation: None,
+        }
+    }
+
+    /// Returns the number of contours.
+    #[inline]
+    pub fn number_of_contours(&self) -> u16 {
+        self.endpoints.len()
+    }
+
+    /// Returns the index of the last point of a contour.
+    #[inline]
+    pub fn contour_end_point(&self, index: u16) -> Option<u16> {
+        self.endpoints.get(index)
+    }
+
+    /// Returns the total number of points.
+    #[inline]
+    pub fn number_of_points(&self) -> u16 {
+        self.all_points.points_left
+    }
+}
+
+impl<'a> Iterator for GlyphPoints<'a> {
+    type Item = GlyphPoint;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let point = self.points.next()?;
+        let (x, y) = match self.variation {
+            Some(ref mut tuples) => {
+                tuples.apply(self.all_points.clone(), self.points.clone(), point)?
+            }
+            None => (f32::from(point.x), f32::from(point.y)),
+        };
+
+        Some(GlyphPoint {
+            x,
+            y,
+            on_curve_point: point.on_curve_point,
+            last_point: point.last_point,
+        })
+    }
+}
+
+impl core::fmt::Debug for GlyphPoints<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "GlyphPoints()")
+    }
+}
+
+
 /// A simple flattening iterator for glyph's endpoints.
 ///
 /// Translates endpoints like: 2 4 7
@@ -477,17 +606,37 @@ impl SimpleGlyphFlags {
 }
 
 
-// https://docs.microsoft.com/en-us/typography/opentype/spec/glyf#composite-glyph-description
-#[derive(Clone, Copy, Debug)]
-pub(crate) struct CompositeGlyphFlags(u16);
+/// Composite glyph component flags.
+///
+/// https://docs.microsoft.com/en-us/typography/opentype/spec/glyf#composite-glyph-description
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct CompositeGlyphFlags(u16);
 
 impl CompositeGlyphFlags {
+    /// Arguments are 16-bit.
     #[inline] pub fn arg_1_and_2_are_words(self) -> bool { self.0 & 0x0001 != 0 }
+    /// Arguments are an offset and not point numbers.
     #[inline] pub fn args_are_xy_values(self) -> bool { self.0 & 0x0002 != 0 }
+    /// The offset should be rounded to the grid.
+    #[inline] pub fn round_xy_to_grid(self) -> bool { self.0 & 0x0004 != 0 }
+    /// Component has a simple scale.
     #[inline] pub fn we_have_a_scale(self) -> bool { self.0 & 0x0008 != 0 }
+    /// At least one more component follows.
     #[inline] pub fn more_components(self) -> bool { self.0 & 0x0020 != 0 }
+    /// Component has different scales for the x and y directions.
     #[inline] pub fn we_have_an_x_and_y_scale(self) -> bool { self.0 & 0x0040 != 0 }
+    /// Component has a 2x2 transformation matrix.
     #[inline] pub fn we_have_a_two_by_two(self) -> bool { self.0 & 0x0080 != 0 }
+    /// Instructions for the composite glyph follow the last component.
+    #[inline] pub fn we_have_instructions(self) -> bool { self.0 & 0x0100 != 0 }
+    /// The composite glyph should use this component's advance width and side bearing.
+    #[inline] pub fn use_my_metrics(self) -> bool { self.0 & 0x0200 != 0 }
+    /// Components of the composite glyph overlap.
+    #[inline] pub fn overlap_compound(self) -> bool { self.0 & 0x0400 != 0 }
+    /// The offset should be scaled by the component's matrix.
+    #[inline] pub fn scaled_component_offset(self) -> bool { self.0 & 0x0800 != 0 }
+    /// The offset should not be scaled by the component's matrix.
+    #[inline] pub fn unscaled_component_offset(self) -> bool { self.0 & 0x1000 != 0 }
 }
 
 
@@ -504,7 +653,81 @@ pub(crate) fn outline(
     let mut b = Builder::new(Transform::default(), None, builder);
     let range = loca_table.glyph_range(glyph_id)?;
     let glyph_data = glyf_table.get(range)?;
-    outline_impl(loca_table, glyf_table, glyph_data, 0, &mut b)
+    outline_impl(loca_table, glyf_table, glyph_id, glyph_data, 0, &mut b)
+}
+
+#[inline]
+pub(crate) fn components<'a>(
+    loca_table: loca::Table,
+    glyf_table: &'a [u8],
+    glyph_id: GlyphId,
+) -> Option<GlyphComponents<'a>> {
+    let range = loca_table.glyph_range(glyph_id)?;
+    let glyph_data = glyf_table.get(range)?;
+    let mut s = Stream::new(glyph_data);
+    let number_of_contours: i16 = s.read()?;
+    if number_of_contours >= 0 {
+        return None;
+    }
+
+    s.advance(8); // bbox
+    Some(GlyphComponents::new(s.tail()?))
+}
+
+#[inline]
+pub(crate) fn points<'a>(
+    loca_table: loca::Table,
+    glyf_table: &'a [u8],
+    glyph_id: GlyphId,
+) -> Option<GlyphPoints<'a>> {
+    let range = loca_table.glyph_range(glyph_id)?;
+    let glyph_data = glyf_table.get(range)?;
+    let mut s = Stream::new(glyph_data);
+    let number_of_contours: i16 = s.read()?;
+    if number_of_contours <= 0 {
+        return None;
+    }
+
+    // u16 casting is safe, since we already checked that the value is positive.
+    let number_of_contours = NonZeroU16::new(number_of_contours as u16)?;
+    s.advance(8); // bbox
+    let points = parse_simple_points(s.tail()?, number_of_contours)?;
+    Some(GlyphPoints::new(points))
+}
+
+#[inline]
+pub(crate) fn instructions<'a>(
+    loca_table: loca::Table,
+    glyf_table: &'a [u8],
+    glyph_id: GlyphId,
+) -> Option<&'a [u8]> {
+    let range = loca_table.glyph_range(glyph_id)?;
+    let glyph_data = glyf_table.get(range)?;
+    let mut s = Stream::new(glyph_data);
+    let number_of_contours: i16 = s.read()?;
+    s.advance(8); // bbox
+
+    if number_of_contours > 0 {
+        // u16 casting is safe, since we already checked that the value is positive.
+        s.advance(usize::from(number_of_contours as u16) * 2); // endPtsOfContours
+    } else if number_of_contours < 0 {
+        let mut components = GlyphComponents::new(s.tail()?);
+        let mut has_instructions = false;
+        for component in components.by_ref() {
+            has_instructions |= component.flags.we_have_instructions();
+        }
+
+        if !has_instructions {
+            return Some(&[]);
+        }
+
+        s = components.stream;
+    } else {
+        return None;
+    }
+
+    let instructions_len: u16 = s.read()?;
+    s.read_bytes(usize::from(instructions_len))
 }
 
 #[inline]
@@ -530,6 +753,7 @@ pub(crate) fn glyph_bbox(
 fn outline_impl(
     loca_table: loca::Table,
     glyf_table: &[u8],
+    glyph_id: GlyphId,
     data: &[u8],
     depth: u8,
     builder: &mut Builder,
@@ -559,12 +783,24 @@ fn outline_impl(
         }
     } else if number_of_contours < 0 {
         // Composite glyph.
-        for comp in CompositeGlyphIter::new(s.tail()?) {
+        let components_data = s.tail()?;
+        for (i, comp) in GlyphComponents::new(components_data).enumerate() {
             if let Some(range) = loca_table.glyph_range(comp.glyph_id) {
                 if let Some(glyph_data) = glyf_table.get(range) {
-                    let transform = Transform::combine(builder.transform, comp.transform);
+                    let mut comp_transform = comp.transform;
+                    if comp.matched_points.is_some() {
+                        let mut resolver = PointsResolver::new(loca_table, glyf_table, None);
+                        if let Some(ts) = resolver.matched_transform(
+                            glyph_id, components_data, i, &comp, depth,
+                        ) {
+                            comp_transform = ts;
+                        }
+                    }
+
+                    let transform = Transform::combine(builder.transform, comp_transform);
                     let mut b = Builder::new(transform, None, builder.builder);
-                    outline_impl(loca_table, glyf_table, glyph_data, depth + 1, &mut b)?;
+                    outline_impl(loca_table, glyf_table, comp.glyph_id, glyph_data,
+                                 depth + 1, &mut b)?;
                 }
             }
         }
@@ -576,22 +812,219 @@ fn outline_impl(
     Some(rect)
 }
 
+/// The maximum number of glyph lookups during a single component's point matching.
+///
+/// A matched point can belong to a component that is positioned by point matching as well,
+/// so we have to limit the number of lookups to prevent an exponential complexity.
+/// Each glyph visited while looking up a point or counting points is a lookup.
+const MAX_POINT_LOOKUPS: u16 = 256;
+
+/// Resolves composite glyph components positioned by point matching.
+///
+/// 'The point number of the parent' refers to the points of the already placed components
+/// and 'the point number of the child' refers to the points of the component itself.
+/// Since we do not allocate, points are found by walking glyphs each time.
+pub(crate) struct PointsResolver<'a, 'b> {
+    loca_table: loca::Table<'a>,
+    glyf_table: &'a [u8],
+    variation: Option<(&'b gvar::Table<'a>, &'b [NormalizedCoord])>,
+    lookups_left: u16,
+}
+
+impl<'a, 'b> PointsResolver<'a, 'b> {
+    #[inline]
+    pub fn new(
+        loca_table: loca::Table<'a>,
+        glyf_table: &'a [u8],
+        variation: Option<(&'b gvar::Table<'a>, &'b [NormalizedCoord])>,
+    ) -> Self {
+        PointsResolver {
+            loca_table,
+            glyf_table,
+            variation,
+            lookups_left: MAX_POINT_LOOKUPS,
+        }
+    }
+
+    /// Returns a transform of a component positioned by point matching.
+    ///
+    /// `index` is the component index in the composite glyph `glyph_id`,
+    /// which components are stored in `data`.
+    pub fn matched_transform(
+        &mut self,
+        glyph_id: GlyphId,
+        data: &'a [u8],
+        index: usize,
+        component: &GlyphComponent,
+        depth: u8,
+    ) -> Option<Transform> {
+        self.lookups_left = MAX_POINT_LOOKUPS;
+        self.matched_transform_impl(glyph_id, data, index, component, depth)
+    }
+
+    fn matched_transform_impl(
+        &mut self,
+        glyph_id: GlyphId,
+        data: &'a [u8],
+        index: usize,
+        component: &GlyphComponent,
+        depth: u8,
+    ) -> Option<Transform> {
+        let (parent_point, child_point) = component.matched_points?;
+        let parent = self.composite_point(glyph_id, data, Some(index), parent_point, depth)?;
+        let mut child = self.glyph_point(component.glyph_id, child_point, depth + 1)?;
+
+        // The component offset is always zero in case of point matching,
+        // so only the matrix is applied.
+        let mut ts = component.transform;
+        ts.apply_to(&mut child.x, &mut child.y);
+        ts.e = parent.x - child.x;
+        ts.f = parent.y - child.y;
+        Some(ts)
+    }
+
+    /// Returns a glyph point by index.
+    ///
+    /// Composite glyph points are numbered in the components order.
+    fn glyph_point(&mut self, glyph_id: GlyphId, index: u16, depth: u8) -> Option<Point> {
+        if depth >= MAX_COMPONENTS {
+            return None;
+        }
+
+        self.lookups_left = self.lookups_left.checked_sub(1)?;
+
+        let range = self.loca_table.glyph_range(glyph_id)?;
+        let glyph_data = self.glyf_table.get(range)?;
+        let mut s = Stream::new(glyph_data);
+        let number_of_contours: i16 = s.read()?;
+        s.advance(8); // bbox
+
+        if number_of_contours > 0 {
+            // u16 casting is safe, since we already checked that the value is positive.
+            let number_of_contours = NonZeroU16::new(number_of_contours as u16)?;
+            let mut points = GlyphPoints::new(parse_simple_points(s.tail()?, number_of_contours)?);
+            if let Some((gvar_table, coordinates)) = self.variation {
+                let points_len = points.number_of_points();
+                points.variation = Some(gvar_table.points_variation(glyph_id, coordinates, points_len)?);
+            }
+
+            let p = points.nth(usize::from(index))?;
+            Some(Point { x: p.x, y: p.y })
+        } else if number_of_contours < 0 {
+            self.composite_point(glyph_id, s.tail()?, None, index, depth)
+        } else {
+            None
+        }
+    }
+
+    /// Returns a point of the first `limit` components of a composite glyph.
+    ///
+    /// All components are used when `limit` is not set.
+    fn composite_point(
+        &mut self,
+        glyph_id: GlyphId,
+        data: &'a [u8],
+        limit: Option<usize>,
+        mut index: u16,
+        depth: u8,
+    ) -> Option<Point> {
+        // Like during outlining, component offsets have to be adjusted by `gvar`.
+        let mut tuples = match self.variation {
+            Some((gvar_table, coordinates)) => {
+                let components_count = u16::try_from(GlyphComponents::new(data).count()).ok()?;
+                Some(gvar_table.points_variation(glyph_id, coordinates, components_count)?)
+            }
+            None => None,
+        };
+
+        for (i, component) in GlyphComponents::new(data).enumerate() {
+            if limit == Some(i) {
+                break;
+            }
+
+            let (dx, dy) = match tuples {
+                Some(ref mut tuples) => tuples.apply_null()?,
+                None => (0.0, 0.0),
+            };
+
+            let points_count = self.points_count(component.glyph_id, depth + 1)?;
+            if index >= points_count {
+                index -= points_count;
+                continue;
+            }
+
+            let ts = if component.matched_points.is_some() {
+                self.matched_transform_impl(glyph_id, data, i, &component, depth)?
+            } else {
+                Transform::combine(Transform::new_translate(dx, dy), component.transform)
+            };
+
+            let mut p = self.glyph_point(component.glyph_id, index, depth + 1)?;
+            ts.apply_to(&mut p.x, &mut p.y);
+            return Some(p);
+        }
+
+        None
+    }
+
+    /// Returns the number of glyph points, including points of all components.
+    fn points_count(&mut self, glyph_id: GlyphId, depth: u8) -> Option<u16> {
+        if depth >= MAX_COMPONENTS {
+            return None;
+        }
+
+        self.lookups_left = self.lookups_left.checked_sub(1)?;
+
+        let range = self.loca_table.glyph_range(glyph_id)?;
+        let glyph_data = self.glyf_table.get(range)?;
+        let mut s = Stream::new(glyph_data);
+        let number_of_contours: i16 = s.read()?;
+        s.advance(8); // bbox
+
+        if number_of_contours > 0 {
+            // u16 casting is safe, since we already checked that the value is positive.
+            let endpoints = s.read_array16::<u16>(number_of_contours as u16)?;
+            endpoints.last()?.checked_add(1)
+        } else if number_of_contours < 0 {
+            let mut count = 0u16;
+            for component in GlyphComponents::new(s.tail()?) {
+                count = count.checked_add(self.points_count(component.glyph_id, depth + 1)?)?;
+            }
+
+            Some(count)
+        } else {
+            Some(0)
+        }
+    }
+}
+
 #[inline]
 pub fn parse_simple_outline(
     glyph_data: &[u8],
     number_of_contours: NonZeroU16,
 ) -> Option<GlyphPointsIter> {
-    let mut s = Stream::new(glyph_data);
-    let endpoints = s.read_array16::<u16>(number_of_contours.get())?;
-
-    let points_total = endpoints.last()?.checked_add(1)?;
+    let points = parse_simple_points(glyph_data, number_of_contours)?;
 
     // Contours with a single point should be ignored.
     // But this is not an error, so we should return an "empty" iterator.
-    if points_total == 1 {
+    if points.points_left == 1 {
         return Some(GlyphPointsIter::default());
     }
 
+    Some(points)
+}
+
+/// Parses simple glyph points as is, without ignoring single point contours.
+#[inline]
+fn parse_simple_points(
+    glyph_data: &[u8],
+    number_of_contours: NonZeroU16,
+) -> Option<GlyphPointsIter<'_>> {
+    let mut s = Stream::new(glyph_data);
+    let endpoints = s.read_array16::<u16>(number_of_contours.get())?;
+
+    let points_total = endpoints.last()?.checked_add(1)?;
+
     // Skip instructions byte code.
     let instructions_len: u16 = s.read()?;
     s.advance(usize::from(instructions_len));
@@ -660,3 +1093,358 @@ fn resolve_coords_len(
 
     Some((x_coords_len, y_coords_len))
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec::Vec;
+    use std::string::String;
+    use std::fmt::Write;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    #[test]
+    fn components() {
+        let data = writer::convert(&[
+            // First component.
+            UInt16(0x0001 | 0x0002 | 0x0004 | 0x0008 | 0x0020 | 0x0200 | 0x0800), // flags
+            UInt16(5), // glyph ID
+            Int16(-10), // dx
+            Int16(300), // dy
+            Int16(0x2000), // scale: 0.5
+            // Second component.
+            UInt16(0x0040 | 0x0400 | 0x0020), // flags
+            UInt16(6), // glyph ID
+            UInt8(3), // parent point
+            UInt8(7), // child point
+            Int16(0x4000), // x scale: 1.0
+            Int16(-0x4000), // y scale: -1.0
+            // Third component.
+            UInt16(0x0001 | 0x0080), // flags
+            UInt16(7), // glyph ID
+            UInt16(300), // parent point
+            UInt16(2), // child point
+            Int16(0x4000), // a
+            Int16(0x1000), // b
+            Int16(0), // c
+            Int16(0x4000), // d
+        ]);
+
+        let mut iter = GlyphComponents::new(&data);
+
+        let c = iter.next().unwrap();
+        assert_eq!(c.glyph_id, GlyphId(5));
+        assert_eq!(c.matched_points, None);
+        assert_eq!(c.transform, Transform { a: 0.5, b: 0.0, c: 0.0, d: 0.5, e: -10.0, f: 300.0 });
+        assert!(c.flags.round_xy_to_grid());
+        assert!(c.flags.use_my_metrics());
+        assert!(c.flags.scaled_component_offset());
+        assert!(!c.flags.overlap_compound());
+
+        let c = iter.next().unwrap();
+        assert_eq!(c.glyph_id, GlyphId(6));
+        assert_eq!(c.matched_points, Some((3, 7)));
+        assert_eq!(c.transform, Transform { a: 1.0, b: 0.0, c: 0.0, d: -1.0, e: 0.0, f: 0.0 });
+        assert!(c.flags.overlap_compound());
+        assert!(!c.flags.use_my_metrics());
+
+        let c = iter.next().unwrap();
+        assert_eq!(c.glyph_id, GlyphId(7));
+        assert_eq!(c.matched_points, Some((300, 2)));
+        assert_eq!(c.transform, Transform { a: 1.0, b: 0.25, c: 0.0, d: 1.0, e: 0.0, f: 0.0 });
+
+        assert!(iter.next().is_none());
+    }
+
+    fn simple_glyph() -> Vec<u8> {
+        writer::convert(&[
+            Int16(2), // number of contours
+            Int16(0), Int16(0), Int16(100), Int16(100), // bbox
+            UInt16(2), UInt16(3), // end points
+            UInt16(0), // instructions length
+            UInt8(1), UInt8(0), UInt8(1), UInt8(1), // flags
+            Int16(0), Int16(100), Int16(0), Int16(-50), // x coordinates
+            Int16(0), Int16(0), Int16(100), Int16(-50), // y coordinates
+        ])
+    }
+
+    #[test]
+    fn points() {
+        let glyf_data = simple_glyph();
+        let loca_data = writer::convert(&[UInt16(0), UInt16(glyf_data.len() as u16 / 2)]);
+        let loca_table = loca::Table::parse(
+            &loca_data, NonZeroU16::new(1).unwrap(), crate::IndexToLocationFormat::Short,
+        ).unwrap();
+
+        let points = super::points(loca_table, &glyf_data, GlyphId(0)).unwrap();
+        assert_eq!(points.number_of_contours(), 2);
+        assert_eq!(points.contour_end_point(0), Some(2));
+        assert_eq!(points.contour_end_point(1), Some(3));
+        assert_eq!(points.contour_end_point(2), None);
+        assert_eq!(points.number_of_points(), 4);
+
+        let points: Vec<_> = points.map(|p| (p.x, p.y, p.on_curve_point, p.last_point)).collect();
+        assert_eq!(points, &[
+            (0.0, 0.0, true, false),
+            (100.0, 0.0, false, false),
+            (100.0, 100.0, true, true),
+            (50.0, 50.0, true, true),
+        ]);
+    }
+
+    #[test]
+    fn points_with_variations() {
+        let glyf_data = simple_glyph();
+        let loca_data = writer::convert(&[UInt16(0), UInt16(glyf_data.len() as u16 / 2)]);
+        let loca_table = loca::Table::parse(
+            &loca_data, NonZeroU16::new(1).unwrap(), crate::IndexToLocationFormat::Short,
+        ).unwrap();
+
+        let gvar_data = writer::convert(&[
+            UInt32(0x00010000), // version
+            UInt16(1), // axis count
+            UInt16(0), // shared tuple count
+            UInt32(24), // offset to shared tuples
+            UInt16(1), // glyph count
+            UInt16(0), // flags
+            UInt32(24), // offset to glyph variation data
+            UInt16(0), UInt16(14), // offsets
+            // Glyph variation data.
+            UInt16(1), // tuple variation count
+            UInt16(10), // offset to serialized data
+            UInt16(18), // variation data size
+            UInt16(0x8000), // tuple index with an embedded peak
+            Int16(0x4000), // peak
+            // Serialized data. All points plus 4 phantom points.
+            UInt8(7), Int8(10), Int8(0), Int8(0), Int8(0), Int8(0), Int8(0), Int8(0), Int8(0),
+            UInt8(7), Int8(0), Int8(0), Int8(0), Int8(5), Int8(0), Int8(0), Int8(0), Int8(0),
+        ]);
+        let gvar_table = gvar::Table::parse(&gvar_data).unwrap();
+
+        let coords = [crate::NormalizedCoord::from(0x2000i16)];
+        let mut points = super::points(loca_table, &glyf_data, GlyphId(0)).unwrap();
+        points.variation = gvar_table.points_variation(GlyphId(0), &coords, 4);
+
+        let points: Vec<_> = points.map(|p| (p.x, p.y)).collect();
+        assert_eq!(points, &[(5.0, 0.0), (100.0, 0.0), (100.0, 100.0), (50.0, 52.5)]);
+    }
+
+    #[test]
+    fn glyph_instructions() {
+        let mut glyf_data = writer::convert(&[
+            Int16(1), // number of contours
+            Int16(0), Int16(0), Int16(0), Int16(0), // bbox
+            UInt16(0), // end points
+            UInt16(3), // instructions length
+            UInt8(0xB0), UInt8(0x01), UInt8(0x2B), // instructions
+            UInt8(1), // flags
+            Int16(0), // x coordinates
+            Int16(0), // y coordinates
+        ]);
+        glyf_data.extend_from_slice(&writer::convert(&[
+            Int16(-1), // number of contours
+            Int16(0), Int16(0), Int16(0), Int16(0), // bbox
+            UInt16(0x0002 | 0x0020), // flags
+            UInt16(0), // glyph ID
+            Int8(1), Int8(2), // offset
+            UInt16(0x0002 | 0x0100), // flags
+            UInt16(0), // glyph ID
+            Int8(3), Int8(4), // offset
+            UInt16(2), // instructions length
+            UInt8(0x18), UInt8(0x7A), // instructions
+        ]));
+        glyf_data.extend_from_slice(&simple_glyph());
+
+        let loca_data = writer::convert(&[UInt16(0), UInt16(11), UInt16(24), UInt16(42)]);
+        let loca_table = loca::Table::parse(
+            &loca_data, NonZeroU16::new(3).unwrap(), crate::IndexToLocationFormat::Short,
+        ).unwrap();
+
+        assert_eq!(instructions(loca_table, &glyf_data, GlyphId(0)), Some(&[0xB0, 0x01, 0x2B][..]));
+        assert_eq!(instructions(loca_table, &glyf_data, GlyphId(1)), Some(&[0x18, 0x7A][..]));
+        assert_eq!(instructions(loca_table, &glyf_data, GlyphId(2)), Some(&[][..]));
+    }
+
+    struct Builder(String);
+
+    impl OutlineBuilder for Builder {
+        fn move_to(&mut self, x: f32, y: f32) {
+            write!(&mut self.0, "M {} {} ", x, y).unwrap();
+        }
+
+        fn line_to(&mut self, x: f32, y: f32) {
+            write!(&mut self.0, "L {} {} ", x, y).unwrap();
+        }
+
+        fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+            write!(&mut self.0, "Q {} {} {} {} ", x1, y1, x, y).unwrap();
+        }
+
+        fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+            write!(&mut self.0, "C {} {} {} {} {} {} ", x1, y1, x2, y2, x, y).unwrap();
+        }
+
+        fn close(&mut self) {
+            write!(&mut self.0, "Z ").unwrap();
+        }
+    }
+
+    // Glyph 0 is `simple_glyph()`, glyph 1 is a triangle and glyph 2 is a composite glyph
+    // with the triangle positioned by matching its first point to the glyph 0 third point.
+    fn matched_points_glyphs() -> (Vec<u8>, Vec<u8>) {
+        let mut glyf_data = simple_glyph();
+        glyf_data.extend_from_slice(&writer::convert(&[
+            Int16(1), // number of contours
+            Int16(0), Int16(0), Int16(10), Int16(10), // bbox
+            UInt16(2), // end points
+            UInt16(0), // instructions length
+            UInt8(1), UInt8(1), UInt8(1), // flags
+            Int16(10), Int16(10), Int16(0), // x coordinates
+            Int16(10), Int16(0), Int16(10), // y coordinates
+            UInt8(0), // padding
+        ]));
+        glyf_data.extend_from_slice(&writer::convert(&[
+            Int16(-1), // number of contours
+            Int16(0), Int16(0), Int16(105), Int16(105), // bbox
+            UInt16(0x0001 | 0x0002 | 0x0020), // flags
+            UInt16(0), // glyph ID
+            Int16(0), Int16(0), // offset
+            UInt16(0x0008), // flags
+            UInt16(1), // glyph ID
+            UInt8(2), UInt8(0), // matched points
+            Int16(0x2000), // scale: 0.5
+        ]));
+
+        let loca_data = writer::convert(&[UInt16(0), UInt16(18), UInt16(33), UInt16(46)]);
+        assert_eq!(glyf_data.len(), 92);
+        (glyf_data, loca_data)
+    }
+
+    #[test]
+    fn matched_points() {
+        let (glyf_data, loca_data) = matched_points_glyphs();
+        let loca_table = loca::Table::parse(
+            &loca_data, NonZeroU16::new(3).unwrap(), crate::IndexToLocationFormat::Short,
+        ).unwrap();
+
+        let mut builder = Builder(String::new());
+        outline(loca_table, &glyf_data, GlyphId(2), &mut builder).unwrap();
+        assert_eq!(builder.0, "M 0 0 Q 100 0 100 100 L 0 0 Z M 50 50 L 50 50 Z \
+                               M 100 100 L 105 100 L 105 105 L 100 100 Z ");
+    }
+
+    #[test]
+    fn matched_points_with_variations() {
+        let (glyf_data, loca_data) = matched_points_glyphs();
+        let loca_table = loca::Table::parse(
+            &loca_data, NonZeroU16::new(3).unwrap(), crate::IndexToLocationFormat::Short,
+        ).unwrap();
+
+        let gvar_data = writer::convert(&[
+            UInt32(0x00010000), // version
+            UInt16(1), // axis count
+            UInt16(0), // shared tuple count
+            UInt32(28), // offset to shared tuples
+            UInt16(3), // glyph count
+            UInt16(0), // flags
+            UInt32(28), // offset to glyph variation data
+            UInt16(0), UInt16(14), UInt16(14), UInt16(14), // offsets
+            // Glyph 0 variation data.
+            UInt16(1), // tuple variation count
+            UInt16(10), // offset to serialized data
+            UInt16(18), // variation data size
+            UInt16(0x8000), // tuple index with an embedded peak
+            Int16(0x4000), // peak
+            // Serialized data. All points plus 4 phantom points.
+            UInt8(7), Int8(0), Int8(0), Int8(20), Int8(0), Int8(0), Int8(0), Int8(0), Int8(0),
+            UInt8(7), Int8(0), Int8(0), Int8(0), Int8(0), Int8(0), Int8(0), Int8(0), Int8(0),
+        ]);
+        let gvar_table = gvar::Table::parse(&gvar_data).unwrap();
+
+        let coords = [crate::NormalizedCoord::from(0x2000i16)];
+        let mut builder = Builder(String::new());
+        gvar::outline(loca_table, &glyf_data, &gvar_table, &coords, GlyphId(2), &mut builder).unwrap();
+        assert_eq!(builder.0, "M 0 0 Q 100 0 110 100 L 0 0 Z M 50 50 L 50 50 Z \
+                               M 110 100 L 115 100 L 115 105 L 110 100 Z ");
+    }
+
+    // Glyph 0 is a single point and glyphs 1..=levels are composite glyphs
+    // made of two copies of the previous one. The last glyph is a composite glyph
+    // with glyph `top` followed by glyph 0 matched to the `top` point `parent_point`.
+    fn fan_out_glyphs(levels: u16, top: u16, parent_point: u16) -> (Vec<u8>, Vec<u8>) {
+        let mut glyf_data = writer::convert(&[
+            Int16(1), // number of contours
+            Int16(0), Int16(0), Int16(0), Int16(0), // bbox
+            UInt16(0), // end points
+            UInt16(0), // instructions length
+            UInt8(1), // flags
+            Int16(0), // x coordinates
+            Int16(0), // y coordinates
+        ]);
+        let mut offsets = vec![0, glyf_data.len() as u32];
+
+        for glyph_id in 0..levels {
+            glyf_data.extend_from_slice(&writer::convert(&[
+                Int16(-1), // number of contours
+                Int16(0), Int16(0), Int16(0), Int16(0), // bbox
+                UInt16(0x0001 | 0x0002 | 0x0020), // flags
+                UInt16(glyph_id), // glyph ID
+                Int16(0), Int16(0), // offset
+                UInt16(0x0001 | 0x0002), // flags
+                UInt16(glyph_id), // glyph ID
+                Int16(0), Int16(0), // offset
+            ]));
+            offsets.push(glyf_data.len() as u32);
+        }
+
+        glyf_data.extend_from_slice(&writer::convert(&[
+            Int16(-1), // number of contours
+            Int16(0), Int16(0), Int16(0), Int16(0), // bbox
+            UInt16(0x0001 | 0x0002 | 0x0020), // flags
+            UInt16(top), // glyph ID
+            Int16(0), Int16(0), // offset
+            UInt16(0x0001), // flags
+            UInt16(0), // glyph ID
+            UInt16(parent_point), UInt16(0), // matched points
+        ]));
+        offsets.push(glyf_data.len() as u32);
+
+        let loca_data = offsets.into_iter().map(UInt32).collect::<Vec<_>>();
+        (glyf_data, writer::convert(&loca_data))
+    }
+
+    fn fan_out_matched_transform(levels: u16, top: u16, parent_point: u16) -> Option<Transform> {
+        let (glyf_data, loca_data) = fan_out_glyphs(levels, top, parent_point);
+        let loca_table = loca::Table::parse(
+            &loca_data, NonZeroU16::new(levels + 2).unwrap(), crate::IndexToLocationFormat::Long,
+        ).unwrap();
+
+        let glyph_id = GlyphId(levels + 1);
+        let glyph_data = &glyf_data[loca_table.glyph_range(glyph_id).unwrap()];
+        let components_data = &glyph_data[10..];
+        let component = GlyphComponents::new(components_data).nth(1).unwrap();
+        let mut resolver = PointsResolver::new(loca_table, &glyf_data, None);
+        resolver.matched_transform(glyph_id, components_data, 1, &component, 0)
+    }
+
+    #[test]
+    fn matched_points_lookups_limit() {
+        // Glyph 2 has 4 points and 7 glyphs to visit.
+        assert!(fan_out_matched_transform(8, 2, 3).is_some());
+
+        // Glyph 8 has 256 points, but counting them requires visiting 511 glyphs.
+        assert!(fan_out_matched_transform(8, 8, 0).is_none());
+    }
+
+    #[test]
+    fn truncated_component() {
+        let data = writer::convert(&[
+            UInt16(0x0001 | 0x0002), // flags
+            UInt16(5), // glyph ID
+            Int16(1), // dx
+        ]);
+
+        assert!(GlyphComponents::new(&data).next().is_none());
+    }
+}
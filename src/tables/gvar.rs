@@ -108,6 +108,18 @@ impl<'a> Table<'a> {
         let data = self.glyphs_variation_data.get(start..end)?;
         parse_variation_data(coordinates, &self.shared_tuple_records, points_len, data, tuples)
     }
+
+    /// Parses variation tuples for a simple glyph with the specified number of points.
+    pub(crate) fn points_variation(
+        &self,
+        glyph_id: GlyphId,
+        coordinates: &[NormalizedCoord],
+        points_len: u16,
+    ) -> Option<VariationTuples<'a>> {
+        let mut tuples = VariationTuples::new();
+        self.parse_variation_data(glyph_id, coordinates, points_len, &mut tuples)?;
+        Some(tuples)
+    }
 }
 
 
@@ -155,10 +167,7 @@ fn outline_var_impl<'a>(
 
     // TODO: This is the most expensive part. Find a way to allocate it only once.
     // `VariationTuples` is a very large struct, so allocate it once.
-    let mut tuples = VariationTuples {
-        headers: [VariationTuple::default(); MAX_TUPLES_LEN as usize],
-        len: 0,
-    };
+    let mut tuples = VariationTuples::new();
 
     if number_of_contours > 0 {
         // Simple glyph.
@@ -186,10 +195,12 @@ fn outline_var_impl<'a>(
         // Details:
         // https://docs.microsoft.com/en-us/typography/opentype/spec/gvar#point-numbers-and-processing-for-composite-glyphs
 
-        let mut components = glyf::CompositeGlyphIter::new(s.tail()?);
+        let components_data = s.tail()?;
+        let mut components = glyf::GlyphComponents::new(components_data);
         let components_count = components.clone().count() as u16;
         gvar_table.parse_variation_data(glyph_id, coordinates, components_count, &mut tuples)?;
 
+        let mut index = 0;
         while let Some(component) = components.next() {
             let (tx, ty) = tuples.apply_null()?;
 
@@ -199,9 +210,19 @@ fn outline_var_impl<'a>(
             // the ARGS_ARE_XY_VALUES flag is set.
             if component.flags.args_are_xy_values() {
                 transform = Transform::combine(transform, Transform::new_translate(tx, ty));
+                transform = Transform::combine(transform, component.transform);
+            } else {
+                // Otherwise, the offset is resolved using variable points.
+                let mut resolver = glyf::PointsResolver::new(
+                    loca_table, glyf_table, Some((gvar_table, coordinates)),
+                );
+                let component_transform = resolver
+                    .matched_transform(glyph_id, components_data, index, &component, depth)
+                    .unwrap_or(component.transform);
+                transform = Transform::combine(transform, component_transform);
             }
 
-            transform = Transform::combine(transform, component.transform);
+            index += 1;
 
             let mut b = glyf::Builder::new(transform, builder.bbox, builder.builder);
             let range = loca_table.glyph_range(component.glyph_id)?;
@@ -308,22 +329,31 @@ const MAX_TUPLES_LEN: u16 = 16;
 /// On stack and not on heap, but still.
 /// This is probably unavoidable due to `gvar` structure,
 /// since we have to iterate all tuples in parallel.
-struct VariationTuples<'a> {
+#[derive(Clone)]
+pub(crate) struct VariationTuples<'a> {
     headers: [VariationTuple<'a>; MAX_TUPLES_LEN as usize], // 1280B
     len: u16,
 }
 
 impl<'a> VariationTuples<'a> {
+    #[inline]
+    fn new() -> Self {
+        VariationTuples {
+            headers: [VariationTuple::default(); MAX_TUPLES_LEN as usize],
+            len: 0,
+        }
+    }
+
     #[inline]
     fn as_mut_slice(&mut self) -> &mut [VariationTuple<'a>] {
         &mut self.headers[0..usize::from(self.len)]
     }
 
-    fn apply(
+    pub(crate) fn apply(
         &mut self,
         all_points: glyf::GlyphPointsIter,
         points: glyf::GlyphPointsIter,
-        point: glyf::GlyphPoint,
+        point: glyf::ParsedPoint,
     ) -> Option<(f32, f32)> {
         let mut x = f32::from(point.x);
         let mut y = f32::from(point.y);
@@ -377,7 +407,7 @@ impl<'a> VariationTuples<'a> {
     // This is just like `apply()`, but without `infer_deltas`,
     // since we use it only for component points and not a contour.
     // And since there are no contour and no points, `infer_deltas()` will do nothing.
-    fn apply_null(&mut self) -> Option<(f32, f32)> {
+    pub(crate) fn apply_null(&mut self) -> Option<(f32, f32)> {
         let mut x = 0.0;
         let mut y = 0.0;
 
@@ -1419,7 +1449,7 @@ fn infer_deltas(
     points: glyf::GlyphPointsIter,
     // A points iterator that starts from the first point in the glyph.
     all_points: glyf::GlyphPointsIter,
-    curr_point: glyf::GlyphPoint,
+    curr_point: glyf::ParsedPoint,
 ) -> (f32, f32) {
     let mut current_contour = points.current_contour();
     if curr_point.last_point && current_contour != 0 {
@@ -1,6 +1,7 @@
 // https://docs.microsoft.com/en-us/typography/opentype/spec/head
 
-use crate::parser::Stream;
+use crate::Rect;
+use crate::parser::{Stream, Fixed};
 
 
 const TABLE_SIZE: usize = 54;
@@ -8,6 +9,139 @@ const UNITS_PER_EM_OFFSET: usize = 18;
 const INDEX_TO_LOC_FORMAT_OFFSET: usize = 50;
 
 
+/// A [font header](https://docs.microsoft.com/en-us/typography/opentype/spec/head).
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct FontHeader {
+    /// Major version. Should be 1.
+    pub major_version: u16,
+    /// Minor version. Should be 0.
+    pub minor_version: u16,
+    /// Font revision set by the font manufacturer.
+    pub font_revision: f32,
+    /// A checksum adjustment.
+    pub checksum_adjustment: u32,
+    /// Font flags.
+    pub flags: FontHeaderFlags,
+    /// Units per EM as is. Can be outside of the 16..=16384 range.
+    pub units_per_em: u16,
+    /// Creation time. Number of seconds since 12:00 midnight, January 1, 1904, UTC.
+    pub created: i64,
+    /// Modification time. Number of seconds since 12:00 midnight, January 1, 1904, UTC.
+    pub modified: i64,
+    /// A bounding box for all glyphs.
+    pub global_bbox: Rect,
+    /// Mac style.
+    pub mac_style: MacStyle,
+    /// Smallest readable size in pixels.
+    pub lowest_rec_ppem: u16,
+    /// Deprecated font direction hint. Should be 2.
+    pub font_direction_hint: i16,
+    /// Raw `indexToLocFormat`. 0 for short offsets and 1 for long.
+    pub index_to_loc_format: i16,
+    /// Raw `glyphDataFormat`. Should be 0.
+    pub glyph_data_format: i16,
+}
+
+impl FontHeader {
+    /// Parses a font header.
+    ///
+    /// Since `head` is validated by `Font::from_data`, missing values are set to zero.
+    pub(crate) fn parse(data: &[u8]) -> Self {
+        let mut s = Stream::new(data);
+        let major_version = s.read().unwrap_or(0);
+        let minor_version = s.read().unwrap_or(0);
+        let font_revision = s.read::<Fixed>().map(|v| v.0).unwrap_or(0.0);
+        let checksum_adjustment = s.read().unwrap_or(0);
+        s.skip::<u32>(); // magicNumber
+        let flags = FontHeaderFlags(s.read().unwrap_or(0));
+        let units_per_em = s.read().unwrap_or(0);
+        let created = s.read().unwrap_or(0);
+        let modified = s.read().unwrap_or(0);
+        let global_bbox = Rect {
+            x_min: s.read().unwrap_or(0),
+            y_min: s.read().unwrap_or(0),
+            x_max: s.read().unwrap_or(0),
+            y_max: s.read().unwrap_or(0),
+        };
+        let mac_style = MacStyle(s.read().unwrap_or(0));
+        let lowest_rec_ppem = s.read().unwrap_or(0);
+        let font_direction_hint = s.read().unwrap_or(0);
+        let index_to_loc_format = s.read().unwrap_or(0);
+        let glyph_data_format = s.read().unwrap_or(0);
+
+        FontHeader {
+            major_version,
+            minor_version,
+            font_revision,
+            checksum_adjustment,
+            flags,
+            units_per_em,
+            created,
+            modified,
+            global_bbox,
+            mac_style,
+            lowest_rec_ppem,
+            font_direction_hint,
+            index_to_loc_format,
+            glyph_data_format,
+        }
+    }
+}
+
+
+/// [Font header flags](https://docs.microsoft.com/en-us/typography/opentype/spec/head).
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct FontHeaderFlags(pub u16);
+
+impl FontHeaderFlags {
+    /// Checks that baseline for font at `y = 0`.
+    #[inline] pub fn is_baseline_at_zero(&self) -> bool { self.0 & (1 << 0) != 0 }
+    /// Checks that left sidebearing point at `x = 0`.
+    ///
+    /// Relevant only for TrueType rasterizers.
+    #[inline] pub fn is_left_side_bearing_at_zero(&self) -> bool { self.0 & (1 << 1) != 0 }
+    /// Checks that instructions may depend on point size.
+    #[inline] pub fn instructions_depend_on_point_size(&self) -> bool { self.0 & (1 << 2) != 0 }
+    /// Checks that ppem should be rounded to an integer for all internal scaler math.
+    #[inline] pub fn is_integer_ppem_forced(&self) -> bool { self.0 & (1 << 3) != 0 }
+    /// Checks that instructions may alter advance width.
+    #[inline] pub fn instructions_may_alter_advance_width(&self) -> bool { self.0 & (1 << 4) != 0 }
+    /// Checks that font data is lossless as a result of having been
+    /// subjected to optimizing transformation and/or compression.
+    #[inline] pub fn is_lossless(&self) -> bool { self.0 & (1 << 11) != 0 }
+    /// Checks that font was converted to produce compatible metrics.
+    #[inline] pub fn is_converted(&self) -> bool { self.0 & (1 << 12) != 0 }
+    /// Checks that font is optimized for ClearType.
+    #[inline] pub fn is_optimized_for_cleartype(&self) -> bool { self.0 & (1 << 13) != 0 }
+    /// Checks that font is a last resort font.
+    #[inline] pub fn is_last_resort(&self) -> bool { self.0 & (1 << 14) != 0 }
+}
+
+
+/// [Mac style](https://docs.microsoft.com/en-us/typography/opentype/spec/head).
+///
+/// Should be in sync with `OS/2` table's `fsSelection`.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct MacStyle(pub u16);
+
+impl MacStyle {
+    /// Checks that the bold bit is set.
+    #[inline] pub fn is_bold(&self) -> bool { self.0 & (1 << 0) != 0 }
+    /// Checks that the italic bit is set.
+    #[inline] pub fn is_italic(&self) -> bool { self.0 & (1 << 1) != 0 }
+    /// Checks that the underline bit is set.
+    #[inline] pub fn is_underline(&self) -> bool { self.0 & (1 << 2) != 0 }
+    /// Checks that the outline bit is set.
+    #[inline] pub fn is_outline(&self) -> bool { self.0 & (1 << 3) != 0 }
+    /// Checks that the shadow bit is set.
+    #[inline] pub fn is_shadow(&self) -> bool { self.0 & (1 << 4) != 0 }
+    /// Checks that the condensed bit is set.
+    #[inline] pub fn is_condensed(&self) -> bool { self.0 & (1 << 5) != 0 }
+    /// Checks that the extended bit is set.
+    #[inline] pub fn is_extended(&self) -> bool { self.0 & (1 << 6) != 0 }
+}
+
+
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub(crate) enum IndexToLocationFormat {
     Short,
@@ -42,3 +176,145 @@ pub(crate) fn index_to_loc_format(data: &[u8]) -> Option<IndexToLocationFormat>
         _ => None,
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+    use super::*;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    fn header_data(units_per_em: u16, index_to_loc_format: i16) -> Vec<u8> {
+        writer::convert(&[
+            UInt16(1), // majorVersion
+            UInt16(0), // minorVersion
+            Int32(0x00018000), // fontRevision
+            UInt32(0x12345678), // checksumAdjustment
+            UInt32(0x5F0F3CF5), // magicNumber
+            UInt16(0x0003), // flags
+            UInt16(units_per_em), // unitsPerEm
+            UInt32(0), UInt32(1), // created
+            Int32(-1), Int32(-2), // modified
+            Int16(-10), // xMin
+            Int16(-20), // yMin
+            Int16(30), // xMax
+            Int16(40), // yMax
+            UInt16(0x0003), // macStyle
+            UInt16(8), // lowestRecPPEM
+            Int16(2), // fontDirectionHint
+            Int16(index_to_loc_format), // indexToLocFormat
+            Int16(0), // glyphDataFormat
+        ])
+    }
+
+    #[test]
+    fn fields() {
+        let data = header_data(1000, 1);
+        assert_eq!(data.len(), TABLE_SIZE);
+        assert!(parse(&data).is_some());
+
+        let header = FontHeader::parse(&data);
+        assert_eq!(header.major_version, 1);
+        assert_eq!(header.minor_version, 0);
+        assert_eq!(header.font_revision, 1.5);
+        assert_eq!(header.checksum_adjustment, 0x12345678);
+        assert_eq!(header.flags, FontHeaderFlags(0x0003));
+        assert_eq!(header.units_per_em, 1000);
+        assert_eq!(header.created, 1);
+        assert_eq!(header.modified, -2);
+        assert_eq!(header.global_bbox, Rect { x_min: -10, y_min: -20, x_max: 30, y_max: 40 });
+        assert_eq!(header.mac_style, MacStyle(0x0003));
+        assert_eq!(header.lowest_rec_ppem, 8);
+        assert_eq!(header.font_direction_hint, 2);
+        assert_eq!(header.index_to_loc_format, 1);
+        assert_eq!(header.glyph_data_format, 0);
+
+        assert_eq!(units_per_em(&data), Some(1000));
+        assert_eq!(index_to_loc_format(&data), Some(IndexToLocationFormat::Long));
+    }
+
+    #[test]
+    fn invalid_size() {
+        let data = header_data(1000, 0);
+        assert!(parse(&data[..TABLE_SIZE - 1]).is_none());
+
+        let mut data = data;
+        data.push(0);
+        assert!(parse(&data).is_none());
+    }
+
+    #[test]
+    fn truncated() {
+        // Missing values are set to zero.
+        let data = header_data(1000, 1);
+        let header = FontHeader::parse(&data[..UNITS_PER_EM_OFFSET + 2]);
+        assert_eq!(header.flags, FontHeaderFlags(0x0003));
+        assert_eq!(header.units_per_em, 1000);
+        assert_eq!(header.created, 0);
+        assert_eq!(header.global_bbox, Rect { x_min: 0, y_min: 0, x_max: 0, y_max: 0 });
+        assert_eq!(header.index_to_loc_format, 0);
+
+        assert_eq!(units_per_em(&data[..UNITS_PER_EM_OFFSET + 1]), None);
+        assert_eq!(index_to_loc_format(&data[..INDEX_TO_LOC_FORMAT_OFFSET + 1]), None);
+    }
+
+    #[test]
+    fn units_per_em_range() {
+        assert_eq!(units_per_em(&header_data(15, 0)), None);
+        assert_eq!(units_per_em(&header_data(16, 0)), Some(16));
+        assert_eq!(units_per_em(&header_data(16384, 0)), Some(16384));
+        assert_eq!(units_per_em(&header_data(16385, 0)), None);
+        // The raw value is still available.
+        assert_eq!(FontHeader::parse(&header_data(16385, 0)).units_per_em, 16385);
+    }
+
+    #[test]
+    fn loc_format() {
+        assert_eq!(index_to_loc_format(&header_data(1000, 0)), Some(IndexToLocationFormat::Short));
+        assert_eq!(index_to_loc_format(&header_data(1000, 1)), Some(IndexToLocationFormat::Long));
+        assert_eq!(index_to_loc_format(&header_data(1000, 2)), None);
+        assert_eq!(index_to_loc_format(&header_data(1000, -1)), None);
+    }
+
+    #[test]
+    fn flags() {
+        let flags = FontHeaderFlags(0);
+        assert!(!flags.is_baseline_at_zero());
+        assert!(!flags.is_last_resort());
+
+        assert!(FontHeaderFlags(1 << 0).is_baseline_at_zero());
+        assert!(FontHeaderFlags(1 << 1).is_left_side_bearing_at_zero());
+        assert!(FontHeaderFlags(1 << 2).instructions_depend_on_point_size());
+        assert!(FontHeaderFlags(1 << 3).is_integer_ppem_forced());
+        assert!(FontHeaderFlags(1 << 4).instructions_may_alter_advance_width());
+        assert!(FontHeaderFlags(1 << 11).is_lossless());
+        assert!(FontHeaderFlags(1 << 12).is_converted());
+        assert!(FontHeaderFlags(1 << 13).is_optimized_for_cleartype());
+        assert!(FontHeaderFlags(1 << 14).is_last_resort());
+
+        // Each bit maps to a single flag.
+        let flags = FontHeaderFlags(1 << 11);
+        assert!(!flags.is_baseline_at_zero());
+        assert!(!flags.instructions_may_alter_advance_width());
+        assert!(!flags.is_converted());
+    }
+
+    #[test]
+    fn mac_style() {
+        assert!(!MacStyle(0).is_bold());
+        assert!(!MacStyle(0).is_italic());
+
+        assert!(MacStyle(1 << 0).is_bold());
+        assert!(MacStyle(1 << 1).is_italic());
+        assert!(MacStyle(1 << 2).is_underline());
+        assert!(MacStyle(1 << 3).is_outline());
+        assert!(MacStyle(1 << 4).is_shadow());
+        assert!(MacStyle(1 << 5).is_condensed());
+        assert!(MacStyle(1 << 6).is_extended());
+
+        let style = MacStyle(1 << 1);
+        assert!(!style.is_bold());
+        assert!(!style.is_underline());
+    }
+}
@@ -12,6 +12,82 @@ const LINE_GAP_OFFSET: usize = 8;
 const NUMBER_OF_H_METRICS_OFFSET: usize = 34;
 
 
+/// A [horizontal header](https://docs.microsoft.com/en-us/typography/opentype/spec/hhea).
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct HorizontalHeader {
+    /// Major version. Should be 1.
+    pub major_version: u16,
+    /// Minor version. Should be 0.
+    pub minor_version: u16,
+    /// Typographic ascent.
+    pub ascender: i16,
+    /// Typographic descent.
+    pub descender: i16,
+    /// Typographic line gap.
+    pub line_gap: i16,
+    /// Maximum advance width value in the `hmtx` table.
+    pub advance_width_max: u16,
+    /// Minimum left side bearing value in the `hmtx` table.
+    pub min_left_side_bearing: i16,
+    /// Minimum right side bearing value.
+    pub min_right_side_bearing: i16,
+    /// Maximum horizontal glyph extent.
+    pub x_max_extent: i16,
+    /// Used to calculate the slope of the cursor. 1 for vertical.
+    pub caret_slope_rise: i16,
+    /// Used to calculate the slope of the cursor. 0 for vertical.
+    pub caret_slope_run: i16,
+    /// The amount by which a slanted highlight on a glyph needs to be shifted
+    /// to produce the best appearance. 0 for non-slanted fonts.
+    pub caret_offset: i16,
+    /// Should be 0.
+    pub metric_data_format: i16,
+    /// Number of long horizontal metrics in the `hmtx` table.
+    pub number_of_h_metrics: u16,
+}
+
+impl HorizontalHeader {
+    /// Parses a horizontal header.
+    ///
+    /// Since `hhea` is validated by `Font::from_data`, missing values are set to zero.
+    pub(crate) fn parse(data: &[u8]) -> Self {
+        let mut s = Stream::new(data);
+        let major_version = s.read().unwrap_or(0);
+        let minor_version = s.read().unwrap_or(0);
+        let ascender = s.read().unwrap_or(0);
+        let descender = s.read().unwrap_or(0);
+        let line_gap = s.read().unwrap_or(0);
+        let advance_width_max = s.read().unwrap_or(0);
+        let min_left_side_bearing = s.read().unwrap_or(0);
+        let min_right_side_bearing = s.read().unwrap_or(0);
+        let x_max_extent = s.read().unwrap_or(0);
+        let caret_slope_rise = s.read().unwrap_or(0);
+        let caret_slope_run = s.read().unwrap_or(0);
+        let caret_offset = s.read().unwrap_or(0);
+        s.advance(8); // reserved
+        let metric_data_format = s.read().unwrap_or(0);
+        let number_of_h_metrics = s.read().unwrap_or(0);
+
+        HorizontalHeader {
+            major_version,
+            minor_version,
+            ascender,
+            descender,
+            line_gap,
+            advance_width_max,
+            min_left_side_bearing,
+            min_right_side_bearing,
+            x_max_extent,
+            caret_slope_rise,
+            caret_slope_run,
+            caret_offset,
+            metric_data_format,
+            number_of_h_metrics,
+        }
+    }
+}
+
+
 #[inline]
 pub fn parse(data: &[u8]) -> Option<&[u8]> {
     if data.len() == TABLE_SIZE {
@@ -40,3 +116,94 @@ pub fn line_gap(data: &[u8]) -> i16 {
 pub fn number_of_h_metrics(data: &[u8]) -> Option<NonZeroU16> {
     Stream::read_at(data, NUMBER_OF_H_METRICS_OFFSET).and_then(NonZeroU16::new)
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    #[test]
+    fn fields() {
+        let data = writer::convert(&[
+            UInt16(1), // majorVersion
+            UInt16(0), // minorVersion
+            Int16(800), // ascender
+            Int16(-200), // descender
+            Int16(90), // lineGap
+            UInt16(1200), // advanceWidthMax
+            Int16(-50), // minLeftSideBearing
+            Int16(-60), // minRightSideBearing
+            Int16(1100), // xMaxExtent
+            Int16(1), // caretSlopeRise
+            Int16(0), // caretSlopeRun
+            Int16(5), // caretOffset
+            Int16(0), Int16(0), Int16(0), Int16(0), // reserved
+            Int16(0), // metricDataFormat
+            UInt16(3), // numberOfHMetrics
+        ]);
+
+        assert_eq!(data.len(), TABLE_SIZE);
+        assert!(parse(&data).is_some());
+
+        let header = HorizontalHeader::parse(&data);
+        assert_eq!(header.major_version, 1);
+        assert_eq!(header.minor_version, 0);
+        assert_eq!(header.ascender, 800);
+        assert_eq!(header.descender, -200);
+        assert_eq!(header.line_gap, 90);
+        assert_eq!(header.advance_width_max, 1200);
+        assert_eq!(header.min_left_side_bearing, -50);
+        assert_eq!(header.min_right_side_bearing, -60);
+        assert_eq!(header.x_max_extent, 1100);
+        assert_eq!(header.caret_slope_rise, 1);
+        assert_eq!(header.caret_slope_run, 0);
+        assert_eq!(header.caret_offset, 5);
+        assert_eq!(header.metric_data_format, 0);
+        assert_eq!(header.number_of_h_metrics, 3);
+
+        assert_eq!(ascender(&data), 800);
+        assert_eq!(descender(&data), -200);
+        assert_eq!(line_gap(&data), 90);
+        assert_eq!(number_of_h_metrics(&data).map(|n| n.get()), Some(3));
+    }
+
+    #[test]
+    fn invalid_size() {
+        let data = [0; TABLE_SIZE + 1];
+        assert!(parse(&data[..TABLE_SIZE]).is_some());
+        assert!(parse(&data[..TABLE_SIZE - 1]).is_none());
+        assert!(parse(&data).is_none());
+    }
+
+    #[test]
+    fn truncated() {
+        // Missing values are set to zero.
+        let data = writer::convert(&[
+            UInt16(1), // majorVersion
+            UInt16(0), // minorVersion
+            Int16(800), // ascender
+            Int16(-200), // descender
+        ]);
+
+        let header = HorizontalHeader::parse(&data);
+        assert_eq!(header.ascender, 800);
+        assert_eq!(header.descender, -200);
+        assert_eq!(header.line_gap, 0);
+        assert_eq!(header.number_of_h_metrics, 0);
+
+        assert_eq!(ascender(&data), 800);
+        assert_eq!(line_gap(&data), 0);
+        assert!(number_of_h_metrics(&data).is_none());
+    }
+
+    #[test]
+    fn zero_h_metrics() {
+        let mut data = [0; TABLE_SIZE];
+        assert!(number_of_h_metrics(&data).is_none());
+
+        data[NUMBER_OF_H_METRICS_OFFSET + 1] = 1;
+        assert_eq!(number_of_h_metrics(&data).map(|n| n.get()), Some(1));
+    }
+}
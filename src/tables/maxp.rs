@@ -4,8 +4,92 @@ use core::num::NonZeroU16;
 
 use crate::parser::Stream;
 
-// We care only about `numGlyphs`.
-pub fn parse(data: &[u8]) -> Option<NonZeroU16> {
+
+/// A [maximum profile](https://docs.microsoft.com/en-us/typography/opentype/spec/maxp).
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct MaximumProfile {
+    /// Number of glyphs.
+    pub number_of_glyphs: NonZeroU16,
+    /// Limits used by TrueType fonts.
+    ///
+    /// Present only in the version 1.0 of the table.
+    pub true_type_limits: Option<TrueTypeLimits>,
+}
+
+impl MaximumProfile {
+    pub(crate) fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        let version: u32 = s.read()?;
+        let number_of_glyphs = NonZeroU16::new(s.read()?)?;
+        let true_type_limits = match version {
+            0x00005000 => None,
+            0x00010000 => TrueTypeLimits::parse(&mut s),
+            _ => return None,
+        };
+
+        Some(MaximumProfile {
+            number_of_glyphs,
+            true_type_limits,
+        })
+    }
+}
+
+
+/// TrueType-specific limits from the
+/// [maximum profile](https://docs.microsoft.com/en-us/typography/opentype/spec/maxp).
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct TrueTypeLimits {
+    /// Maximum points in a non-composite glyph.
+    pub max_points: u16,
+    /// Maximum contours in a non-composite glyph.
+    pub max_contours: u16,
+    /// Maximum points in a composite glyph.
+    pub max_composite_points: u16,
+    /// Maximum contours in a composite glyph.
+    pub max_composite_contours: u16,
+    /// 1 if instructions do not use the twilight zone, 2 otherwise.
+    pub max_zones: u16,
+    /// Maximum points used in the twilight zone.
+    pub max_twilight_points: u16,
+    /// Number of storage area locations.
+    pub max_storage: u16,
+    /// Number of function definitions.
+    pub max_function_defs: u16,
+    /// Number of instruction definitions.
+    pub max_instruction_defs: u16,
+    /// Maximum stack depth across `fpgm`, `prep` and glyph programs.
+    pub max_stack_elements: u16,
+    /// Maximum byte count for glyph instructions.
+    pub max_size_of_instructions: u16,
+    /// Maximum number of components referenced at the top level of any composite glyph.
+    pub max_component_elements: u16,
+    /// Maximum levels of recursion. 1 for simple components.
+    pub max_component_depth: u16,
+}
+
+impl TrueTypeLimits {
+    fn parse(s: &mut Stream) -> Option<Self> {
+        Some(TrueTypeLimits {
+            max_points: s.read()?,
+            max_contours: s.read()?,
+            max_composite_points: s.read()?,
+            max_composite_contours: s.read()?,
+            max_zones: s.read()?,
+            max_twilight_points: s.read()?,
+            max_storage: s.read()?,
+            max_function_defs: s.read()?,
+            max_instruction_defs: s.read()?,
+            max_stack_elements: s.read()?,
+            max_size_of_instructions: s.read()?,
+            max_component_elements: s.read()?,
+            max_component_depth: s.read()?,
+        })
+    }
+}
+
+
+// Used by `Font::from_data`, where we care only about `numGlyphs`.
+pub fn number_of_glyphs(data: &[u8]) -> Option<NonZeroU16> {
     let mut s = Stream::new(data);
     let version: u32 = s.read()?;
     if !(version == 0x00005000 || version == 0x00010000) {
@@ -15,3 +99,61 @@ pub fn parse(data: &[u8]) -> Option<NonZeroU16> {
     let n: u16 = s.read()?;
     NonZeroU16::new(n)
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    #[test]
+    fn version_0_5() {
+        let data = writer::convert(&[
+            UInt32(0x00005000), // version
+            UInt16(1), // numGlyphs
+        ]);
+
+        let table = MaximumProfile::parse(&data).unwrap();
+        assert_eq!(table.number_of_glyphs.get(), 1);
+        assert_eq!(table.true_type_limits, None);
+    }
+
+    #[test]
+    fn version_1_0() {
+        let data = writer::convert(&[
+            UInt32(0x00010000), // version
+            UInt16(1), // numGlyphs
+            UInt16(10), // maxPoints
+            UInt16(2), // maxContours
+            UInt16(0), // maxCompositePoints
+            UInt16(0), // maxCompositeContours
+            UInt16(2), // maxZones
+            UInt16(4), // maxTwilightPoints
+            UInt16(8), // maxStorage
+            UInt16(3), // maxFunctionDefs
+            UInt16(0), // maxInstructionDefs
+            UInt16(64), // maxStackElements
+            UInt16(100), // maxSizeOfInstructions
+            UInt16(0), // maxComponentElements
+            UInt16(0), // maxComponentDepth
+        ]);
+
+        let limits = MaximumProfile::parse(&data).unwrap().true_type_limits.unwrap();
+        assert_eq!(limits.max_points, 10);
+        assert_eq!(limits.max_zones, 2);
+        assert_eq!(limits.max_stack_elements, 64);
+        assert_eq!(limits.max_size_of_instructions, 100);
+    }
+
+    #[test]
+    fn zero_glyphs() {
+        let data = writer::convert(&[
+            UInt32(0x00005000), // version
+            UInt16(0), // numGlyphs
+        ]);
+
+        assert!(MaximumProfile::parse(&data).is_none());
+        assert!(number_of_glyphs(&data).is_none());
+    }
+}
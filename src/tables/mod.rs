@@ -4,6 +4,7 @@ pub mod cblc;
 pub mod cff2;
 pub mod cff;
 pub mod cmap;
+pub mod cvt;
 pub mod fvar;
 pub mod gdef;
 pub mod glyf;
@@ -1,14 +1,10 @@
 // https://docs.microsoft.com/en-us/typography/opentype/spec/name
 
-#[cfg(feature = "std")]
-use std::vec::Vec;
-#[cfg(feature = "std")]
-use std::string::String;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
 
-#[cfg(feature = "std")]
-use crate::parser::LazyArray16;
-
-use crate::parser::{Stream, FromData};
+use crate::{language, mac_encodings};
+use crate::parser::{Stream, FromData, LazyArray16, LazyArrayIter16, Offset16, Offset};
 
 
 /// A list of [name ID](https://docs.microsoft.com/en-us/typography/opentype/spec/name#name-ids)'s.
@@ -120,6 +116,7 @@ impl FromData for NameRecord {
 pub struct Name<'a> {
     data: NameRecord,
     strings: &'a [u8],
+    lang_tags: LazyArray16<'a, LangTagRecord>,
 }
 
 impl<'a> Name<'a> {
@@ -138,6 +135,34 @@ impl<'a> Name<'a> {
         self.data.language_id
     }
 
+    /// Returns a [BCP 47](https://tools.ietf.org/html/bcp47) language tag.
+    ///
+    /// Windows and Macintosh language IDs are mapped to the corresponding tags,
+    /// while the naming table format 1 language-tag records are returned as is.
+    ///
+    /// Returns `None` for an unknown language ID.
+    pub fn language_tag(&self) -> Option<LanguageTag<'a>> {
+        // https://docs.microsoft.com/en-us/typography/opentype/spec/name#naming-table-format-1
+        const LANG_TAG_ID_START: u16 = 0x8000;
+
+        let id = self.language_id();
+        if id >= LANG_TAG_ID_START {
+            let record = self.lang_tags.get(id - LANG_TAG_ID_START)?;
+            let start = usize::from(record.offset);
+            let end = start + usize::from(record.length);
+            let data = self.strings.get(start..end)?;
+            return Some(LanguageTag(LanguageTagInner::Custom(data)));
+        }
+
+        let tag = match self.platform_id()? {
+            PlatformId::Windows => language::windows_language_tag(id)?,
+            PlatformId::Macintosh => language::mac_language_tag(id)?,
+            _ => return None,
+        };
+
+        Some(LanguageTag(LanguageTagInner::Static(tag)))
+    }
+
     /// Parses the [Name ID](https://docs.microsoft.com/en-us/typography/opentype/spec/name#name-ids).
     ///
     /// A predefined list of ID's can be found in the [`name_id`](name_id/index.html) module.
@@ -156,42 +181,85 @@ impl<'a> Name<'a> {
 
     /// Parses the Name's data as a UTF-8 string.
     ///
-    /// Only Unicode names are supported. And since they are stored as UTF-16BE,
+    /// Since names are stored as UTF-16BE or in a legacy Macintosh encoding,
     /// we can't return `&str` and have to allocate a `String`.
+    /// Use [`chars()`] when an allocator is not available.
     ///
     /// Supports:
     /// - Unicode Platform ID
     /// - Windows Platform ID + Symbol
     /// - Windows Platform ID + Unicode BMP
-    #[cfg(feature = "std")]
+    /// - Macintosh Platform ID + Roman, Greek, Russian and Slavic
+    /// - Macintosh Platform ID + Japanese, Traditional Chinese, Korean and Simplified Chinese
+    ///   when the `mac-cjk` feature is enabled
+    ///
+    /// [`chars()`]: #method.chars
+    #[cfg(feature = "alloc")]
     #[inline(never)]
     pub fn name_utf8(&self) -> Option<String> {
         if self.is_unicode() {
             self.name_from_utf16_be()
+        } else if self.platform_id() == Some(PlatformId::Macintosh) {
+            mac_encodings::decode(self.encoding_id(), self.name())
         } else {
             None
         }
     }
 
+    /// Returns an iterator over the Name's characters.
+    ///
+    /// Unlike [`name_utf8()`], doesn't allocate.
+    /// Malformed UTF-16 sequences are replaced with `U+FFFD`.
+    ///
+    /// Supports the same encodings as [`name_utf8()`], except the Macintosh CJK ones.
+    ///
+    /// Returns `None` for an unsupported encoding.
+    ///
+    /// [`name_utf8()`]: #method.name_utf8
+    #[inline]
+    pub fn chars(&self) -> Option<NameChars<'a>> {
+        let inner = if self.is_unicode() {
+            let data = LazyArray16::<u16>::new(self.name());
+            NameCharsInner::Utf16(core::char::decode_utf16(data))
+        } else if self.platform_id() == Some(PlatformId::Macintosh) {
+            let table = mac_encodings::single_byte_table(self.encoding_id())?;
+            NameCharsInner::SingleByte(table, self.name().iter())
+        } else {
+            return None;
+        };
+
+        Some(NameChars(inner))
+    }
+
     /// Checks that the current Name data has a Unicode encoding.
     #[inline]
     pub fn is_unicode(&self) -> bool {
-        is_unicode_encoding(self.platform_id().unwrap(), self.encoding_id())
+        match self.platform_id() {
+            Some(platform_id) => is_unicode_encoding(platform_id, self.encoding_id()),
+            None => false,
+        }
     }
 
-    #[cfg(feature = "std")]
-    #[inline(never)]
-    pub(crate) fn name_from_utf16_be(&self) -> Option<String> {
-        let mut name: Vec<u16> = Vec::new();
-        for c in LazyArray16::<u16>::new(self.name()) {
-            name.push(c);
+    /// Checks that the current Name data can be decoded by [`name_utf8()`].
+    ///
+    /// [`name_utf8()`]: #method.name_utf8
+    #[inline]
+    pub fn is_supported_encoding(&self) -> bool {
+        match self.platform_id() {
+            Some(PlatformId::Macintosh) => mac_encodings::is_supported(self.encoding_id()),
+            _ => self.is_unicode(),
         }
+    }
 
-        String::from_utf16(&name).ok()
+    #[cfg(feature = "alloc")]
+    #[inline(never)]
+    pub(crate) fn name_from_utf16_be(&self) -> Option<String> {
+        let data = LazyArray16::<u16>::new(self.name());
+        core::char::decode_utf16(data).collect::<Result<String, _>>().ok()
     }
 }
 
-#[cfg(feature = "std")]
+#[cfg(feature = "alloc")]
 impl<'a> core::fmt::Debug for Name<'a> {
     fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         // TODO: https://github.com/rust-lang/rust/issues/50264
@@ -208,7 +276,7 @@ impl<'a> core::fmt::Debug for Name<'a> {
     }
 }
 
-#[cfg(not(feature = "std"))]
+#[cfg(not(feature = "alloc"))]
 impl<'a> core::fmt::Debug for Name<'a> {
     fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         f.debug_struct("Name")
@@ -222,12 +290,137 @@ impl<'a> core::fmt::Debug for Name<'a> {
 }
 
 
+#[derive(Clone)]
+enum NameCharsInner<'a> {
+    Utf16(core::char::DecodeUtf16<LazyArrayIter16<'a, u16>>),
+    SingleByte(&'static mac_encodings::SingleByteTable, core::slice::Iter<'a, u8>),
+}
+
+/// An iterator over Name's characters.
+#[derive(Clone)]
+#[allow(missing_debug_implementations)]
+pub struct NameChars<'a>(NameCharsInner<'a>);
+
+impl<'a> Iterator for NameChars<'a> {
+    type Item = char;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.0 {
+            NameCharsInner::Utf16(ref mut iter) => {
+                iter.next().map(|c| c.unwrap_or(core::char::REPLACEMENT_CHARACTER))
+            }
+            NameCharsInner::SingleByte(table, ref mut iter) => {
+                iter.next().map(|b| mac_encodings::decode_single_byte(table, *b))
+            }
+        }
+    }
+}
+
+
+#[derive(Clone, Copy)]
+struct LangTagRecord {
+    length: u16,
+    offset: u16,
+}
+
+impl FromData for LangTagRecord {
+    const SIZE: usize = 4;
+
+    #[inline]
+    fn parse(data: &[u8]) -> Option<Self> {
+        let mut s = Stream::new(data);
+        Some(LangTagRecord {
+            length: s.read()?,
+            offset: s.read()?,
+        })
+    }
+}
+
+
+#[derive(Clone, Copy)]
+enum LanguageTagInner<'a> {
+    Static(&'static str),
+    // UTF-16BE string from the storage.
+    Custom(&'a [u8]),
+}
+
+/// A [BCP 47](https://tools.ietf.org/html/bcp47) language tag.
+#[derive(Clone, Copy)]
+pub struct LanguageTag<'a>(LanguageTagInner<'a>);
+
+impl<'a> LanguageTag<'a> {
+    fn chars(&self) -> impl Iterator<Item = char> + 'a {
+        let (s, data) = match self.0 {
+            LanguageTagInner::Static(s) => (s, &[][..]),
+            LanguageTagInner::Custom(data) => ("", data),
+        };
+
+        let utf16 = core::char::decode_utf16(LazyArray16::<u16>::new(data))
+            .map(|c| c.unwrap_or(core::char::REPLACEMENT_CHARACTER));
+        s.chars().chain(utf16)
+    }
+
+    /// Checks that tag is equal to the provided one, ignoring ASCII case.
+    pub fn eq_ignore_ascii_case(&self, tag: &str) -> bool {
+        let mut chars = self.chars();
+        for c in tag.chars() {
+            match chars.next() {
+                Some(c2) if c2.eq_ignore_ascii_case(&c) => {}
+                _ => return false,
+            }
+        }
+
+        chars.next().is_none()
+    }
+
+    /// Checks that tag matches the provided language range.
+    ///
+    /// Implements the [Basic Filtering](https://tools.ietf.org/html/rfc4647#section-3.3.1)
+    /// scheme. So `zh-Hant-TW` matches `zh-Hant` and `zh`, but not `zh-Hans`.
+    pub fn matches(&self, range: &str) -> bool {
+        let mut chars = self.chars();
+        for c in range.chars() {
+            match chars.next() {
+                Some(c2) if c2.eq_ignore_ascii_case(&c) => {}
+                _ => return false,
+            }
+        }
+
+        // A range must end at a subtag boundary.
+        match chars.next() {
+            Some(c) => c == '-',
+            None => true,
+        }
+    }
+}
+
+impl core::fmt::Debug for LanguageTag<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "LanguageTag({})", self)
+    }
+}
+
+impl core::fmt::Display for LanguageTag<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        use core::fmt::Write;
+
+        for c in self.chars() {
+            f.write_char(c)?;
+        }
+
+        Ok(())
+    }
+}
+
+
 /// An iterator over font's names.
 #[derive(Clone, Copy)]
 #[allow(missing_debug_implementations)]
 pub struct Names<'a> {
     names: &'a [u8],
     storage: &'a [u8],
+    lang_tags: LazyArray16<'a, LangTagRecord>,
     index: u16,
     total: u16,
 }
@@ -237,6 +430,7 @@ impl Default for Names<'_> {
         Names {
             names: &[],
             storage: &[],
+            lang_tags: LazyArray16::default(),
             index: 0,
             total: 0,
         }
@@ -244,10 +438,16 @@ impl Default for Names<'_> {
 }
 
 impl<'a> Names<'a> {
-    fn new(names: &'a [u8], storage: &'a [u8], total: u16) -> Self {
+    fn new(
+        names: &'a [u8],
+        storage: &'a [u8],
+        lang_tags: LazyArray16<'a, LangTagRecord>,
+        total: u16,
+    ) -> Self {
         Names {
             names,
             storage,
+            lang_tags,
             index: 0,
             total,
         }
@@ -274,6 +474,7 @@ impl<'a> Iterator for Names<'a> {
         Some(Name {
             data: Stream::read_at(self.names, NameRecord::SIZE * n)?,
             strings: self.storage,
+            lang_tags: self.lang_tags,
         })
     }
 }
@@ -281,25 +482,137 @@ impl<'a> Iterator for Names<'a> {
 
 #[inline(never)]
 pub(crate) fn parse(data: &[u8]) -> Option<Names> {
-    // https://docs.microsoft.com/en-us/typography/opentype/spec/name#naming-table-format-1
-    const LANG_TAG_RECORD_SIZE: u16 = 4;
-
     let mut s = Stream::new(data);
     let format: u16 = s.read()?;
     let count: u16 = s.read()?;
-    s.skip::<u16>(); // offset
-
-    if format == 0 {
-        let names_data = s.read_bytes(NameRecord::SIZE * usize::from(count))?;
-        Some(Names::new(names_data, s.tail()?, count))
-    } else if format == 1 {
-        let lang_tag_count: u16 = s.read()?;
-        let lang_tag_len = lang_tag_count.checked_mul(LANG_TAG_RECORD_SIZE)?;
-
-        s.advance(usize::from(lang_tag_len)); // langTagRecords
-        let names_data = s.read_bytes(NameRecord::SIZE * usize::from(count))?;
-        Some(Names::new(names_data, s.tail()?, count))
-    } else {
-        None
+    let storage_offset: Offset16 = s.read()?;
+    let names_data = s.read_bytes(NameRecord::SIZE * usize::from(count))?;
+
+    let lang_tags = match format {
+        0 => LazyArray16::default(),
+        // https://docs.microsoft.com/en-us/typography/opentype/spec/name#naming-table-format-1
+        1 => {
+            let lang_tag_count: u16 = s.read()?;
+            s.read_array16(lang_tag_count)?
+        }
+        _ => return None,
+    };
+
+    let storage = data.get(storage_offset.to_usize()..)?;
+    Some(Names::new(names_data, storage, lang_tags, count))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::string::ToString;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    #[test]
+    fn format_1_language_tags() {
+        let data = writer::convert(&[
+            UInt16(1), // version
+            UInt16(2), // count
+            UInt16(36), // storageOffset
+            // Name Record [0]
+            UInt16(3), // platformID
+            UInt16(1), // encodingID
+            UInt16(0x0411), // languageID
+            UInt16(1), // nameID
+            UInt16(2), // length
+            UInt16(0), // offset
+            // Name Record [1]
+            UInt16(0), // platformID
+            UInt16(4), // encodingID
+            UInt16(0x8000), // languageID
+            UInt16(1), // nameID
+            UInt16(2), // length
+            UInt16(2), // offset
+            UInt16(1), // langTagCount
+            // Lang Tag Record [0]
+            UInt16(12), // length
+            UInt16(4), // offset
+            // Storage
+            UInt16(0x660E), // 明
+            UInt16(0x0041), // A
+            Raw(&[0, b'z', 0, b'h', 0, b'-', 0, b'H', 0, b'a', 0, b'n']),
+        ]);
+
+        let mut names = parse(&data).unwrap();
+
+        let name = names.next().unwrap();
+        let tag = name.language_tag().unwrap();
+        assert!(tag.eq_ignore_ascii_case("JA"));
+        assert!(tag.matches("ja"));
+        assert!(!tag.matches("j"));
+        assert_eq!(name.name_utf8().unwrap(), "明");
+
+        let name = names.next().unwrap();
+        let tag = name.language_tag().unwrap();
+        assert_eq!(tag.to_string(), "zh-Han");
+        assert!(tag.matches("zh"));
+        assert!(!tag.matches("zh-Hant"));
+        assert_eq!(name.name_utf8().unwrap(), "A");
+
+        assert!(names.next().is_none());
+    }
+
+    #[test]
+    fn chars() {
+        let data = writer::convert(&[
+            UInt16(0), // version
+            UInt16(2), // count
+            UInt16(30), // storageOffset
+            // Name Record [0]
+            UInt16(3), // platformID
+            UInt16(1), // encodingID
+            UInt16(0x0409), // languageID
+            UInt16(1), // nameID
+            UInt16(6), // length
+            UInt16(0), // offset
+            // Name Record [1]
+            UInt16(1), // platformID
+            UInt16(0), // encodingID
+            UInt16(0), // languageID
+            UInt16(1), // nameID
+            UInt16(3), // length
+            UInt16(6), // offset
+            // Storage
+            UInt16(0x0041), // A
+            UInt16(0xD800), // unpaired surrogate
+            UInt16(0x0042), // B
+            Raw(b"C\x8Ex"),
+        ]);
+
+        let mut names = parse(&data).unwrap();
+
+        let name = names.next().unwrap();
+        assert!(name.chars().unwrap().eq("A\u{FFFD}B".chars()));
+        assert!(name.name_utf8().is_none());
+
+        let name = names.next().unwrap();
+        assert!(name.chars().unwrap().eq("Céx".chars()));
+        assert_eq!(name.name_utf8().unwrap(), "Céx");
+    }
+
+    #[test]
+    fn unknown_language_id() {
+        let data = writer::convert(&[
+            UInt16(0), // version
+            UInt16(1), // count
+            UInt16(18), // storageOffset
+            // Name Record [0]
+            UInt16(3), // platformID
+            UInt16(1), // encodingID
+            UInt16(0x7FFF), // languageID
+            UInt16(1), // nameID
+            UInt16(0), // length
+            UInt16(0), // offset
+        ]);
+
+        let name = parse(&data).unwrap().next().unwrap();
+        assert!(name.language_tag().is_none());
     }
 }
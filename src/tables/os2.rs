@@ -1,20 +1,32 @@
 // https://docs.microsoft.com/en-us/typography/opentype/spec/os2
 
-use crate::LineMetrics;
+use crate::{LineMetrics, Tag};
 use crate::parser::Stream;
 
 
 const US_WEIGHT_CLASS_OFFSET: usize = 4;
 const US_WIDTH_CLASS_OFFSET: usize = 6;
+const FS_TYPE_OFFSET: usize = 8;
 const Y_SUBSCRIPT_X_SIZE_OFFSET: usize = 10;
 const Y_SUPERSCRIPT_X_SIZE_OFFSET: usize = 18;
 const Y_STRIKEOUT_SIZE_OFFSET: usize = 26;
 const Y_STRIKEOUT_POSITION_OFFSET: usize = 28;
+const PANOSE_OFFSET: usize = 32;
+const UL_UNICODE_RANGE_1_OFFSET: usize = 42;
+const ACH_VEND_ID_OFFSET: usize = 58;
 const FS_SELECTION_OFFSET: usize = 62;
 const S_TYPO_ASCENDER_OFFSET: usize = 68;
 const S_TYPO_DESCENDER_OFFSET: usize = 70;
 const S_TYPO_LINE_GAP_OFFSET: usize = 72;
+const US_WIN_ASCENT_OFFSET: usize = 74;
+const US_WIN_DESCENT_OFFSET: usize = 76;
+const UL_CODE_PAGE_RANGE_1_OFFSET: usize = 78;
 const SX_HEIGHT_OFFSET: usize = 86;
+const S_CAP_HEIGHT_OFFSET: usize = 88;
+const US_DEFAULT_CHAR_OFFSET: usize = 90;
+const US_BREAK_CHAR_OFFSET: usize = 92;
+const US_LOWER_OPTICAL_POINT_SIZE_OFFSET: usize = 96;
+const US_UPPER_OPTICAL_POINT_SIZE_OFFSET: usize = 98;
 
 
 /// A font [weight](https://docs.microsoft.com/en-us/typography/opentype/spec/os2#usweightclass).
@@ -137,6 +149,87 @@ pub struct ScriptMetrics {
 }
 
 
+/// A font [embedding permissions](https://docs.microsoft.com/en-us/typography/opentype/spec/os2#fstype).
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Permissions {
+    /// The font may be embedded and permanently installed on the remote system.
+    Installable,
+    /// The font must not be embedded without an explicit permission from the legal owner.
+    Restricted,
+    /// The font may be embedded, but only for preview and printing.
+    PreviewAndPrint,
+    /// The font may be embedded and temporarily loaded for editing.
+    Editable,
+}
+
+
+/// A [PANOSE](https://docs.microsoft.com/en-us/typography/opentype/spec/os2#panose)
+/// classification.
+///
+/// The meaning of each value depends on `family_type`.
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[allow(missing_docs)]
+pub struct Panose {
+    pub family_type: u8,
+    pub serif_style: u8,
+    pub weight: u8,
+    pub proportion: u8,
+    pub contrast: u8,
+    pub stroke_variation: u8,
+    pub arm_style: u8,
+    pub letterform: u8,
+    pub midline: u8,
+    pub x_height: u8,
+}
+
+
+/// [Unicode ranges](https://docs.microsoft.com/en-us/typography/opentype/spec/os2#ur)
+/// supported by the font.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct UnicodeRanges(pub u128);
+
+impl UnicodeRanges {
+    /// Checks that a specified range bit is set.
+    ///
+    /// Bit numbers are listed in the
+    /// [spec](https://docs.microsoft.com/en-us/typography/opentype/spec/os2#ur).
+    /// Returns `false` for bits >= 128.
+    #[inline]
+    pub fn contains(&self, bit: u32) -> bool {
+        bit < 128 && self.0 & (1 << bit) != 0
+    }
+}
+
+
+/// [Code page character ranges](https://docs.microsoft.com/en-us/typography/opentype/spec/os2#cpr)
+/// supported by the font.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct CodePageRanges(pub u64);
+
+impl CodePageRanges {
+    /// Checks that a specified code page bit is set.
+    ///
+    /// Bit numbers are listed in the
+    /// [spec](https://docs.microsoft.com/en-us/typography/opentype/spec/os2#cpr).
+    /// Returns `false` for bits >= 64.
+    #[inline]
+    pub fn contains(&self, bit: u32) -> bool {
+        bit < 64 && self.0 & (1 << bit) != 0
+    }
+}
+
+
+// https://docs.microsoft.com/en-us/typography/opentype/spec/os2#fstype
+#[derive(Clone, Copy)]
+struct TypeFlags(u16);
+
+impl TypeFlags {
+    #[inline] fn no_subsetting(self) -> bool { self.0 & (1 << 8) != 0 }
+    #[inline] fn bitmap_embedding_only(self) -> bool { self.0 & (1 << 9) != 0 }
+}
+
+
 // https://docs.microsoft.com/en-us/typography/opentype/spec/os2#fsselection
 #[derive(Clone, Copy)]
 struct SelectionFlags(u16);
@@ -171,7 +264,8 @@ impl<'a> Table<'a> {
             _ => return None,
         };
 
-        if data.len() != table_len {
+        // Some fonts have a padding at the end of the table, so do not check for equality.
+        if data.len() < table_len {
             return None;
         }
 
@@ -202,6 +296,99 @@ impl<'a> Table<'a> {
         }
     }
 
+    #[inline]
+    pub fn permissions(&self) -> Option<Permissions> {
+        let n: u16 = Stream::read_at(self.data, FS_TYPE_OFFSET)?;
+        if self.version <= 2 {
+            // Versions 0..=2 allow setting multiple bits. Pick the least restrictive one.
+            // Bit 0 is reserved and must be ignored.
+            let n = n & 0x000E;
+            if n == 0 {
+                Some(Permissions::Installable)
+            } else if n & 8 != 0 {
+                Some(Permissions::Editable)
+            } else if n & 4 != 0 {
+                Some(Permissions::PreviewAndPrint)
+            } else {
+                Some(Permissions::Restricted)
+            }
+        } else {
+            match n & 0xF {
+                0 => Some(Permissions::Installable),
+                2 => Some(Permissions::Restricted),
+                4 => Some(Permissions::PreviewAndPrint),
+                8 => Some(Permissions::Editable),
+                _ => None,
+            }
+        }
+    }
+
+    #[inline]
+    pub fn is_subsetting_allowed(&self) -> bool {
+        if self.version <= 1 {
+            // Flag introduced in version 2.
+            true
+        } else {
+            !TypeFlags(self.fs_type()).no_subsetting()
+        }
+    }
+
+    #[inline]
+    pub fn is_bitmap_embedding_only(&self) -> bool {
+        if self.version <= 1 {
+            // Flag introduced in version 2.
+            false
+        } else {
+            TypeFlags(self.fs_type()).bitmap_embedding_only()
+        }
+    }
+
+    #[inline]
+    pub fn panose(&self) -> Panose {
+        let mut s = Stream::new_at(self.data, PANOSE_OFFSET).unwrap_or_default();
+        Panose {
+            family_type: s.read().unwrap_or(0),
+            serif_style: s.read().unwrap_or(0),
+            weight: s.read().unwrap_or(0),
+            proportion: s.read().unwrap_or(0),
+            contrast: s.read().unwrap_or(0),
+            stroke_variation: s.read().unwrap_or(0),
+            arm_style: s.read().unwrap_or(0),
+            letterform: s.read().unwrap_or(0),
+            midline: s.read().unwrap_or(0),
+            x_height: s.read().unwrap_or(0),
+        }
+    }
+
+    #[inline]
+    pub fn unicode_ranges(&self) -> UnicodeRanges {
+        let mut s = Stream::new_at(self.data, UL_UNICODE_RANGE_1_OFFSET).unwrap_or_default();
+        let t1: u32 = s.read().unwrap_or(0);
+        let t2: u32 = s.read().unwrap_or(0);
+        let t3: u32 = s.read().unwrap_or(0);
+        let t4: u32 = s.read().unwrap_or(0);
+        UnicodeRanges(
+            u128::from(t4) << 96 | u128::from(t3) << 64 | u128::from(t2) << 32 | u128::from(t1)
+        )
+    }
+
+    #[inline]
+    pub fn code_page_ranges(&self) -> Option<CodePageRanges> {
+        if self.version < 1 {
+            return None;
+        }
+
+        let mut s = Stream::new_at(self.data, UL_CODE_PAGE_RANGE_1_OFFSET)?;
+        let t1: u32 = s.read()?;
+        let t2: u32 = s.read()?;
+        Some(CodePageRanges(u64::from(t2) << 32 | u64::from(t1)))
+    }
+
+    #[inline]
+    pub fn vendor_id(&self) -> Tag {
+        Stream::read_at(self.data, ACH_VEND_ID_OFFSET).unwrap_or(Tag(0))
+    }
+
     #[inline]
     pub fn is_regular(&self) -> bool {
         SelectionFlags(self.fs_selection()).regular()
@@ -227,7 +414,7 @@ impl<'a> Table<'a> {
     }
 
     #[inline]
-    pub(crate) fn is_use_typo_metrics(&self) -> bool {
+    pub fn is_use_typo_metrics(&self) -> bool {
         if self.version < 4 {
             false
         } else {
@@ -245,6 +432,44 @@ impl<'a> Table<'a> {
         }
     }
 
+    #[inline]
+    pub fn capital_height(&self) -> Option<i16> {
+        if self.version < 2 {
+            None
+        } else {
+            Stream::read_at(self.data, S_CAP_HEIGHT_OFFSET)
+        }
+    }
+
+    #[inline]
+    pub fn default_char(&self) -> Option<u16> {
+        if self.version < 2 {
+            None
+        } else {
+            Stream::read_at(self.data, US_DEFAULT_CHAR_OFFSET)
+        }
+    }
+
+    #[inline]
+    pub fn break_char(&self) -> Option<u16> {
+        if self.version < 2 {
+            None
+        } else {
+            Stream::read_at(self.data, US_BREAK_CHAR_OFFSET)
+        }
+    }
+
+    #[inline]
+    pub fn optical_point_size_range(&self) -> Option<(u16, u16)> {
+        if self.version < 5 {
+            None
+        } else {
+            let lower = Stream::read_at(self.data, US_LOWER_OPTICAL_POINT_SIZE_OFFSET)?;
+            let upper = Stream::read_at(self.data, US_UPPER_OPTICAL_POINT_SIZE_OFFSET)?;
+            Some((lower, upper))
+        }
+    }
+
     #[inline]
     pub fn strikeout_metrics(&self) -> LineMetrics {
         LineMetrics {
@@ -290,8 +515,166 @@ impl<'a> Table<'a> {
         Stream::read_at(self.data, S_TYPO_LINE_GAP_OFFSET).unwrap_or(0)
     }
 
+    #[inline]
+    pub fn windows_ascent(&self) -> u16 {
+        Stream::read_at(self.data, US_WIN_ASCENT_OFFSET).unwrap_or(0)
+    }
+
+    #[inline]
+    pub fn windows_descent(&self) -> u16 {
+        Stream::read_at(self.data, US_WIN_DESCENT_OFFSET).unwrap_or(0)
+    }
+
+    #[inline]
+    fn fs_type(&self) -> u16 {
+        Stream::read_at(self.data, FS_TYPE_OFFSET).unwrap_or(0)
+    }
+
     #[inline]
     fn fs_selection(&self) -> u16 {
         Stream::read_at(self.data, FS_SELECTION_OFFSET).unwrap_or(0)
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+    use super::*;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    fn permissions(version: u16, fs_type: u16) -> Option<Permissions> {
+        let data = writer::os2(version, fs_type, 0, (0, 0, 0), (0, 0));
+        Table::parse(&data).unwrap().permissions()
+    }
+
+    #[test]
+    fn permissions_multiple_bits() {
+        assert_eq!(permissions(2, 0x0003), Some(Permissions::Restricted));
+        assert_eq!(permissions(2, 0x000E), Some(Permissions::Editable));
+        assert_eq!(permissions(2, 0x0006), Some(Permissions::PreviewAndPrint));
+        assert_eq!(permissions(0, 0x000C), Some(Permissions::Editable));
+    }
+
+    #[test]
+    fn permissions_reserved_bit() {
+        assert_eq!(permissions(2, 0x0001), Some(Permissions::Installable));
+        assert_eq!(permissions(2, 0x0009), Some(Permissions::Editable));
+    }
+
+    #[test]
+    fn permissions_exclusive_bits() {
+        assert_eq!(permissions(3, 0x0000), Some(Permissions::Installable));
+        assert_eq!(permissions(3, 0x0002), Some(Permissions::Restricted));
+        assert_eq!(permissions(3, 0x0004), Some(Permissions::PreviewAndPrint));
+        assert_eq!(permissions(3, 0x0008), Some(Permissions::Editable));
+        assert_eq!(permissions(3, 0x0003), None);
+        assert_eq!(permissions(4, 0x000C), None);
+    }
+
+    fn os2(version: u16) -> Vec<u8> {
+        writer::os2(version, 0, 0, (0, 0, 0), (0, 0))
+    }
+
+    #[test]
+    fn panose() {
+        let mut data = os2(0);
+        data[PANOSE_OFFSET..PANOSE_OFFSET + 10].copy_from_slice(&[2, 11, 6, 4, 5, 6, 7, 8, 9, 10]);
+
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(table.panose(), Panose {
+            family_type: 2,
+            serif_style: 11,
+            weight: 6,
+            proportion: 4,
+            contrast: 5,
+            stroke_variation: 6,
+            arm_style: 7,
+            letterform: 8,
+            midline: 9,
+            x_height: 10,
+        });
+    }
+
+    #[test]
+    fn unicode_ranges() {
+        // ulUnicodeRange1 bit 0, ulUnicodeRange2 bit 1, ulUnicodeRange3 bit 2 and ulUnicodeRange4 bit 31.
+        let mut data = os2(0);
+        let offset = UL_UNICODE_RANGE_1_OFFSET;
+        data[offset..offset + 16].copy_from_slice(&writer::convert(&[
+            UInt32(1 << 0),
+            UInt32(1 << 1),
+            UInt32(1 << 2),
+            UInt32(1 << 31),
+        ]));
+
+        let ranges = Table::parse(&data).unwrap().unicode_ranges();
+        assert_eq!(ranges, UnicodeRanges(1 | 1 << 33 | 1 << 66 | 1 << 127));
+        assert!(ranges.contains(0));
+        assert!(ranges.contains(33));
+        assert!(ranges.contains(66));
+        assert!(ranges.contains(127));
+        assert!(!ranges.contains(1));
+        assert!(!ranges.contains(32));
+        assert!(!ranges.contains(128));
+    }
+
+    #[test]
+    fn code_page_ranges() {
+        // ulCodePageRange1 bit 0 and ulCodePageRange2 bit 31.
+        let mut data = os2(1);
+        let offset = UL_CODE_PAGE_RANGE_1_OFFSET;
+        data[offset..offset + 8].copy_from_slice(&writer::convert(&[
+            UInt32(1 << 0),
+            UInt32(1 << 31),
+        ]));
+
+        let ranges = Table::parse(&data).unwrap().code_page_ranges().unwrap();
+        assert_eq!(ranges, CodePageRanges(1 | 1 << 63));
+        assert!(ranges.contains(0));
+        assert!(ranges.contains(63));
+        assert!(!ranges.contains(31));
+        assert!(!ranges.contains(32));
+        assert!(!ranges.contains(64));
+    }
+
+    #[test]
+    fn code_page_ranges_version_0() {
+        // Version 0 has no code page ranges, even when the table is padded.
+        let mut data = os2(0);
+        data.resize(UL_CODE_PAGE_RANGE_1_OFFSET + 8, 0xFF);
+        assert_eq!(Table::parse(&data).unwrap().code_page_ranges(), None);
+    }
+
+    #[test]
+    fn vendor_id() {
+        let mut data = os2(0);
+        data[ACH_VEND_ID_OFFSET..ACH_VEND_ID_OFFSET + 4].copy_from_slice(b"ADBE");
+        assert_eq!(Table::parse(&data).unwrap().vendor_id(), Tag::from_bytes(b"ADBE"));
+    }
+
+    #[test]
+    fn optical_point_size_range() {
+        let mut data = os2(5);
+        let offset = US_LOWER_OPTICAL_POINT_SIZE_OFFSET;
+        data[offset..offset + 4].copy_from_slice(&writer::convert(&[
+            UInt16(160), // 8pt
+            UInt16(1440), // 72pt
+        ]));
+        assert_eq!(Table::parse(&data).unwrap().optical_point_size_range(), Some((160, 1440)));
+
+        // Only version 5 has an optical size range.
+        data[0..2].copy_from_slice(&[0, 4]);
+        assert_eq!(Table::parse(&data).unwrap().optical_point_size_range(), None);
+    }
+
+    #[test]
+    fn truncated() {
+        for &version in &[0, 1, 4, 5] {
+            let data = os2(version);
+            assert!(Table::parse(&data).is_some());
+            assert!(Table::parse(&data[..data.len() - 1]).is_none());
+        }
+    }
+}
@@ -1,12 +1,10 @@
 // https://docs.microsoft.com/en-us/typography/opentype/spec/post
 
 use crate::{LineMetrics, GlyphId};
-use crate::parser::{Stream, LazyArray16};
+use crate::parser::{Stream, LazyArray16, Fixed};
 
 
 const TABLE_SIZE: usize = 32;
-const UNDERLINE_POSITION_OFFSET: usize = 8;
-const UNDERLINE_THICKNESS_OFFSET: usize = 10;
 
 // https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6post.html
 const MACINTOSH_NAMES: &[&str] = &[
@@ -271,9 +269,33 @@ const MACINTOSH_NAMES: &[&str] = &[
 ];
 
 
+/// A [PostScript header](https://docs.microsoft.com/en-us/typography/opentype/spec/post).
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PostScriptHeader {
+    /// Raw table version. 0x00010000 for version 1.0, 0x00025000 for version 2.5, etc.
+    pub version: u32,
+    /// Italic angle in counter-clockwise degrees from the vertical.
+    ///
+    /// Negative for fonts that lean to the right.
+    pub italic_angle: f32,
+    /// Underline metrics.
+    pub underline: LineMetrics,
+    /// Indicates that the font is monospaced.
+    pub is_fixed_pitch: bool,
+    /// Minimum memory usage when a font is downloaded as a Type 42 font.
+    pub min_mem_type42: u32,
+    /// Maximum memory usage when a font is downloaded as a Type 42 font.
+    pub max_mem_type42: u32,
+    /// Minimum memory usage when a font is downloaded as a Type 1 font.
+    pub min_mem_type1: u32,
+    /// Maximum memory usage when a font is downloaded as a Type 1 font.
+    pub max_mem_type1: u32,
+}
+
+
 #[derive(Clone, Copy)]
 pub struct Table<'a> {
-    underline: LineMetrics,
+    header: PostScriptHeader,
     name_indexes: LazyArray16<'a, u16>,
     names: &'a [u8],
 }
@@ -292,9 +314,22 @@ impl<'a> Table<'a> {
             return None;
         }
 
+        let mut s = Stream::new_at(data, 4)?;
+        let italic_angle = s.read::<Fixed>()?.0;
         let underline = LineMetrics {
-            position: Stream::read_at(data, UNDERLINE_POSITION_OFFSET)?,
-            thickness: Stream::read_at(data, UNDERLINE_THICKNESS_OFFSET)?,
+            position: s.read()?,
+            thickness: s.read()?,
+        };
+        let is_fixed_pitch = s.read::<u32>()? != 0;
+        let header = PostScriptHeader {
+            version,
+            italic_angle,
+            underline,
+            is_fixed_pitch,
+            min_mem_type42: s.read()?,
+            max_mem_type42: s.read()?,
+            min_mem_type1: s.read()?,
+            max_mem_type1: s.read()?,
         };
 
         let mut name_indexes = LazyArray16::default();
@@ -309,15 +344,20 @@ impl<'a> Table<'a> {
         }
 
         Some(Table {
-            underline,
+            header,
             name_indexes,
             names,
         })
     }
 
+    #[inline]
+    pub fn header(&self) -> PostScriptHeader {
+        self.header
+    }
+
     #[inline]
     pub fn underline_metrics(&self) -> LineMetrics {
-        self.underline
+        self.header.underline
     }
 
     #[inline]
@@ -357,3 +397,127 @@ impl<'a> Table<'a> {
         }
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+    use super::*;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    fn header_data(version: u32) -> Vec<u8> {
+        writer::convert(&[
+            UInt32(version), // version
+            Int32(-0x000C8000), // italicAngle
+            Int16(-75), // underlinePosition
+            Int16(50), // underlineThickness
+            UInt32(1), // isFixedPitch
+            UInt32(10), // minMemType42
+            UInt32(20), // maxMemType42
+            UInt32(30), // minMemType1
+            UInt32(40), // maxMemType1
+        ])
+    }
+
+    #[test]
+    fn fields() {
+        let data = header_data(0x00030000);
+        assert_eq!(data.len(), TABLE_SIZE);
+
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(table.header(), PostScriptHeader {
+            version: 0x00030000,
+            italic_angle: -12.5,
+            underline: LineMetrics { position: -75, thickness: 50 },
+            is_fixed_pitch: true,
+            min_mem_type42: 10,
+            max_mem_type42: 20,
+            min_mem_type1: 30,
+            max_mem_type1: 40,
+        });
+        assert_eq!(table.underline_metrics(), LineMetrics { position: -75, thickness: 50 });
+        // Version 3.0 has no glyph names.
+        assert_eq!(table.glyph_name(GlyphId(0)), None);
+    }
+
+    #[test]
+    fn fixed_pitch() {
+        // Any non-zero value indicates a monospaced font.
+        let mut data = header_data(0x00010000);
+        data[12..16].copy_from_slice(&[0, 0, 0, 0]);
+        assert!(!Table::parse(&data).unwrap().header().is_fixed_pitch);
+
+        data[12..16].copy_from_slice(&[0, 1, 0, 0]);
+        assert!(Table::parse(&data).unwrap().header().is_fixed_pitch);
+    }
+
+    #[test]
+    fn versions() {
+        for &version in &[0x00010000, 0x00020000, 0x00025000, 0x00030000, 0x00040000] {
+            let mut data = header_data(version);
+            if version == 0x00020000 {
+                data.extend_from_slice(&[0, 0]); // numGlyphs
+            }
+
+            assert!(Table::parse(&data).is_some());
+        }
+
+        assert!(Table::parse(&header_data(0)).is_none());
+        assert!(Table::parse(&header_data(0x00020001)).is_none());
+        assert!(Table::parse(&header_data(0x00050000)).is_none());
+    }
+
+    #[test]
+    fn truncated() {
+        let data = header_data(0x00010000);
+        assert!(Table::parse(&data[..TABLE_SIZE - 1]).is_none());
+
+        // Version 2.0 requires a glyph names count.
+        assert!(Table::parse(&header_data(0x00020000)).is_none());
+
+        // Not enough name indexes.
+        let mut data = header_data(0x00020000);
+        data.extend_from_slice(&writer::convert(&[UInt16(2), UInt16(0)]));
+        assert!(Table::parse(&data).is_none());
+    }
+
+    #[test]
+    fn glyph_names() {
+        let mut data = header_data(0x00020000);
+        data.extend_from_slice(&writer::convert(&[
+            UInt16(5), // numGlyphs
+            UInt16(0), // glyphNameIndex[0]
+            UInt16(257), // glyphNameIndex[1]
+            UInt16(258), // glyphNameIndex[2]
+            UInt16(259), // glyphNameIndex[3]
+            UInt16(261), // glyphNameIndex[4]
+            // stringData
+            UInt8(3), Raw(b"foo"),
+            UInt8(0),
+        ]));
+
+        let table = Table::parse(&data).unwrap();
+        // Standard Macintosh names.
+        assert_eq!(table.glyph_name(GlyphId(0)), Some(".notdef"));
+        assert_eq!(table.glyph_name(GlyphId(1)), Some("dcroat"));
+        // Custom names.
+        assert_eq!(table.glyph_name(GlyphId(2)), Some("foo"));
+        // Empty name.
+        assert_eq!(table.glyph_name(GlyphId(3)), None);
+        // Out of bounds name index.
+        assert_eq!(table.glyph_name(GlyphId(4)), None);
+        // Out of bounds glyph.
+        assert_eq!(table.glyph_name(GlyphId(5)), None);
+    }
+
+    #[test]
+    fn version_1_has_no_names() {
+        // Trailing data is ignored by versions other than 2.0.
+        let mut data = header_data(0x00010000);
+        data.extend_from_slice(&writer::convert(&[UInt16(1), UInt16(0)]));
+
+        let table = Table::parse(&data).unwrap();
+        assert_eq!(table.glyph_name(GlyphId(0)), None);
+    }
+}
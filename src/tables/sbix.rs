@@ -4,7 +4,7 @@ use core::convert::TryFrom;
 use core::num::NonZeroU16;
 
 use crate::{GlyphId, RasterGlyphImage, RasterImageFormat, Tag};
-use crate::parser::{Stream, FromData, Offset, Offset32};
+use crate::parser::{Stream, FromData, Offset, Offset32, NumFrom};
 
 pub fn parse(
     data: &[u8],
@@ -25,7 +25,7 @@ pub fn parse(
         return None;
     }
 
-    s.skip::<u16>(); // flags
+    let flags: u16 = s.read()?;
     let count: u32 = s.read()?;
     if count == 0 {
         return None;
@@ -76,8 +76,11 @@ pub fn parse(
     // We do ignore `pdf` and `mask` intentionally, because Apple docs state that:
     // 'Support for the 'pdf ' and 'mask' data types and sbixDrawOutlines flag
     // are planned for future releases of iOS and OS X.'
+    // The `drawOutlines` flag is widely used nowadays though.
     let format = match &image_type.to_bytes() {
         b"png " => RasterImageFormat::PNG,
+        b"jpg " => RasterImageFormat::JPEG,
+        b"tiff" => RasterImageFormat::TIFF,
         b"dupe" => {
             // 'The special graphicType of 'dupe' indicates that
             // the data field contains a glyph ID. The bitmap data for
@@ -85,13 +88,14 @@ pub fn parse(
             let glyph_id = GlyphId::parse(image_data)?;
             return parse(data, number_of_glyphs, glyph_id, pixels_per_em, depth + 1);
         }
-        _ => {
-            // TODO: support JPEG and TIFF
-            return None;
-        }
+        _ => return None,
     };
 
-    let (width, height) = png_size(image_data)?;
+    let (width, height) = match format {
+        RasterImageFormat::JPEG => jpeg_size(image_data)?,
+        RasterImageFormat::TIFF => tiff_size(image_data)?,
+        _ => png_size(image_data)?,
+    };
 
     Some(RasterGlyphImage {
         x,
@@ -100,13 +104,14 @@ pub fn parse(
         height,
         pixels_per_em: max_ppem,
         format,
+        draw_outlines: flags & 2 != 0,
+        advance: None,
+        vertical_metrics: None,
         data: image_data,
     })
 }
 
 // The `sbix` table doesn't store the image size, so we have to parse it manually.
-// Which is quite simple in case of PNG, but way more complex for JPEG.
-// Therefore we are omitting it for now.
 fn png_size(data: &[u8]) -> Option<(u16, u16)> {
     // PNG stores its size as u32 BE at a fixed offset.
     let mut s = Stream::new_at(data, 16)?;
@@ -119,3 +124,167 @@ fn png_size(data: &[u8]) -> Option<(u16, u16)> {
         u16::try_from(height).ok()?,
     ))
 }
+
+fn jpeg_size(data: &[u8]) -> Option<(u16, u16)> {
+    let mut s = Stream::new(data);
+    if s.read::<u16>()? != 0xFFD8 {
+        return None;
+    }
+
+    // Look for the first Start Of Frame segment.
+    while !s.at_end() {
+        if s.read::<u8>()? != 0xFF {
+            return None;
+        }
+
+        let mut marker: u8 = s.read()?;
+        // Markers can be padded with any number of 0xFF bytes.
+        while marker == 0xFF {
+            marker = s.read()?;
+        }
+
+        match marker {
+            // Markers without a payload.
+            0x01 | 0xD0..=0xD7 => continue,
+            // SOF0..=SOF15, excluding DHT (C4), JPG (C8) and DAC (CC).
+            0xC0..=0xCF if marker != 0xC4 && marker != 0xC8 && marker != 0xCC => {
+                s.skip::<u16>(); // length
+                s.skip::<u8>(); // precision
+                let height: u16 = s.read()?;
+                let width: u16 = s.read()?;
+                return Some((width, height));
+            }
+            // Start Of Scan or End Of Image. Image data follows, so we can stop.
+            0xDA | 0xD9 => return None,
+            _ => {
+                let len: u16 = s.read()?;
+                s.advance_checked(usize::from(len.checked_sub(2)?))?;
+            }
+        }
+    }
+
+    None
+}
+
+fn tiff_size(data: &[u8]) -> Option<(u16, u16)> {
+    // TIFF can be either little or big endian, while `Stream` supports only the latter.
+    let is_le = match data.get(0..4)? {
+        b"II*\0" => true,
+        b"MM\0*" => false,
+        _ => return None,
+    };
+
+    let read_u16 = |offset: usize| -> Option<u16> {
+        let bytes = [*data.get(offset)?, *data.get(offset + 1)?];
+        Some(if is_le { u16::from_le_bytes(bytes) } else { u16::from_be_bytes(bytes) })
+    };
+
+    let read_u32 = |offset: usize| -> Option<u32> {
+        let bytes = [
+            *data.get(offset)?, *data.get(offset + 1)?,
+            *data.get(offset + 2)?, *data.get(offset + 3)?,
+        ];
+        Some(if is_le { u32::from_le_bytes(bytes) } else { u32::from_be_bytes(bytes) })
+    };
+
+    const IMAGE_WIDTH_TAG: u16 = 256;
+    const IMAGE_LENGTH_TAG: u16 = 257;
+    const SHORT_TYPE: u16 = 3;
+    const LONG_TYPE: u16 = 4;
+    const ENTRY_SIZE: usize = 12;
+
+    // Only the first Image File Directory is checked.
+    let ifd_offset = usize::num_from(read_u32(4)?);
+    let count = read_u16(ifd_offset)?;
+
+    let mut width = None;
+    let mut height = None;
+    for i in 0..usize::from(count) {
+        let offset = ifd_offset.checked_add(2 + i * ENTRY_SIZE)?;
+        let tag = read_u16(offset)?;
+        if tag != IMAGE_WIDTH_TAG && tag != IMAGE_LENGTH_TAG {
+            continue;
+        }
+
+        // A value that fits into 4 bytes is stored inline.
+        let value = match read_u16(offset + 2)? {
+            SHORT_TYPE => read_u16(offset + 8)?,
+            LONG_TYPE => u16::try_from(read_u32(offset + 8)?).ok()?,
+            _ => return None,
+        };
+
+        if tag == IMAGE_WIDTH_TAG {
+            width = Some(value);
+        } else {
+            height = Some(value);
+        }
+    }
+
+    Some((width?, height?))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    #[test]
+    fn jpeg() {
+        let data = writer::convert(&[
+            UInt16(0xFFD8), // SOI
+            UInt16(0xFFE0), // APP0
+            UInt16(4), // length
+            UInt16(0),
+            UInt8(0xFF), // padding
+            UInt16(0xFFC2), // SOF2
+            UInt16(11), // length
+            UInt8(8), // precision
+            UInt16(20), // height
+            UInt16(30), // width
+            UInt8(1), // components
+        ]);
+
+        assert_eq!(jpeg_size(&data), Some((30, 20)));
+    }
+
+    #[test]
+    fn jpeg_without_frame() {
+        let data = writer::convert(&[
+            UInt16(0xFFD8), // SOI
+            UInt16(0xFFC4), // DHT
+            UInt16(2), // length
+            UInt16(0xFFDA), // SOS
+        ]);
+
+        assert_eq!(jpeg_size(&data), None);
+    }
+
+    #[test]
+    fn tiff_le() {
+        let data = writer::convert(&[
+            Raw(b"II*\0"),
+            Raw(&[8, 0, 0, 0]), // IFD offset
+            Raw(&[2, 0]), // count
+            Raw(&[0, 1, 3, 0, 1, 0, 0, 0, 30, 0, 0, 0]), // ImageWidth, SHORT
+            Raw(&[1, 1, 4, 0, 1, 0, 0, 0, 20, 0, 0, 0]), // ImageLength, LONG
+        ]);
+
+        assert_eq!(tiff_size(&data), Some((30, 20)));
+    }
+
+    #[test]
+    fn tiff_be() {
+        let data = writer::convert(&[
+            Raw(b"MM\0*"),
+            UInt32(8), // IFD offset
+            UInt16(3), // count
+            UInt16(254), UInt16(4), UInt32(1), UInt32(0), // NewSubfileType
+            UInt16(256), UInt16(3), UInt32(1), UInt16(30), UInt16(0), // ImageWidth
+            UInt16(257), UInt16(3), UInt32(1), UInt16(20), UInt16(0), // ImageLength
+        ]);
+
+        assert_eq!(tiff_size(&data), Some((30, 20)));
+    }
+}
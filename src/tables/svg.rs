@@ -1,7 +1,10 @@
 // https://docs.microsoft.com/en-us/typography/opentype/spec/svg
 
+use core::convert::TryFrom;
+use core::ops::RangeInclusive;
+
 use crate::GlyphId;
-use crate::parser::{Stream, FromData, Offset, Offset32, NumFrom};
+use crate::parser::{Stream, FromData, Offset, Offset32, NumFrom, LazyArray16};
 
 
 #[derive(Clone, Copy)]
@@ -28,10 +31,143 @@ impl FromData for SvgDocumentRecord {
 }
 
 
-pub fn parse(
-    data: &[u8],
-    glyph_id: GlyphId,
-) -> Option<&[u8]> {
+/// An SVG document.
+///
+/// A single document can contain multiple glyphs. A glyph is stored in an element
+/// with the `glyph<ID>` id, like `glyph42`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SvgDocument<'a> {
+    /// A raw SVG document data.
+    ///
+    /// Can be compressed. See `is_compressed()`.
+    ///
+    /// Multiple records may reference the same document,
+    /// so the data pointer can be used as a cache key.
+    pub data: &'a [u8],
+
+    /// The first glyph ID in the document.
+    pub start_glyph_id: GlyphId,
+
+    /// The last glyph ID in the document. Inclusive.
+    pub end_glyph_id: GlyphId,
+}
+
+impl<'a> SvgDocument<'a> {
+    /// Returns a range of glyphs stored in the document.
+    #[inline]
+    pub fn glyphs_range(&self) -> RangeInclusive<GlyphId> {
+        self.start_glyph_id..=self.end_glyph_id
+    }
+
+    /// Checks that the document is compressed using gzip (SVGZ).
+    #[inline]
+    pub fn is_compressed(&self) -> bool {
+        self.data.starts_with(&[0x1F, 0x8B])
+    }
+
+    /// Decompresses a gzip-compressed document.
+    ///
+    /// Returns `None` when the document is not compressed or malformed.
+    /// Decompressed data larger than 64 MiB is considered malformed.
+    #[cfg(feature = "svgz")]
+    pub fn decompress(&self) -> Option<alloc::vec::Vec<u8>> {
+        // https://tools.ietf.org/html/rfc1952#page-5
+        const FHCRC: u8 = 1 << 1;
+        const FEXTRA: u8 = 1 << 2;
+        const FNAME: u8 = 1 << 3;
+        const FCOMMENT: u8 = 1 << 4;
+        const DEFLATE_METHOD: u8 = 8;
+        const MAX_SIZE: usize = 64 * 1024 * 1024;
+
+        if !self.is_compressed() {
+            return None;
+        }
+
+        let mut s = Stream::new(self.data);
+        s.skip::<u16>(); // magic
+        if s.read::<u8>()? != DEFLATE_METHOD {
+            return None;
+        }
+
+        let flags: u8 = s.read()?;
+        s.advance(6); // mtime (u32) + xfl (u8) + os (u8)
+
+        if flags & FEXTRA != 0 {
+            let len = u16::from_le_bytes([s.read()?, s.read()?]);
+            s.advance_checked(usize::from(len))?;
+        }
+
+        // Skip zero-terminated strings.
+        for flag in &[FNAME, FCOMMENT] {
+            if flags & flag != 0 {
+                while s.read::<u8>()? != 0 {}
+            }
+        }
+
+        if flags & FHCRC != 0 {
+            s.skip::<u16>();
+        }
+
+        let compressed = s.tail()?;
+        miniz_oxide::inflate::decompress_to_vec_with_limit(compressed, MAX_SIZE).ok()
+    }
+}
+
+
+/// An iterator over SVG documents.
+#[derive(Clone, Copy, Default)]
+pub struct SvgDocuments<'a> {
+    data: &'a [u8],
+    records: LazyArray16<'a, SvgDocumentRecord>,
+    index: u16,
+}
+
+impl<'a> SvgDocuments<'a> {
+    fn get(&self, index: u16) -> Option<SvgDocument<'a>> {
+        let record = self.records.get(index)?;
+        let offset = record.svg_doc_offset?.to_usize();
+        let mut s = Stream::new_at(self.data, offset)?;
+        let data = s.read_bytes(usize::num_from(record.svg_doc_length))?;
+        Some(SvgDocument {
+            data,
+            start_glyph_id: record.start_glyph_id,
+            end_glyph_id: record.end_glyph_id,
+        })
+    }
+
+    /// Finds a document that contains a specified glyph.
+    pub fn find(&self, glyph_id: GlyphId) -> Option<SvgDocument<'a>> {
+        let index = self.records.into_iter()
+            .position(|rec| (rec.start_glyph_id..=rec.end_glyph_id).contains(&glyph_id))?;
+        self.get(u16::try_from(index).ok()?)
+    }
+}
+
+impl<'a> Iterator for SvgDocuments<'a> {
+    type Item = SvgDocument<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Skip malformed records.
+        while self.index < self.records.len() {
+            let index = self.index;
+            self.index += 1;
+            if let Some(doc) = self.get(index) {
+                return Some(doc);
+            }
+        }
+
+        None
+    }
+}
+
+impl core::fmt::Debug for SvgDocuments<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "SvgDocuments()")
+    }
+}
+
+
+pub fn parse(data: &[u8]) -> Option<SvgDocuments<'_>> {
     let mut s = Stream::new(data);
     s.skip::<u16>(); // version
     let doc_list_offset = s.read::<Option<Offset32>>()??;
@@ -39,12 +175,112 @@ pub fn parse(
     let mut s = Stream::new_at(data, doc_list_offset.to_usize())?;
     let count: u16 = s.read()?;
     let records = s.read_array16::<SvgDocumentRecord>(count)?;
-    let record = records.into_iter()
-        .find(|rec| (rec.start_glyph_id..=rec.end_glyph_id).contains(&glyph_id))?;
 
-    let svg_offset = record.svg_doc_offset?;
-    let mut s = Stream::new_at(data, doc_list_offset.to_usize() + svg_offset.to_usize())?;
-    let svg_data = s.read_bytes(usize::num_from(record.svg_doc_length))?;
+    Some(SvgDocuments {
+        // Document offsets are relative to the document list.
+        data: data.get(doc_list_offset.to_usize()..)?,
+        records,
+        index: 0,
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer;
+    use writer::TtfType::*;
+
+    const SVGZ: &[u8] = &[
+        0x1F, 0x8B, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0xB3, 0x29, 0x2E,
+        0x4B, 0xD7, 0xB7, 0x03, 0x00, 0x49, 0xFB, 0xB9, 0xAC, 0x06, 0x00, 0x00, 0x00,
+    ];
+
+    fn table(doc: &'static [u8]) -> std::vec::Vec<u8> {
+        writer::convert(&[
+            UInt16(0), // version
+            UInt32(10), // offset to the SVG Document List
+            UInt32(0), // reserved
+            // SVG Document List
+            UInt16(3), // numEntries
+            // SVG Document Record
+            UInt16(1), // startGlyphID
+            UInt16(3), // endGlyphID
+            UInt32(38), // svgDocOffset
+            UInt32(doc.len() as u32), // svgDocLength
+            // SVG Document Record
+            UInt16(5), // startGlyphID
+            UInt16(5), // endGlyphID
+            UInt32(0), // svgDocOffset
+            UInt32(0), // svgDocLength
+            // SVG Document Record
+            UInt16(7), // startGlyphID
+            UInt16(8), // endGlyphID
+            UInt32(38), // svgDocOffset
+            UInt32(doc.len() as u32), // svgDocLength
+            Raw(doc),
+        ])
+    }
 
-    Some(svg_data)
+    #[test]
+    fn documents() {
+        let data = table(b"<svg/>");
+        let docs = parse(&data).unwrap();
+        assert_eq!(docs.count(), 2);
+
+        let mut iter = docs;
+        let doc = iter.next().unwrap();
+        assert_eq!(doc.data, b"<svg/>");
+        assert_eq!(doc.glyphs_range(), GlyphId(1)..=GlyphId(3));
+        assert!(!doc.is_compressed());
+
+        let doc = iter.next().unwrap();
+        assert_eq!(doc.glyphs_range(), GlyphId(7)..=GlyphId(8));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn find() {
+        let data = table(b"<svg/>");
+        let docs = parse(&data).unwrap();
+        assert_eq!(docs.find(GlyphId(2)).unwrap().start_glyph_id, GlyphId(1));
+        assert_eq!(docs.find(GlyphId(8)).unwrap().start_glyph_id, GlyphId(7));
+        assert!(docs.find(GlyphId(4)).is_none());
+        assert!(docs.find(GlyphId(5)).is_none());
+    }
+
+    #[test]
+    fn compressed() {
+        let data = table(SVGZ);
+        let doc = parse(&data).unwrap().find(GlyphId(1)).unwrap();
+        assert!(doc.is_compressed());
+    }
+
+    #[cfg(feature = "svgz")]
+    #[test]
+    fn decompress() {
+        let data = table(SVGZ);
+        let doc = parse(&data).unwrap().find(GlyphId(1)).unwrap();
+        assert_eq!(doc.decompress().unwrap(), b"<svg/>");
+    }
+
+    #[cfg(feature = "svgz")]
+    #[test]
+    fn decompress_with_file_name() {
+        let mut gzip = std::vec::Vec::new();
+        gzip.extend_from_slice(&SVGZ[..10]);
+        gzip[3] = 1 << 3; // FNAME
+        gzip.extend_from_slice(b"a.svg\0");
+        gzip.extend_from_slice(&SVGZ[10..]);
+
+        let doc = SvgDocument { data: &gzip, start_glyph_id: GlyphId(0), end_glyph_id: GlyphId(0) };
+        assert_eq!(doc.decompress().unwrap(), b"<svg/>");
+    }
+
+    #[cfg(feature = "svgz")]
+    #[test]
+    fn decompress_plain() {
+        let doc = SvgDocument { data: b"<svg/>", start_glyph_id: GlyphId(0), end_glyph_id: GlyphId(0) };
+        assert!(doc.decompress().is_none());
+    }
 }
@@ -0,0 +1,1471 @@
+// Useful links:
+// https://adobe-type-tools.github.io/font-tech-notes/pdfs/T1_SPEC.pdf
+// https://adobe-type-tools.github.io/font-tech-notes/pdfs/5015.Type1_Supp.pdf
+
+use core::convert::TryFrom;
+use core::ops::Range;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{GlyphId, OutlineBuilder, UnhintedOutlineBuilder, DummyOutline, Rect, BBox, FontMatrix};
+use crate::parser::{Stream, NumFrom, TryNumFrom};
+use crate::cff::{self, Builder, ArgumentsStack, CFFError, STANDARD_ENCODING, STANDARD_NAMES};
+
+// Limits according to the Adobe Type 1 Font Format, Appendix 1.
+const STACK_LIMIT: u8 = 10;
+const MAX_ARGUMENTS_STACK_LEN: usize = 24;
+
+// The PostScript interpreter stack, which is used by `callothersubr` and `pop`,
+// doesn't have a defined limit, so we are using the same one.
+const MAX_PS_STACK_LEN: usize = 24;
+
+// Flex is defined by a reference point and 6 curve points.
+const FLEX_POINTS_LEN: usize = 7;
+
+const EEXEC_KEY: u16 = 55665;
+const CHAR_STRING_KEY: u16 = 4330;
+const DEFAULT_LEN_IV: i32 = 4;
+
+// Glyph IDs are 16-bit.
+const MAX_GLYPHS_LEN: usize = 65535;
+
+const PFB_MARKER: u8 = 0x80;
+
+mod pfb_segment {
+    pub const ASCII: u8     = 1;
+    pub const BINARY: u8    = 2;
+    pub const EOF: u8       = 3;
+}
+
+// Adobe Type 1 Font Format, Chapter 6 "CharString Operators".
+mod operator {
+    pub const HORIZONTAL_STEM: u8               = 1;
+    pub const VERTICAL_STEM: u8                 = 3;
+    pub const VERTICAL_MOVE_TO: u8              = 4;
+    pub const LINE_TO: u8                       = 5;
+    pub const HORIZONTAL_LINE_TO: u8            = 6;
+    pub const VERTICAL_LINE_TO: u8              = 7;
+    pub const CURVE_TO: u8                      = 8;
+    pub const CLOSE_PATH: u8                    = 9;
+    pub const CALL_SUBROUTINE: u8               = 10;
+    pub const RETURN: u8                        = 11;
+    pub const TWO_BYTE_OPERATOR_MARK: u8        = 12;
+    pub const HORIZONTAL_SIDE_BEARING_WIDTH: u8 = 13;
+    pub const ENDCHAR: u8                       = 14;
+    pub const MOVE_TO: u8                       = 21;
+    pub const HORIZONTAL_MOVE_TO: u8            = 22;
+    pub const VH_CURVE_TO: u8                   = 30;
+    pub const HV_CURVE_TO: u8                   = 31;
+
+    // Two-byte operators.
+    pub const DOT_SECTION: u8                   = 0;
+    pub const VERTICAL_STEM_3: u8               = 1;
+    pub const HORIZONTAL_STEM_3: u8             = 2;
+    pub const SEAC: u8                          = 6;
+    pub const SIDE_BEARING_WIDTH: u8            = 7;
+    pub const DIV: u8                           = 12;
+    pub const CALL_OTHER_SUBROUTINE: u8         = 16;
+    pub const POP: u8                           = 17;
+    pub const SET_CURRENT_POINT: u8             = 33;
+}
+
+// Adobe Type 1 Font Format, Chapter 8 "Using Subroutines".
+mod other_subroutine {
+    pub const FLEX_END: u8      = 0;
+    pub const FLEX_START: u8    = 1;
+    pub const FLEX_POINT: u8    = 2;
+}
+
+
+/// A Type 1 font handle.
+///
+/// Supports PFB, PFA and raw binary font programs, like the one stored
+/// in a PDF `FontFile` stream.
+///
+/// Unlike other parsers in this crate, has to allocate,
+/// because Type 1 fonts are encrypted.
+///
+/// Glyph IDs are assigned in the `CharStrings` dictionary order.
+#[derive(Clone)]
+pub struct Type1Font {
+    font_name: String,
+    font_matrix: FontMatrix,
+    encoding: Vec<Option<GlyphId>>,
+    // Decrypted charstrings and subroutines.
+    data: Vec<u8>,
+    glyphs: Vec<Glyph>,
+    subrs: Vec<Range<usize>>,
+}
+
+#[derive(Clone, Debug)]
+struct Glyph {
+    name: String,
+    data: Range<usize>,
+}
+
+impl Type1Font {
+    /// Parses a Type 1 font.
+    ///
+    /// Returns `None` when the font is malformed or has no glyphs.
+    pub fn from_data(data: &[u8]) -> Option<Self> {
+        let (cleartext, private) = if data.first() == Some(&PFB_MARKER) {
+            let (cleartext, encrypted) = split_pfb(data)?;
+            let private = decrypt(&encrypted, EEXEC_KEY, 4);
+            (cleartext, private)
+        } else {
+            let (cleartext, encrypted) = split_pfa(data)?;
+            let private = if is_hex_encoded(encrypted) {
+                decrypt(&decode_hex(encrypted), EEXEC_KEY, 4)
+            } else {
+                decrypt(encrypted, EEXEC_KEY, 4)
+            };
+            (Vec::from(cleartext), private)
+        };
+
+        let mut font = Type1Font {
+            font_name: String::new(),
+            font_matrix: FontMatrix::default(),
+            encoding: Vec::new(),
+            data: Vec::new(),
+            glyphs: Vec::new(),
+            subrs: Vec::new(),
+        };
+
+        let encoding = parse_cleartext(&cleartext, &mut font)?;
+        parse_private(&private, &mut font)?;
+
+        if font.glyphs.is_empty() {
+            return None;
+        }
+
+        font.encoding = encoding.iter()
+            .map(|name| name.as_ref().and_then(|name| font.glyph_index_by_name(name)))
+            .collect();
+
+        Some(font)
+    }
+
+    /// Returns a font name.
+    #[inline]
+    pub fn font_name(&self) -> Option<&str> {
+        if self.font_name.is_empty() {
+            None
+        } else {
+            Some(&self.font_name)
+        }
+    }
+
+    /// Returns a font matrix.
+    #[inline]
+    pub fn font_matrix(&self) -> FontMatrix {
+        self.font_matrix
+    }
+
+    /// Returns a total number of glyphs in the font.
+    #[inline]
+    pub fn number_of_glyphs(&self) -> u16 {
+        // Cannot overflow, since we are not parsing more than `u16::MAX` glyphs.
+        self.glyphs.len() as u16
+    }
+
+    /// Resolves a Glyph ID for a glyph name.
+    #[inline]
+    pub fn glyph_index_by_name(&self, name: &str) -> Option<GlyphId> {
+        let index = self.glyphs.iter().position(|glyph| glyph.name == name)?;
+        u16::try_from(index).ok().map(GlyphId)
+    }
+
+    /// Resolves a Glyph ID for a single-byte character code.
+    ///
+    /// Uses the font's `Encoding`.
+    #[inline]
+    pub fn glyph_index_by_code(&self, code: u8) -> Option<GlyphId> {
+        self.encoding.get(usize::from(code)).cloned().and_then(|id| id)
+    }
+
+    /// Returns glyph's name.
+    #[inline]
+    pub fn glyph_name(&self, glyph_id: GlyphId) -> Option<&str> {
+        self.glyphs.get(usize::from(glyph_id.0)).map(|glyph| glyph.name.as_str())
+    }
+
+    /// Returns glyph's horizontal advance in font units.
+    ///
+    /// Uses the width set by `hsbw` or `sbw`.
+    #[inline]
+    pub fn glyph_hor_advance(&self, glyph_id: GlyphId) -> Option<u16> {
+        let mut builder = UnhintedOutlineBuilder(&mut DummyOutline);
+        let (_, width) = parse_char_string(self, glyph_id, &mut builder).ok()?;
+        cff::round_width(width?)
+    }
+
+    /// Outlines a glyph and returns its tight bounding box.
+    ///
+    /// Hints are ignored.
+    ///
+    /// Returns `None` when glyph has no outline or on error.
+    #[inline]
+    pub fn outline_glyph(&self, glyph_id: GlyphId, builder: &mut dyn OutlineBuilder) -> Option<Rect> {
+        let mut builder = UnhintedOutlineBuilder(builder);
+        let (bbox, _) = parse_char_string(self, glyph_id, &mut builder).ok()?;
+        if bbox.is_default() {
+            return None;
+        }
+
+        bbox.to_rect()
+    }
+
+    #[inline]
+    fn char_string(&self, glyph_id: GlyphId) -> Option<&[u8]> {
+        let range = self.glyphs.get(usize::from(glyph_id.0))?.data.clone();
+        self.data.get(range)
+    }
+}
+
+impl core::fmt::Debug for Type1Font {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "Type1Font()")
+    }
+}
+
+
+// A PFB file is a list of segments, each starting with a 6 bytes header.
+// ASCII segments contain the cleartext portion and the trailer,
+// while binary segments contain the encrypted portion.
+fn split_pfb(data: &[u8]) -> Option<(Vec<u8>, Vec<u8>)> {
+    let mut cleartext = Vec::new();
+    let mut encrypted = Vec::new();
+
+    let mut s = Stream::new(data);
+    while !s.at_end() {
+        if s.read::<u8>()? != PFB_MARKER {
+            return None;
+        }
+
+        let kind: u8 = s.read()?;
+        if kind == pfb_segment::EOF {
+            break;
+        }
+
+        // Segment length is stored in little-endian.
+        let len = s.read_bytes(4)?;
+        let len = u32::from(len[0])
+            | u32::from(len[1]) << 8
+            | u32::from(len[2]) << 16
+            | u32::from(len[3]) << 24;
+
+        let segment = s.read_bytes(usize::num_from(len))?;
+        match kind {
+            pfb_segment::ASCII => {
+                // Skip the trailer.
+                if encrypted.is_empty() {
+                    cleartext.extend_from_slice(segment);
+                }
+            }
+            pfb_segment::BINARY => encrypted.extend_from_slice(segment),
+            _ => return None,
+        }
+    }
+
+    Some((cleartext, encrypted))
+}
+
+// A PFA file and a PDF `FontFile` stream are stored as is,
+// with an encrypted portion following the `eexec` operator.
+fn split_pfa(data: &[u8]) -> Option<(&[u8], &[u8])> {
+    const EEXEC: &[u8] = b"eexec";
+
+    let pos = data.windows(EEXEC.len()).position(|w| w == EEXEC)? + EEXEC.len();
+    let (cleartext, mut encrypted) = data.split_at(pos);
+
+    // Skip whitespaces before the encrypted data.
+    while let Some((c, rest)) = encrypted.split_first() {
+        if !is_whitespace(*c) {
+            break;
+        }
+
+        encrypted = rest;
+    }
+
+    Some((cleartext, encrypted))
+}
+
+// 'If the first 4 bytes are hexadecimal digits, the encrypted portion
+// is assumed to be in hexadecimal form.'
+fn is_hex_encoded(data: &[u8]) -> bool {
+    data.len() >= 4 && data[0..4].iter().all(u8::is_ascii_hexdigit)
+}
+
+fn decode_hex(data: &[u8]) -> Vec<u8> {
+    let mut decoded = Vec::with_capacity(data.len() / 2);
+    let mut high = None;
+    for c in data {
+        let n = match *c {
+            b'0'..=b'9' => c - b'0',
+            b'a'..=b'f' => c - b'a' + 10,
+            b'A'..=b'F' => c - b'A' + 10,
+            c if is_whitespace(c) => continue,
+            // The encrypted portion ends with a non-hex character.
+            _ => break,
+        };
+
+        match high.take() {
+            Some(high) => decoded.push(high << 4 | n),
+            None => high = Some(n),
+        }
+    }
+
+    decoded
+}
+
+// Adobe Type 1 Font Format, Chapter 7 "Encryption".
+fn decrypt(data: &[u8], key: u16, skip: usize) -> Vec<u8> {
+    const C1: u16 = 52845;
+    const C2: u16 = 22719;
+
+    let mut decrypted = Vec::with_capacity(data.len());
+    let mut r = key;
+    for (i, c) in data.iter().enumerate() {
+        let p = c ^ (r >> 8) as u8;
+        r = u16::from(*c).wrapping_add(r).wrapping_mul(C1).wrapping_add(C2);
+        if i >= skip {
+            decrypted.push(p);
+        }
+    }
+
+    decrypted
+}
+
+// Returns glyph names for each character code.
+fn parse_cleartext(data: &[u8], font: &mut Type1Font) -> Option<Vec<Option<String>>> {
+    let mut encoding = Vec::new();
+
+    let mut t = Tokenizer::new(data);
+    while let Some(token) = t.next() {
+        match token {
+            b"/FontName" => {
+                let name = t.next()?;
+                if name.len() > 1 && name[0] == b'/' {
+                    font.font_name = String::from(core::str::from_utf8(&name[1..]).ok()?);
+                }
+            }
+            b"/FontMatrix" => {
+                font.font_matrix = parse_font_matrix(&mut t)?;
+            }
+            b"/Encoding" => {
+                encoding = parse_encoding(&mut t)?;
+            }
+            _ => {}
+        }
+    }
+
+    Some(encoding)
+}
+
+// `[0.001 0 0 0.001 0 0] readonly def`
+fn parse_font_matrix(t: &mut Tokenizer) -> Option<FontMatrix> {
+    match t.next()? {
+        b"[" | b"{" => {}
+        _ => return None,
+    }
+
+    let mut values = [0.0; 6];
+    for value in &mut values {
+        *value = parse_real(t.next()?)?;
+    }
+
+    Some(FontMatrix {
+        sx: values[0],
+        ky: values[1],
+        kx: values[2],
+        sy: values[3],
+        tx: values[4],
+        ty: values[5],
+    })
+}
+
+// Either `StandardEncoding def`
+// or `256 array 0 1 255 {1 index exch /.notdef put} for dup 65 /A put ... readonly def`
+fn parse_encoding(t: &mut Tokenizer) -> Option<Vec<Option<String>>> {
+    let mut encoding = alloc::vec![None; 256];
+
+    let token = t.next()?;
+    if token == b"StandardEncoding" {
+        for (name, sid) in encoding.iter_mut().zip(STANDARD_ENCODING.iter()) {
+            // SID 0 is `.notdef`.
+            if *sid != 0 {
+                *name = STANDARD_NAMES.get(usize::from(*sid)).map(|s| String::from(*s));
+            }
+        }
+
+        return Some(encoding);
+    }
+
+    parse_int(token)?;
+
+    while let Some(token) = t.next() {
+        match token {
+            b"dup" => {
+                let code = t.next()?;
+                let name = t.next()?;
+                if name.len() < 2 || name[0] != b'/' {
+                    // Not an encoding entry.
+                    continue;
+                }
+
+                let code = match parse_int(code).and_then(|n| u8::try_from(n).ok()) {
+                    Some(v) => v,
+                    None => continue,
+                };
+
+                encoding[usize::from(code)] = core::str::from_utf8(&name[1..]).ok().map(String::from);
+            }
+            b"def" => break,
+            _ => {}
+        }
+    }
+
+    Some(encoding)
+}
+
+fn parse_private(data: &[u8], font: &mut Type1Font) -> Option<()> {
+    let mut len_iv = DEFAULT_LEN_IV;
+
+    let mut t = Tokenizer::new(data);
+    while let Some(token) = t.next() {
+        match token {
+            b"/lenIV" => {
+                len_iv = parse_int(t.next()?)?;
+            }
+            b"/Subrs" => {
+                parse_subrs(&mut t, len_iv, font)?;
+            }
+            b"/CharStrings" => {
+                parse_char_strings(&mut t, len_iv, font)?;
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    Some(())
+}
+
+// `/Subrs 2 array
+//  dup 0 15 RD <binary> NP
+//  dup 1 9 RD <binary> NP
+//  ND`
+fn parse_subrs(t: &mut Tokenizer, len_iv: i32, font: &mut Type1Font) -> Option<()> {
+    let count = match parse_int(t.next()?).and_then(|n| usize::try_from(n).ok()) {
+        Some(v) => v,
+        // Not a definition.
+        None => return Some(()),
+    };
+
+    // Each entry takes at least one byte, so we can use the remaining data length
+    // to limit the allocation for malformed fonts.
+    let count = count.min(t.data.len().saturating_sub(t.offset));
+    font.subrs = alloc::vec![0..0; count];
+
+    let mut parsed = 0;
+    while parsed < count {
+        if t.peek_token()?.first() == Some(&b'/') {
+            // Unexpected key. Looks like some subroutines are missing.
+            // The key is not consumed, since it can be `/CharStrings`.
+            break;
+        }
+
+        let token = t.next()?;
+        if token == b"dup" {
+            let index = usize::try_from(parse_int(t.next()?)?).ok()?;
+            let data = t.read_binary()?;
+            let range = push_char_string(data, len_iv, &mut font.data);
+            if let Some(subr) = font.subrs.get_mut(index) {
+                *subr = range;
+            }
+
+            parsed += 1;
+        }
+    }
+
+    Some(())
+}
+
+// `/CharStrings 2 dict dup begin
+//  /.notdef 9 RD <binary> ND
+//  /A 30 RD <binary> ND
+//  end`
+fn parse_char_strings(t: &mut Tokenizer, len_iv: i32, font: &mut Type1Font) -> Option<()> {
+    while let Some(token) = t.next() {
+        if token == b"end" {
+            break;
+        }
+
+        if token.len() < 2 || token[0] != b'/' {
+            continue;
+        }
+
+        if font.glyphs.len() == MAX_GLYPHS_LEN {
+            break;
+        }
+
+        let name = String::from(core::str::from_utf8(&token[1..]).ok()?);
+        let data = t.read_binary()?;
+        let data = push_char_string(data, len_iv, &mut font.data);
+        font.glyphs.push(Glyph { name, data });
+    }
+
+    Some(())
+}
+
+// Decrypts a charstring and appends it to the storage.
+fn push_char_string(data: &[u8], len_iv: i32, storage: &mut Vec<u8>) -> Range<usize> {
+    let start = storage.len();
+    // 'lenIV -1 indicates that charstrings are not encrypted.'
+    match usize::try_from(len_iv) {
+        Ok(skip) => storage.extend_from_slice(&decrypt(data, CHAR_STRING_KEY, skip)),
+        Err(_) => storage.extend_from_slice(data),
+    }
+
+    start..storage.len()
+}
+
+fn parse_int(token: &[u8]) -> Option<i32> {
+    core::str::from_utf8(token).ok()?.parse().ok()
+}
+
+fn parse_real(token: &[u8]) -> Option<f32> {
+    core::str::from_utf8(token).ok()?.parse().ok()
+}
+
+#[inline]
+fn is_whitespace(c: u8) -> bool {
+    c.is_ascii_whitespace() || c == b'\0'
+}
+
+#[inline]
+fn is_delimiter(c: u8) -> bool {
+    b"()<>[]{}/%".contains(&c)
+}
+
+
+// A minimal PostScript tokenizer.
+//
+// Doesn't interpret anything and doesn't validate strings.
+#[derive(Clone, Copy)]
+struct Tokenizer<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Tokenizer<'a> {
+    #[inline]
+    fn new(data: &'a [u8]) -> Self {
+        Tokenizer { data, offset: 0 }
+    }
+
+    #[inline]
+    fn peek(&self) -> Option<u8> {
+        self.data.get(self.offset).cloned()
+    }
+
+    fn skip_whitespaces(&mut self) {
+        while let Some(c) = self.peek() {
+            if c == b'%' {
+                // Skip a comment.
+                while let Some(c) = self.peek() {
+                    if c == b'\r' || c == b'\n' {
+                        break;
+                    }
+
+                    self.offset += 1;
+                }
+            } else if is_whitespace(c) {
+                self.offset += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn skip_regular(&mut self) {
+        while let Some(c) = self.peek() {
+            if is_whitespace(c) || is_delimiter(c) {
+                break;
+            }
+
+            self.offset += 1;
+        }
+    }
+
+    fn skip_string(&mut self) {
+        let mut depth = 0;
+        while let Some(c) = self.peek() {
+            self.offset += 1;
+            match c {
+                b'\\' => self.offset += 1,
+                b'(' => depth += 1,
+                b')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn next(&mut self) -> Option<&'a [u8]> {
+        self.skip_whitespaces();
+
+        let start = self.offset;
+        match self.peek()? {
+            b'/' => {
+                self.offset += 1;
+                self.skip_regular();
+            }
+            b'(' => {
+                self.skip_string();
+            }
+            b'<' | b'>' => {
+                let c = self.data[self.offset];
+                self.offset += 1;
+                if self.peek() == Some(c) {
+                    // A dictionary.
+                    self.offset += 1;
+                } else if c == b'<' {
+                    // Skip a hex string.
+                    while let Some(c) = self.peek() {
+                        self.offset += 1;
+                        if c == b'>' {
+                            break;
+                        }
+                    }
+                }
+            }
+            b'[' | b']' | b'{' | b'}' | b')' => {
+                self.offset += 1;
+            }
+            _ => {
+                self.skip_regular();
+            }
+        }
+
+        self.data.get(start..self.offset)
+    }
+
+    #[inline]
+    fn peek_token(&self) -> Option<&'a [u8]> {
+        let mut t = *self;
+        t.next()
+    }
+
+    // Reads `len RD <binary>`.
+    //
+    // `RD` can have any name, usually `RD` or `-|`, and is followed by a single space.
+    fn read_binary(&mut self) -> Option<&'a [u8]> {
+        let len = usize::try_from(parse_int(self.next()?)?).ok()?;
+        self.next()?;
+        let start = self.offset.checked_add(1)?;
+        let end = start.checked_add(len)?;
+        let data = self.data.get(start..end)?;
+        self.offset = end;
+        Some(data)
+    }
+}
+
+
+struct CharStringParserContext<'a> {
+    font: &'a Type1Font,
+    x: f32,
+    y: f32,
+    // An offset used by `seac` components.
+    origin_x: f32,
+    origin_y: f32,
+    width: Option<f32>,
+    is_path_open: bool,
+    is_flex: bool,
+    flex_start: (f32, f32),
+    flex_points: [(f32, f32); FLEX_POINTS_LEN],
+    flex_points_len: usize,
+    ps_stack: [f32; MAX_PS_STACK_LEN],
+    ps_stack_len: usize,
+    has_endchar: bool,
+}
+
+impl CharStringParserContext<'_> {
+    // Type 1 doesn't require an explicit `moveto` before each contour,
+    // so a contour is started on the first drawing operator.
+    #[inline]
+    fn open_path(&mut self, builder: &mut Builder) {
+        if !self.is_path_open {
+            self.is_path_open = true;
+            builder.move_to(self.x, self.y);
+        }
+    }
+
+    #[inline]
+    fn close_path(&mut self, builder: &mut Builder) {
+        if self.is_path_open {
+            self.is_path_open = false;
+            builder.close();
+        }
+    }
+
+    #[inline]
+    fn move_to(&mut self, dx: f32, dy: f32, builder: &mut Builder) {
+        // Flex points are set via `moveto`, but they are not the actual moves.
+        if !self.is_flex {
+            self.close_path(builder);
+        }
+
+        self.x += dx;
+        self.y += dy;
+    }
+
+    #[inline]
+    fn line_to(&mut self, dx: f32, dy: f32, builder: &mut Builder) {
+        self.open_path(builder);
+        self.x += dx;
+        self.y += dy;
+        builder.line_to(self.x, self.y);
+    }
+
+    #[inline]
+    fn curve_to(&mut self, d1: (f32, f32), d2: (f32, f32), d3: (f32, f32), builder: &mut Builder) {
+        self.open_path(builder);
+        let x1 = self.x + d1.0;
+        let y1 = self.y + d1.1;
+        let x2 = x1 + d2.0;
+        let y2 = y1 + d2.1;
+        self.x = x2 + d3.0;
+        self.y = y2 + d3.1;
+        builder.curve_to(x1, y1, x2, y2, self.x, self.y);
+    }
+
+    #[inline]
+    fn push_ps(&mut self, n: f32) -> Result<(), CFFError> {
+        if self.ps_stack_len == MAX_PS_STACK_LEN {
+            return Err(CFFError::ArgumentsStackLimitReached);
+        }
+
+        self.ps_stack[self.ps_stack_len] = n;
+        self.ps_stack_len += 1;
+        Ok(())
+    }
+}
+
+// Returns a bounding box and a width set by `hsbw` or `sbw`.
+fn parse_char_string(
+    font: &Type1Font,
+    glyph_id: GlyphId,
+    builder: &mut UnhintedOutlineBuilder,
+) -> Result<(BBox, Option<f32>), CFFError> {
+    let data = font.char_string(glyph_id).ok_or(CFFError::ReadOutOfBounds)?;
+
+    let mut ctx = CharStringParserContext {
+        font,
+        x: 0.0,
+        y: 0.0,
+        origin_x: 0.0,
+        origin_y: 0.0,
+        width: None,
+        is_path_open: false,
+        is_flex: false,
+        flex_start: (0.0, 0.0),
+        flex_points: [(0.0, 0.0); FLEX_POINTS_LEN],
+        flex_points_len: 0,
+        ps_stack: [0.0; MAX_PS_STACK_LEN],
+        ps_stack_len: 0,
+        has_endchar: false,
+    };
+
+    let mut inner_builder = Builder {
+        builder,
+        bbox: BBox::new(),
+    };
+
+    let mut stack = ArgumentsStack {
+        data: &mut [0.0; MAX_ARGUMENTS_STACK_LEN], // 96B
+        len: 0,
+        max_len: MAX_ARGUMENTS_STACK_LEN,
+    };
+    _parse_char_string(&mut ctx, data, &mut stack, 0, &mut inner_builder)?;
+
+    if !ctx.has_endchar {
+        return Err(CFFError::MissingEndChar);
+    }
+
+    Ok((inner_builder.bbox, ctx.width))
+}
+
+fn _parse_char_string(
+    ctx: &mut CharStringParserContext,
+    char_string: &[u8],
+    stack: &mut ArgumentsStack,
+    depth: u8,
+    builder: &mut Builder,
+) -> Result<(), CFFError> {
+    let mut s = Stream::new(char_string);
+    while !s.at_end() {
+        let op: u8 = s.read().ok_or(CFFError::ReadOutOfBounds)?;
+        match op {
+            operator::HORIZONTAL_STEM |
+            operator::VERTICAL_STEM => {
+                // y dy hstem
+                // x dx vstem
+                stack.clear();
+            }
+            operator::VERTICAL_MOVE_TO => {
+                // dy vmoveto
+                let base = args_base(stack, 1)?;
+                ctx.move_to(0.0, stack.at(base), builder);
+                stack.clear();
+            }
+            operator::LINE_TO => {
+                // dx dy rlineto
+                let base = args_base(stack, 2)?;
+                ctx.line_to(stack.at(base), stack.at(base + 1), builder);
+                stack.clear();
+            }
+            operator::HORIZONTAL_LINE_TO => {
+                // dx hlineto
+                let base = args_base(stack, 1)?;
+                ctx.line_to(stack.at(base), 0.0, builder);
+                stack.clear();
+            }
+            operator::VERTICAL_LINE_TO => {
+                // dy vlineto
+                let base = args_base(stack, 1)?;
+                ctx.line_to(0.0, stack.at(base), builder);
+                stack.clear();
+            }
+            operator::CURVE_TO => {
+                // dx1 dy1 dx2 dy2 dx3 dy3 rrcurveto
+                let base = args_base(stack, 6)?;
+                ctx.curve_to(
+                    (stack.at(base), stack.at(base + 1)),
+                    (stack.at(base + 2), stack.at(base + 3)),
+                    (stack.at(base + 4), stack.at(base + 5)),
+                    builder,
+                );
+                stack.clear();
+            }
+            operator::CLOSE_PATH => {
+                ctx.close_path(builder);
+                stack.clear();
+            }
+            operator::CALL_SUBROUTINE => {
+                // subr# callsubr
+                if stack.is_empty() {
+                    return Err(CFFError::InvalidArgumentsStackLength);
+                }
+
+                if depth == STACK_LIMIT {
+                    return Err(CFFError::NestingLimitReached);
+                }
+
+                let index = i32::try_num_from(stack.pop())
+                    .and_then(|n| usize::try_from(n).ok())
+                    .ok_or(CFFError::InvalidSubroutineIndex)?;
+                let range = ctx.font.subrs.get(index).cloned()
+                    .ok_or(CFFError::InvalidSubroutineIndex)?;
+                let font = ctx.font;
+                let char_string = font.data.get(range)
+                    .ok_or(CFFError::InvalidSubroutineIndex)?;
+                _parse_char_string(ctx, char_string, stack, depth + 1, builder)?;
+
+                if ctx.has_endchar {
+                    break;
+                }
+            }
+            operator::RETURN => {
+                break;
+            }
+            operator::HORIZONTAL_SIDE_BEARING_WIDTH => {
+                // sbx wx hsbw
+                let base = args_base(stack, 2)?;
+                ctx.x = ctx.origin_x + stack.at(base);
+                ctx.y = ctx.origin_y;
+                if ctx.width.is_none() {
+                    ctx.width = Some(stack.at(base + 1));
+                }
+
+                stack.clear();
+            }
+            operator::ENDCHAR => {
+                ctx.close_path(builder);
+                ctx.has_endchar = true;
+                break;
+            }
+            operator::MOVE_TO => {
+                // dx dy rmoveto
+                let base = args_base(stack, 2)?;
+                ctx.move_to(stack.at(base), stack.at(base + 1), builder);
+                stack.clear();
+            }
+            operator::HORIZONTAL_MOVE_TO => {
+                // dx hmoveto
+                let base = args_base(stack, 1)?;
+                ctx.move_to(stack.at(base), 0.0, builder);
+                stack.clear();
+            }
+            operator::VH_CURVE_TO => {
+                // dy1 dx2 dy2 dx3 vhcurveto
+                let base = args_base(stack, 4)?;
+                ctx.curve_to(
+                    (0.0, stack.at(base)),
+                    (stack.at(base + 1), stack.at(base + 2)),
+                    (stack.at(base + 3), 0.0),
+                    builder,
+                );
+                stack.clear();
+            }
+            operator::HV_CURVE_TO => {
+                // dx1 dx2 dy2 dy3 hvcurveto
+                let base = args_base(stack, 4)?;
+                ctx.curve_to(
+                    (stack.at(base), 0.0),
+                    (stack.at(base + 1), stack.at(base + 2)),
+                    (0.0, stack.at(base + 3)),
+                    builder,
+                );
+                stack.clear();
+            }
+            operator::TWO_BYTE_OPERATOR_MARK => {
+                let op2: u8 = s.read().ok_or(CFFError::ReadOutOfBounds)?;
+                match op2 {
+                    operator::DOT_SECTION |
+                    operator::VERTICAL_STEM_3 |
+                    operator::HORIZONTAL_STEM_3 => {
+                        stack.clear();
+                    }
+                    operator::SEAC => {
+                        // asb adx ady bchar achar seac
+                        let base = args_base(stack, 5)?;
+                        let asb = stack.at(base);
+                        let adx = stack.at(base + 1);
+                        let ady = stack.at(base + 2);
+                        let base_char = seac_code_to_glyph_id(ctx.font, stack.at(base + 3))
+                            .ok_or(CFFError::InvalidSeacCode)?;
+                        let accent_char = seac_code_to_glyph_id(ctx.font, stack.at(base + 4))
+                            .ok_or(CFFError::InvalidSeacCode)?;
+
+                        if depth == STACK_LIMIT {
+                            return Err(CFFError::NestingLimitReached);
+                        }
+
+                        // 'The accent is positioned relative to the origin of the base character.'
+                        let origin = (ctx.origin_x, ctx.origin_y);
+                        parse_seac_component(ctx, base_char, origin.0, origin.1, stack, depth + 1, builder)?;
+                        parse_seac_component(ctx, accent_char, origin.0 + adx - asb, origin.1 + ady,
+                                             stack, depth + 1, builder)?;
+                        ctx.origin_x = origin.0;
+                        ctx.origin_y = origin.1;
+
+                        // `seac` is the last operator.
+                        break;
+                    }
+                    operator::SIDE_BEARING_WIDTH => {
+                        // sbx sby wx wy sbw
+                        let base = args_base(stack, 4)?;
+                        ctx.x = ctx.origin_x + stack.at(base);
+                        ctx.y = ctx.origin_y + stack.at(base + 1);
+                        if ctx.width.is_none() {
+                            ctx.width = Some(stack.at(base + 2));
+                        }
+
+                        stack.clear();
+                    }
+                    operator::DIV => {
+                        // num1 num2 div
+                        if stack.len() < 2 {
+                            return Err(CFFError::InvalidArgumentsStackLength);
+                        }
+
+                        let num2 = stack.pop();
+                        let num1 = stack.pop();
+                        stack.push(num1 / num2)?;
+                    }
+                    operator::CALL_OTHER_SUBROUTINE => {
+                        // arg1 ... argn n othersubr# callothersubr
+                        call_other_subroutine(ctx, stack, builder)?;
+                    }
+                    operator::POP => {
+                        if ctx.ps_stack_len == 0 {
+                            return Err(CFFError::InvalidArgumentsStackLength);
+                        }
+
+                        ctx.ps_stack_len -= 1;
+                        stack.push(ctx.ps_stack[ctx.ps_stack_len])?;
+                    }
+                    operator::SET_CURRENT_POINT => {
+                        // x y setcurrentpoint
+                        let base = args_base(stack, 2)?;
+                        ctx.x = stack.at(base);
+                        ctx.y = stack.at(base + 1);
+                        stack.clear();
+                    }
+                    _ => {
+                        return Err(CFFError::UnsupportedOperator);
+                    }
+                }
+            }
+            32..=246 => {
+                stack.push(f32::from(i16::from(op) - 139))?;
+            }
+            247..=250 => {
+                let b1: u8 = s.read().ok_or(CFFError::ReadOutOfBounds)?;
+                let n = (i16::from(op) - 247) * 256 + i16::from(b1) + 108;
+                stack.push(f32::from(n))?;
+            }
+            251..=254 => {
+                let b1: u8 = s.read().ok_or(CFFError::ReadOutOfBounds)?;
+                let n = -(i16::from(op) - 251) * 256 - i16::from(b1) - 108;
+                stack.push(f32::from(n))?;
+            }
+            255 => {
+                let n: i32 = s.read().ok_or(CFFError::ReadOutOfBounds)?;
+                stack.push(n as f32)?;
+            }
+            _ => {
+                return Err(CFFError::InvalidOperator);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Returns an index of the first argument.
+//
+// Operators use the topmost values, but clear the whole stack.
+#[inline]
+fn args_base(stack: &ArgumentsStack, count: usize) -> Result<usize, CFFError> {
+    if stack.len() < count {
+        Err(CFFError::InvalidArgumentsStackLength)
+    } else {
+        Ok(stack.len() - count)
+    }
+}
+
+// Adobe Type 1 Font Format, Chapter 8 "Using Subroutines".
+//
+// Other subroutines are PostScript procedures, so we are emulating
+// the standard ones and pass arguments of others back via `pop`.
+fn call_other_subroutine(
+    ctx: &mut CharStringParserContext,
+    stack: &mut ArgumentsStack,
+    builder: &mut Builder,
+) -> Result<(), CFFError> {
+    if stack.len() < 2 {
+        return Err(CFFError::InvalidArgumentsStackLength);
+    }
+
+    let index = i32::try_num_from(stack.pop()).ok_or(CFFError::InvalidSubroutineIndex)?;
+    let args_len = i32::try_num_from(stack.pop())
+        .and_then(|n| usize::try_from(n).ok())
+        .ok_or(CFFError::InvalidArgumentsStackLength)?;
+    let base = args_base(stack, args_len)?;
+
+    match u8::try_from(index) {
+        Ok(other_subroutine::FLEX_START) => {
+            ctx.is_flex = true;
+            ctx.flex_start = (ctx.x, ctx.y);
+            ctx.flex_points_len = 0;
+        }
+        Ok(other_subroutine::FLEX_POINT) if ctx.is_flex => {
+            if ctx.flex_points_len == FLEX_POINTS_LEN {
+                return Err(CFFError::InvalidArgumentsStackLength);
+            }
+
+            ctx.flex_points[ctx.flex_points_len] = (ctx.x, ctx.y);
+            ctx.flex_points_len += 1;
+        }
+        Ok(other_subroutine::FLEX_END) if ctx.is_flex => {
+            if ctx.flex_points_len != FLEX_POINTS_LEN {
+                return Err(CFFError::InvalidArgumentsStackLength);
+            }
+
+            ctx.is_flex = false;
+
+            // The first point is a reference point and is not a part of the path.
+            let p = ctx.flex_points;
+            ctx.x = ctx.flex_start.0;
+            ctx.y = ctx.flex_start.1;
+            ctx.open_path(builder);
+            builder.curve_to(p[1].0, p[1].1, p[2].0, p[2].1, p[3].0, p[3].1);
+            builder.curve_to(p[4].0, p[4].1, p[5].0, p[5].1, p[6].0, p[6].1);
+            ctx.x = p[6].0;
+            ctx.y = p[6].1;
+
+            // `pop pop setcurrentpoint` will set the final point.
+            ctx.push_ps(ctx.y)?;
+            ctx.push_ps(ctx.x)?;
+        }
+        _ => {
+            // Arguments are returned in the original order.
+            for i in (base..stack.len()).rev() {
+                ctx.push_ps(stack.at(i))?;
+            }
+        }
+    }
+
+    stack.len = base;
+    Ok(())
+}
+
+// Components of an accented character are separate glyphs,
+// so the path state must be reset for each of them.
+fn parse_seac_component(
+    ctx: &mut CharStringParserContext,
+    glyph_id: GlyphId,
+    origin_x: f32,
+    origin_y: f32,
+    stack: &mut ArgumentsStack,
+    depth: u8,
+    builder: &mut Builder,
+) -> Result<(), CFFError> {
+    let font = ctx.font;
+    let char_string = font.char_string(glyph_id).ok_or(CFFError::InvalidSeacCode)?;
+
+    ctx.x = origin_x;
+    ctx.y = origin_y;
+    ctx.origin_x = origin_x;
+    ctx.origin_y = origin_y;
+    ctx.is_path_open = false;
+    ctx.is_flex = false;
+    ctx.has_endchar = false;
+    stack.clear();
+
+    _parse_char_string(ctx, char_string, stack, depth, builder)?;
+
+    if !ctx.has_endchar {
+        return Err(CFFError::MissingEndChar);
+    }
+
+    Ok(())
+}
+
+// 'bchar' and 'achar' are Standard Encoding codes,
+// which are mapped to glyphs by name.
+fn seac_code_to_glyph_id(font: &Type1Font, n: f32) -> Option<GlyphId> {
+    let code = u8::try_from(i32::try_num_from(n)?).ok()?;
+    let sid = STANDARD_ENCODING[usize::from(code)];
+    let name = STANDARD_NAMES.get(usize::from(sid))?;
+    font.glyph_index_by_name(name)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fmt::Write;
+    use std::string::ToString;
+
+    struct Builder(String);
+    impl OutlineBuilder for Builder {
+        fn move_to(&mut self, x: f32, y: f32) {
+            write!(&mut self.0, "M {} {} ", x, y).unwrap();
+        }
+
+        fn line_to(&mut self, x: f32, y: f32) {
+            write!(&mut self.0, "L {} {} ", x, y).unwrap();
+        }
+
+        fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+            write!(&mut self.0, "Q {} {} {} {} ", x1, y1, x, y).unwrap();
+        }
+
+        fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+            write!(&mut self.0, "C {} {} {} {} {} {} ", x1, y1, x2, y2, x, y).unwrap();
+        }
+
+        fn close(&mut self) {
+            write!(&mut self.0, "Z ").unwrap();
+        }
+    }
+
+    // Converts a charstring from a text form.
+    fn char_string(text: &str) -> Vec<u8> {
+        let mut data = Vec::new();
+        for token in text.split_whitespace() {
+            if let Ok(n) = token.parse::<i32>() {
+                match n {
+                    -107..=107 => data.push((n + 139) as u8),
+                    108..=1131 => {
+                        let n = n - 108;
+                        data.push((247 + n / 256) as u8);
+                        data.push((n % 256) as u8);
+                    }
+                    -1131..=-108 => {
+                        let n = -n - 108;
+                        data.push((251 + n / 256) as u8);
+                        data.push((n % 256) as u8);
+                    }
+                    _ => {
+                        data.push(255);
+                        data.extend_from_slice(&n.to_be_bytes());
+                    }
+                }
+
+                continue;
+            }
+
+            let op = match token {
+                "hstem" => operator::HORIZONTAL_STEM,
+                "vstem" => operator::VERTICAL_STEM,
+                "vmoveto" => operator::VERTICAL_MOVE_TO,
+                "rlineto" => operator::LINE_TO,
+                "hlineto" => operator::HORIZONTAL_LINE_TO,
+                "vlineto" => operator::VERTICAL_LINE_TO,
+                "rrcurveto" => operator::CURVE_TO,
+                "closepath" => operator::CLOSE_PATH,
+                "callsubr" => operator::CALL_SUBROUTINE,
+                "return" => operator::RETURN,
+                "hsbw" => operator::HORIZONTAL_SIDE_BEARING_WIDTH,
+                "endchar" => operator::ENDCHAR,
+                "rmoveto" => operator::MOVE_TO,
+                "hmoveto" => operator::HORIZONTAL_MOVE_TO,
+                "vhcurveto" => operator::VH_CURVE_TO,
+                "hvcurveto" => operator::HV_CURVE_TO,
+                _ => {
+                    let op2 = match token {
+                        "dotsection" => operator::DOT_SECTION,
+                        "seac" => operator::SEAC,
+                        "sbw" => operator::SIDE_BEARING_WIDTH,
+                        "div" => operator::DIV,
+                        "callothersubr" => operator::CALL_OTHER_SUBROUTINE,
+                        "pop" => operator::POP,
+                        "setcurrentpoint" => operator::SET_CURRENT_POINT,
+                        _ => panic!("unknown operator: {}", token),
+                    };
+
+                    data.push(operator::TWO_BYTE_OPERATOR_MARK);
+                    op2
+                }
+            };
+
+            data.push(op);
+        }
+
+        data
+    }
+
+    fn encrypt(data: &[u8], key: u16) -> Vec<u8> {
+        let mut encrypted = Vec::new();
+        let mut r = key;
+        // Prepend 4 random bytes.
+        for p in [0x12, 0x34, 0x56, 0x78].iter().chain(data) {
+            let c = p ^ (r >> 8) as u8;
+            r = u16::from(c).wrapping_add(r).wrapping_mul(52845).wrapping_add(22719);
+            encrypted.push(c);
+        }
+
+        encrypted
+    }
+
+    fn push_binary(w: &mut Vec<u8>, prefix: &str, char_string_text: &str, suffix: &str) {
+        let data = encrypt(&char_string(char_string_text), CHAR_STRING_KEY);
+        w.extend_from_slice(format!("{} {} RD ", prefix, data.len()).as_bytes());
+        w.extend_from_slice(&data);
+        w.extend_from_slice(suffix.as_bytes());
+    }
+
+    // Returns a cleartext and a not yet encrypted private portion.
+    fn gen_font() -> (Vec<u8>, Vec<u8>) {
+        gen_font_with_subrs_count(2)
+    }
+
+    // Only two subroutines are defined, no matter the declared count.
+    fn gen_font_with_subrs_count(subrs_count: u32) -> (Vec<u8>, Vec<u8>) {
+        let cleartext = "\
+%!PS-AdobeFont-1.0: Test 001
+%%Title: Test
+/FontName /Test-Regular def
+/FontMatrix [0.001 0 0 0.001 0 0] readonly def
+/FontInfo 2 dict dup begin
+/Notice (Copyright (c) Test. /FontName /Wrong) readonly def
+end readonly def
+/Encoding 256 array
+0 1 255 {1 index exch /.notdef put} for
+dup 65 /A put
+dup 66 /B put
+dup 97 /a put
+readonly def
+currentfile eexec
+";
+
+        let mut private = Vec::new();
+        private.extend_from_slice(b"\
+dup /Private 8 dict dup begin
+/RD{string currentfile exch readstring pop}executeonly def
+/ND{noaccess def}executeonly def
+/NP{noaccess put}executeonly def
+/BlueValues [-10 0 700 710] def
+/lenIV 4 def
+");
+        private.extend_from_slice(format!("/Subrs {} array\n", subrs_count).as_bytes());
+        push_binary(&mut private, "dup 0", "0 700 rlineto return", " NP\n");
+        push_binary(&mut private, "dup 1", "0 50 hstem return", " NP\n");
+        private.extend_from_slice(b"ND\n2 index /CharStrings 5 dict dup begin\n");
+        push_binary(&mut private, "/.notdef", "0 500 hsbw endchar", " ND\n");
+        push_binary(&mut private, "/A",
+                    "50 600 hsbw 0 0 rmoveto 500 hlineto 0 callsubr closepath endchar", " ND\n");
+        push_binary(&mut private, "/acute",
+                    "0 300 hsbw 0 800 rmoveto 100 100 rlineto -100 hlineto closepath endchar", " ND\n");
+        push_binary(&mut private, "/Aacute",
+                    "0 600 hsbw 0 200 0 65 194 seac", " ND\n");
+        push_binary(&mut private, "/B",
+                    "0 1000 2 div hsbw 1 1 3 callothersubr pop callsubr \
+                     0 0 rmoveto 100 0 rlineto \
+                     0 1 callothersubr \
+                     50 10 rmoveto 0 2 callothersubr \
+                     -30 0 rmoveto 0 2 callothersubr \
+                     20 10 rmoveto 0 2 callothersubr \
+                     10 0 rmoveto 0 2 callothersubr \
+                     10 0 rmoveto 0 2 callothersubr \
+                     20 -10 rmoveto 0 2 callothersubr \
+                     20 -10 rmoveto 0 2 callothersubr \
+                     50 200 0 3 0 callothersubr pop pop setcurrentpoint \
+                     100 vlineto closepath endchar", " ND\n");
+        private.extend_from_slice(b"end\nend\nreadonly put\nnoaccess put\ndup /FontName get exch definefont pop\nmark currentfile closefile\n");
+
+        (cleartext.as_bytes().to_vec(), private)
+    }
+
+    fn check_font(font: &Type1Font) {
+        assert_eq!(font.font_name(), Some("Test-Regular"));
+        assert_eq!(font.font_matrix(), FontMatrix::default());
+        assert_eq!(font.number_of_glyphs(), 5);
+        assert_eq!(font.glyph_name(GlyphId(3)), Some("Aacute"));
+        assert_eq!(font.glyph_name(GlyphId(5)), None);
+        assert_eq!(font.glyph_index_by_name("acute"), Some(GlyphId(2)));
+        assert_eq!(font.glyph_index_by_code(65), Some(GlyphId(1)));
+        assert_eq!(font.glyph_index_by_code(66), Some(GlyphId(4)));
+        // Not present in the font.
+        assert_eq!(font.glyph_index_by_code(97), None);
+        assert_eq!(font.glyph_index_by_code(67), None);
+
+        assert_eq!(font.glyph_hor_advance(GlyphId(0)), Some(500));
+        assert_eq!(font.glyph_hor_advance(GlyphId(3)), Some(600));
+
+        let mut builder = Builder(String::new());
+        let bbox = font.outline_glyph(GlyphId(1), &mut builder).unwrap();
+        assert_eq!(builder.0, "M 50 0 L 550 0 L 550 700 Z ");
+        assert_eq!(bbox, Rect { x_min: 50, y_min: 0, x_max: 550, y_max: 700 });
+
+        assert!(font.outline_glyph(GlyphId(0), &mut Builder(String::new())).is_none());
+    }
+
+    #[test]
+    fn missing_subrs() {
+        let (mut data, private) = gen_font_with_subrs_count(3);
+        data.extend_from_slice(&encrypt(&private, EEXEC_KEY));
+        check_font(&Type1Font::from_data(&data).unwrap());
+    }
+
+    #[test]
+    fn huge_subrs_count() {
+        let (mut data, private) = gen_font_with_subrs_count(2_000_000_000);
+        data.extend_from_slice(&encrypt(&private, EEXEC_KEY));
+        check_font(&Type1Font::from_data(&data).unwrap());
+    }
+
+    #[test]
+    fn pfa_binary() {
+        let (mut data, private) = gen_font();
+        data.extend_from_slice(&encrypt(&private, EEXEC_KEY));
+        check_font(&Type1Font::from_data(&data).unwrap());
+    }
+
+    #[test]
+    fn pfa_hex() {
+        let (mut data, private) = gen_font();
+        for (i, b) in encrypt(&private, EEXEC_KEY).iter().enumerate() {
+            data.extend_from_slice(format!("{:02x}", b).as_bytes());
+            if i % 32 == 31 {
+                data.push(b'\n');
+            }
+        }
+        data.extend_from_slice(b"\n0000000000000000\ncleartomark\n");
+
+        check_font(&Type1Font::from_data(&data).unwrap());
+    }
+
+    #[test]
+    fn pfb() {
+        fn segment(w: &mut Vec<u8>, kind: u8, data: &[u8]) {
+            w.push(PFB_MARKER);
+            w.push(kind);
+            w.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            w.extend_from_slice(data);
+        }
+
+        let (cleartext, private) = gen_font();
+        let mut data = Vec::new();
+        segment(&mut data, pfb_segment::ASCII, &cleartext);
+        segment(&mut data, pfb_segment::BINARY, &encrypt(&private, EEXEC_KEY));
+        segment(&mut data, pfb_segment::ASCII, b"0000000000000000\ncleartomark\n");
+        data.push(PFB_MARKER);
+        data.push(pfb_segment::EOF);
+
+        check_font(&Type1Font::from_data(&data).unwrap());
+
+        // Truncated.
+        assert!(Type1Font::from_data(&data[0..20]).is_none());
+    }
+
+    #[test]
+    fn seac() {
+        let (mut data, private) = gen_font();
+        data.extend_from_slice(&encrypt(&private, EEXEC_KEY));
+        let font = Type1Font::from_data(&data).unwrap();
+
+        let mut builder = Builder(String::new());
+        let bbox = font.outline_glyph(GlyphId(3), &mut builder).unwrap();
+        assert_eq!(builder.0, "M 50 0 L 550 0 L 550 700 Z M 200 800 L 300 900 L 200 900 Z ");
+        assert_eq!(bbox, Rect { x_min: 50, y_min: 0, x_max: 550, y_max: 900 });
+    }
+
+    #[test]
+    fn flex_and_hint_replacement() {
+        let (mut data, private) = gen_font();
+        data.extend_from_slice(&encrypt(&private, EEXEC_KEY));
+        let font = Type1Font::from_data(&data).unwrap();
+
+        let mut builder = Builder(String::new());
+        let bbox = font.outline_glyph(GlyphId(4), &mut builder).unwrap();
+        assert_eq!(builder.0, "M 0 0 L 100 0 C 120 10 140 20 150 20 C 160 20 180 10 200 0 L 200 100 Z ");
+        assert_eq!(bbox, Rect { x_min: 0, y_min: 0, x_max: 200, y_max: 100 });
+        assert_eq!(font.glyph_hor_advance(GlyphId(4)), Some(500));
+    }
+
+    #[test]
+    fn no_eexec() {
+        let (data, _) = gen_font();
+        let data = &data[0..data.len() - 7];
+        assert!(Type1Font::from_data(data).is_none());
+        assert!(Type1Font::from_data(&[]).is_none());
+    }
+
+    #[test]
+    fn decrypt_roundtrip() {
+        let data = b"dup /Private 8 dict";
+        assert_eq!(decrypt(&encrypt(data, EEXEC_KEY), EEXEC_KEY, 4), data.to_vec());
+        assert_eq!(decode_hex(b"0a Ff\n1\n0 x"), [0x0A, 0xFF, 0x10]);
+    }
+
+    #[test]
+    fn tokenizer() {
+        let mut t = Tokenizer::new(b"/A 12 (st(r)\\)) [ ] {} <0F>%comment\n<< >>/B");
+        let mut tokens = Vec::new();
+        while let Some(token) = t.next() {
+            tokens.push(std::str::from_utf8(token).unwrap().to_string());
+        }
+
+        assert_eq!(tokens, ["/A", "12", "(st(r)\\))", "[", "]", "{", "}", "<0F>", "<<", ">>", "/B"]);
+    }
+}
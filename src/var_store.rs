@@ -57,6 +57,11 @@ impl<'a> ItemVariationStore<'a> {
         Some(ItemVariationStore { data, data_offsets: offsets, regions })
     }
 
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.data_offsets.len() == 0
+    }
+
     pub fn region_indices(&self, index: u16) -> Option<LazyArray16<u16>> {
         // Offsets in bytes from the start of the item variation store
         // to each item variation data subtable.
@@ -108,3 +108,111 @@ impl Writer {
         convert_type(value, &mut self.data);
     }
 }
+
+/// Builds a TrueType font from a list of tables.
+///
+/// Checksums are not calculated.
+pub fn font(tables: &[(&[u8; 4], &[u8])]) -> Vec<u8> {
+    let mut data = convert(&[
+        TtfType::TrueTypeMagic,
+        TtfType::UInt16(tables.len() as u16), // numTables
+        TtfType::UInt16(0), // searchRange
+        TtfType::UInt16(0), // entrySelector
+        TtfType::UInt16(0), // rangeShift
+    ]);
+
+    let mut offset = data.len() + tables.len() * 16;
+    for (tag, table) in tables {
+        data.extend_from_slice(*tag);
+        data.extend_from_slice(&convert(&[
+            TtfType::UInt32(0), // checksum
+            TtfType::UInt32(offset as u32), // offset
+            TtfType::UInt32(table.len() as u32), // length
+        ]));
+        offset += table.len();
+    }
+
+    for (_, table) in tables {
+        data.extend_from_slice(table);
+    }
+
+    data
+}
+
+/// Builds a `head` table.
+pub fn head(units_per_em: u16, index_to_loc_format: i16) -> Vec<u8> {
+    convert(&[
+        TtfType::UInt16(1), TtfType::UInt16(0), // version
+        TtfType::UInt32(0), // font revision
+        TtfType::UInt32(0), // checksum adjustment
+        TtfType::UInt32(0x5F0F3CF5), // magic number
+        TtfType::UInt16(0), // flags
+        TtfType::UInt16(units_per_em), // units per em
+        TtfType::UInt32(0), TtfType::UInt32(0), // created
+        TtfType::UInt32(0), TtfType::UInt32(0), // modified
+        TtfType::Int16(0), TtfType::Int16(0), TtfType::Int16(0), TtfType::Int16(0), // bbox
+        TtfType::UInt16(0), // mac style
+        TtfType::UInt16(0), // lowest rec ppem
+        TtfType::Int16(0), // font direction hint
+        TtfType::Int16(index_to_loc_format), // index to location format
+        TtfType::Int16(0), // glyph data format
+    ])
+}
+
+/// Builds a `hhea` table.
+pub fn hhea(ascender: i16, descender: i16, line_gap: i16, number_of_h_metrics: u16) -> Vec<u8> {
+    convert(&[
+        TtfType::UInt16(1), TtfType::UInt16(0), // version
+        TtfType::Int16(ascender),
+        TtfType::Int16(descender),
+        TtfType::Int16(line_gap),
+        TtfType::UInt16(0), // advance width max
+        TtfType::Int16(0), TtfType::Int16(0), TtfType::Int16(0), // min lsb, min rsb, x max extent
+        TtfType::Int16(1), TtfType::Int16(0), TtfType::Int16(0), // caret slope rise, run and offset
+        TtfType::Int16(0), TtfType::Int16(0), TtfType::Int16(0), TtfType::Int16(0), // reserved
+        TtfType::Int16(0), // metric data format
+        TtfType::UInt16(number_of_h_metrics),
+    ])
+}
+
+/// Builds a version 0.5 `maxp` table.
+pub fn maxp(number_of_glyphs: u16) -> Vec<u8> {
+    convert(&[
+        TtfType::UInt32(0x00005000), // version
+        TtfType::UInt16(number_of_glyphs),
+    ])
+}
+
+/// Builds an `OS/2` table of the specified version.
+///
+/// `typo` is ascender, descender and line gap. `win` is ascent and descent.
+/// Other fields are zeroed.
+pub fn os2(version: u16, fs_type: u16, fs_selection: u16, typo: (i16, i16, i16), win: (u16, u16)) -> Vec<u8> {
+    let len = match version {
+        0 => 78,
+        1 => 86,
+        2..=4 => 96,
+        _ => 100,
+    };
+
+    let mut data = convert(&[
+        TtfType::UInt16(version),
+        TtfType::Int16(0), // average char width
+        TtfType::UInt16(400), // weight class
+        TtfType::UInt16(5), // width class
+        TtfType::UInt16(fs_type),
+    ]);
+    data.resize(62, 0); // subscript, superscript, strikeout, family class, panose, unicode ranges, vendor id
+    data.extend_from_slice(&convert(&[
+        TtfType::UInt16(fs_selection),
+        TtfType::UInt16(0), // first char index
+        TtfType::UInt16(0), // last char index
+        TtfType::Int16(typo.0),
+        TtfType::Int16(typo.1),
+        TtfType::Int16(typo.2),
+        TtfType::UInt16(win.0),
+        TtfType::UInt16(win.1),
+    ]));
+    data.resize(len, 0);
+    data
+}